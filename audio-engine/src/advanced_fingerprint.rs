@@ -20,6 +20,8 @@ pub struct AdvancedFingerprint {
     pub hash_fingerprint: super::fingerprint::Fingerprint,
     /// MFCC features for spectral analysis
     pub mfcc_features: Vec<f32>,
+    /// Single-Gaussian timbre model fit over the per-frame MFCC vectors
+    pub timbre_model: MfccTimbreModel,
     /// Chroma features for harmonic analysis
     pub chroma_features: Vec<f32>,
     /// Rhythm features for tempo analysis
@@ -56,6 +58,8 @@ pub struct VocalCharacteristics {
     pub ornamentation_intensity: f32,
     /// Nasal resonance features
     pub nasal_resonance: f32,
+    /// Mean LPC-derived formant frequencies (F1, F2, F3, ...) averaged over voiced frames
+    pub formants: Vec<f32>,
 }
 
 /// Instrumental patterns in Indian music
@@ -82,13 +86,20 @@ pub struct RhythmicPatterns {
     pub taal_cycle: f32,
     /// Laya (tempo) variations
     pub laya_variations: Vec<f32>,
+    /// Tempo-robust rhythm-periodicity signature (diagonal-averaged self-similarity), unit-energy normalized
+    pub beat_spectrum: Vec<f32>,
 }
 
 /// Melodic characteristics (raga-like)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MelodicCharacteristics {
-    /// Scale/mode detection
+    /// Scale/mode detection, e.g. "C# minor"
     pub scale_type: String,
+    /// Pearson correlation against all 24 rotated Krumhansl-Schmuckler key
+    /// profiles (12 major tonics C..B, then 12 minor tonics C..B). Indian
+    /// ragas rarely fit a single major/minor label, so downstream raga
+    /// classification can use this full distribution instead of `scale_type` alone.
+    pub key_correlations: Vec<f32>,
     /// Melodic contour
     pub melodic_contour: Vec<f32>,
     /// Ornamentation patterns
@@ -97,6 +108,215 @@ pub struct MelodicCharacteristics {
     pub microtonal_features: Vec<f32>,
 }
 
+/// Compact timbral model: a single multivariate Gaussian fit to the
+/// per-frame MFCC vectors. Compared via symmetric KL-divergence, which is
+/// robust to frame count/ordering differences that trip up a flat cosine
+/// similarity over concatenated frames.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfccTimbreModel {
+    /// Mean MFCC vector (length `dimensions`)
+    pub mean: Vec<f32>,
+    /// Covariance matrix over the MFCC vectors, row-major (`dimensions` x `dimensions`)
+    pub covariance: Vec<f32>,
+    /// MFCC dimensionality (13 in this crate)
+    pub dimensions: usize,
+}
+
+impl MfccTimbreModel {
+    /// Fit a single Gaussian to a sequence of per-frame MFCC vectors, each of
+    /// length `dimensions`. Uses an identity covariance when there are fewer
+    /// than two frames, and a zero mean as well when there are no frames.
+    fn from_frames(frames: &[Vec<f32>], dimensions: usize) -> Self {
+        if frames.is_empty() {
+            return MfccTimbreModel {
+                mean: vec![0.0; dimensions],
+                covariance: identity_matrix(dimensions),
+                dimensions,
+            };
+        }
+
+        let n = frames.len() as f32;
+        let mut mean = vec![0.0f32; dimensions];
+        for frame in frames {
+            for d in 0..dimensions {
+                mean[d] += frame[d];
+            }
+        }
+        for m in mean.iter_mut() {
+            *m /= n;
+        }
+
+        let mut covariance = vec![0.0f32; dimensions * dimensions];
+        if frames.len() > 1 {
+            for frame in frames {
+                for i in 0..dimensions {
+                    let di = frame[i] - mean[i];
+                    for j in 0..dimensions {
+                        let dj = frame[j] - mean[j];
+                        covariance[i * dimensions + j] += di * dj;
+                    }
+                }
+            }
+            for c in covariance.iter_mut() {
+                *c /= n - 1.0;
+            }
+        } else {
+            covariance = identity_matrix(dimensions);
+        }
+
+        MfccTimbreModel { mean, covariance, dimensions }
+    }
+
+    /// Symmetric KL-divergence similarity in [0, 1]: KL(1‖2) + KL(2‖1) mapped
+    /// through exp(-beta * divergence) so identical models score 1.0 and
+    /// increasingly divergent timbres decay toward 0.
+    fn similarity(&self, other: &MfccTimbreModel) -> f32 {
+        const BETA: f32 = 0.02;
+        const EPSILON: f32 = 1e-3;
+
+        if self.dimensions != other.dimensions || self.dimensions == 0 {
+            return 0.0;
+        }
+
+        let divergence = symmetric_kl_divergence(self, other, EPSILON);
+        (-BETA * divergence).exp()
+    }
+}
+
+fn identity_matrix(dimensions: usize) -> Vec<f32> {
+    let mut m = vec![0.0f32; dimensions * dimensions];
+    for i in 0..dimensions {
+        m[i * dimensions + i] = 1.0;
+    }
+    m
+}
+
+/// KL(N(mu1,cov1) || N(mu2,cov2)) + KL(N(mu2,cov2) || N(mu1,cov1)), with both
+/// covariances regularized by adding `epsilon` to the diagonal before
+/// inversion so near-silent/short clips (near-singular covariance) stay
+/// well-defined.
+fn symmetric_kl_divergence(a: &MfccTimbreModel, b: &MfccTimbreModel, epsilon: f32) -> f32 {
+    let d = a.dimensions;
+    let cov_a = regularize_diagonal(&a.covariance, d, epsilon);
+    let cov_b = regularize_diagonal(&b.covariance, d, epsilon);
+
+    let (inv_a, log_det_a) = match invert_with_log_det(&cov_a, d) {
+        Some(result) => result,
+        None => return f32::INFINITY,
+    };
+    let (inv_b, log_det_b) = match invert_with_log_det(&cov_b, d) {
+        Some(result) => result,
+        None => return f32::INFINITY,
+    };
+
+    let mean_diff: Vec<f32> = a.mean.iter().zip(b.mean.iter()).map(|(x, y)| x - y).collect();
+
+    let kl_a_b = kl_divergence_term(&cov_a, &inv_b, &mean_diff, log_det_a, log_det_b, d);
+    let kl_b_a = kl_divergence_term(&cov_b, &inv_a, &mean_diff, log_det_b, log_det_a, d);
+
+    (kl_a_b + kl_b_a).max(0.0)
+}
+
+/// KL(N(mu1,cov1) || N(mu2,cov2)) given cov1, the inverse of cov2, the mean
+/// difference (mu2 - mu1 or mu1 - mu2, sign doesn't matter since it's squared
+/// through the quadratic form), and the log-determinants of cov1 and cov2.
+fn kl_divergence_term(
+    cov1: &[f32],
+    inv_cov2: &[f32],
+    mean_diff: &[f32],
+    log_det1: f32,
+    log_det2: f32,
+    d: usize,
+) -> f32 {
+    // tr(cov2^-1 * cov1)
+    let mut trace = 0.0f32;
+    for i in 0..d {
+        for k in 0..d {
+            trace += inv_cov2[i * d + k] * cov1[k * d + i];
+        }
+    }
+
+    // mean_diff^T * cov2^-1 * mean_diff
+    let mut quadratic = 0.0f32;
+    for i in 0..d {
+        let mut row_sum = 0.0f32;
+        for k in 0..d {
+            row_sum += inv_cov2[i * d + k] * mean_diff[k];
+        }
+        quadratic += mean_diff[i] * row_sum;
+    }
+
+    0.5 * (trace + quadratic - d as f32 + (log_det2 - log_det1))
+}
+
+fn regularize_diagonal(matrix: &[f32], dimensions: usize, epsilon: f32) -> Vec<f32> {
+    let mut result = matrix.to_vec();
+    for i in 0..dimensions {
+        result[i * dimensions + i] += epsilon;
+    }
+    result
+}
+
+/// Invert a square matrix via Gauss-Jordan elimination with partial pivoting,
+/// returning `(inverse, ln(det))`. Returns `None` if the matrix is singular.
+fn invert_with_log_det(matrix: &[f32], dimensions: usize) -> Option<(Vec<f32>, f32)> {
+    let d = dimensions;
+    let mut work = matrix.to_vec();
+    let mut inverse = identity_matrix(d);
+    let mut log_det = 0.0f32;
+    let mut sign = 1.0f32;
+
+    for col in 0..d {
+        // Partial pivot: find the row with the largest magnitude in this column
+        let mut pivot_row = col;
+        let mut pivot_val = work[col * d + col].abs();
+        for row in (col + 1)..d {
+            let val = work[row * d + col].abs();
+            if val > pivot_val {
+                pivot_val = val;
+                pivot_row = row;
+            }
+        }
+
+        if pivot_val < 1e-10 {
+            return None;
+        }
+
+        if pivot_row != col {
+            for k in 0..d {
+                work.swap(col * d + k, pivot_row * d + k);
+                inverse.swap(col * d + k, pivot_row * d + k);
+            }
+            sign = -sign;
+        }
+
+        let pivot = work[col * d + col];
+        log_det += pivot.abs().ln();
+
+        for k in 0..d {
+            work[col * d + k] /= pivot;
+            inverse[col * d + k] /= pivot;
+        }
+
+        for row in 0..d {
+            if row == col {
+                continue;
+            }
+            let factor = work[row * d + col];
+            if factor == 0.0 {
+                continue;
+            }
+            for k in 0..d {
+                work[row * d + k] -= factor * work[col * d + k];
+                inverse[row * d + k] -= factor * inverse[col * d + k];
+            }
+        }
+    }
+
+    let _ = sign; // determinant sign is irrelevant once we only need ln|det|
+    Some((inverse, log_det))
+}
+
 /// Multi-scale temporal features
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemporalFeatures {
@@ -116,17 +336,19 @@ impl AdvancedFingerprint {
         // Generate base fingerprint
         let hash_fingerprint = super::fingerprint::Fingerprint::generate(audio_data)?;
         
-        // Extract MFCC features
-        let mfcc_features = extract_mfcc_features(audio_data, sample_rate)?;
-        
+        // Extract MFCC features and fit a single-Gaussian timbre model over them
+        let mfcc_frames = extract_mfcc_frames(audio_data, sample_rate)?;
+        let mfcc_features: Vec<f32> = mfcc_frames.iter().flatten().cloned().collect();
+        let timbre_model = MfccTimbreModel::from_frames(&mfcc_frames, NUM_MFCC);
+
         // Extract chroma features
         let chroma_features = extract_chroma_features(audio_data, sample_rate)?;
-        
+
         // Extract rhythm features
         let rhythm_features = extract_rhythm_features(audio_data, sample_rate)?;
-        
+
         // Extract language-specific features
-        let language_features = extract_language_features(audio_data, sample_rate)?;
+        let language_features = extract_language_features(audio_data, sample_rate, &chroma_features)?;
         
         // Extract temporal features
         let temporal_features = extract_temporal_features(audio_data, sample_rate)?;
@@ -142,6 +364,7 @@ impl AdvancedFingerprint {
         Ok(AdvancedFingerprint {
             hash_fingerprint,
             mfcc_features,
+            timbre_model,
             chroma_features,
             rhythm_features,
             language_features,
@@ -152,49 +375,266 @@ impl AdvancedFingerprint {
     
     /// Calculate similarity with another advanced fingerprint
     pub fn similarity(&self, other: &AdvancedFingerprint) -> f32 {
+        self.similarity_with_timbre_mode(other, TimbreSimilarityMode::KlDivergence)
+    }
+
+    /// Calculate similarity with another advanced fingerprint, choosing how
+    /// the MFCC/timbre term is compared. `KlDivergence` (the default used by
+    /// `similarity`) models each track's per-frame MFCCs as a Gaussian and is
+    /// more robust to frame count/order than `Cosine`, which some
+    /// voice/instrument-discrimination use cases may still prefer.
+    pub fn similarity_with_timbre_mode(&self, other: &AdvancedFingerprint, mode: TimbreSimilarityMode) -> f32 {
         // Weighted combination of different similarity measures
         let hash_similarity = self.hash_fingerprint.similarity(&other.hash_fingerprint);
-        let mfcc_similarity = cosine_similarity(&self.mfcc_features, &other.mfcc_features);
+        let mfcc_similarity = match mode {
+            TimbreSimilarityMode::Cosine => cosine_similarity(&self.mfcc_features, &other.mfcc_features),
+            TimbreSimilarityMode::KlDivergence => self.timbre_model.similarity(&other.timbre_model),
+        };
         let chroma_similarity = cosine_similarity(&self.chroma_features, &other.chroma_features);
         let rhythm_similarity = cosine_similarity(&self.rhythm_features, &other.rhythm_features);
         let language_similarity = self.language_features.similarity(&other.language_features);
         let temporal_similarity = self.temporal_features.similarity(&other.temporal_features);
-        
+
         // Weighted combination (weights can be tuned)
         let weights = [0.3, 0.2, 0.15, 0.15, 0.1, 0.1];
-        let similarities = [hash_similarity, mfcc_similarity, chroma_similarity, 
+        let similarities = [hash_similarity, mfcc_similarity, chroma_similarity,
                           rhythm_similarity, language_similarity, temporal_similarity];
-        
+
         let weighted_similarity = similarities.iter()
             .zip(weights.iter())
             .map(|(sim, weight)| sim * weight)
             .sum::<f32>();
-        
+
         // Apply confidence weighting
         let confidence_factor = (self.confidence + other.confidence) / 2.0;
         weighted_similarity * confidence_factor
     }
+
+    /// Calculate similarity with another advanced fingerprint, blending
+    /// rhythm and timbre strength through `rhythm_weighting` (clamped to
+    /// `[0.0, 1.0]`: 0 = pure MFCC/timbre comparison, 1 = pure rhythm
+    /// comparison). The MFCC (0.2) and rhythm (0.15) weights from
+    /// `similarity_with_timbre_mode` are pooled and redistributed between the
+    /// two, so groove-first use cases (remix/cover detection) and
+    /// sound-first use cases (vocalist/instrument identification) can both
+    /// be served by the same fingerprint.
+    pub fn similarity_with_rhythm_weighting(&self, other: &AdvancedFingerprint, rhythm_weighting: f32) -> f32 {
+        const RHYTHM_TIMBRE_WEIGHT: f32 = 0.35;
+
+        let rhythm_weighting = rhythm_weighting.clamp(0.0, 1.0);
+
+        let hash_similarity = self.hash_fingerprint.similarity(&other.hash_fingerprint);
+        let mfcc_similarity = self.timbre_model.similarity(&other.timbre_model);
+        let rhythm_similarity = cosine_similarity(&self.rhythm_features, &other.rhythm_features);
+        let chroma_similarity = cosine_similarity(&self.chroma_features, &other.chroma_features);
+        let language_similarity = self.language_features.similarity(&other.language_features);
+        let temporal_similarity = self.temporal_features.similarity(&other.temporal_features);
+
+        let rhythm_timbre_similarity =
+            rhythm_weighting * rhythm_similarity + (1.0 - rhythm_weighting) * mfcc_similarity;
+
+        let weights = [0.3, RHYTHM_TIMBRE_WEIGHT, 0.15, 0.1, 0.1];
+        let similarities = [
+            hash_similarity,
+            rhythm_timbre_similarity,
+            chroma_similarity,
+            language_similarity,
+            temporal_similarity,
+        ];
+
+        let weighted_similarity = similarities.iter()
+            .zip(weights.iter())
+            .map(|(sim, weight)| sim * weight)
+            .sum::<f32>();
+
+        let confidence_factor = (self.confidence + other.confidence) / 2.0;
+        weighted_similarity * confidence_factor
+    }
 }
 
-/// Extract MFCC (Mel-Frequency Cepstral Coefficients) features
-fn extract_mfcc_features(audio_data: &[f32], sample_rate: u32) -> Result<Vec<f32>> {
+/// Selects how `AdvancedFingerprint::similarity_with_timbre_mode` compares the
+/// MFCC/timbre term between two fingerprints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimbreSimilarityMode {
+    /// Flat cosine similarity over concatenated per-frame MFCC vectors.
+    Cosine,
+    /// Symmetric KL-divergence between single-Gaussian timbre models (see `MfccTimbreModel`).
+    KlDivergence,
+}
+
+/// A matched time span found by `AdvancedFingerprint::match_segments`: the
+/// query track's hash sequence aligned with the reference track's over
+/// `duration` seconds starting at `query_start`/`reference_start`, with a
+/// mean [0,1] `score` (1.0 = identical hashes throughout the span).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchedSegment {
+    /// Offset into the query track, in seconds
+    pub query_start: f32,
+    /// Offset into the reference track, in seconds
+    pub reference_start: f32,
+    /// Length of the matched span, in seconds
+    pub duration: f32,
+    /// Mean match score over the span, in [0, 1]
+    pub score: f32,
+}
+
+impl AdvancedFingerprint {
+    /// Find matching time spans between this track's hash fingerprint and
+    /// `other`'s, rather than collapsing the whole comparison to one average
+    /// score. Slides the query hash sequence against the reference across
+    /// every candidate offset, tracks runs of consecutive frames whose
+    /// bit-error rate stays at or below `maximum_difference`, and reports the
+    /// runs lasting at least `minimum_segment_duration` seconds. This is what
+    /// lets a short excerpt be recognized as matching the chorus of a full
+    /// track, and gives covers/samples per-region confidence instead of one
+    /// opaque number.
+    pub fn match_segments(
+        &self,
+        other: &AdvancedFingerprint,
+        minimum_segment_duration: f32,
+        maximum_difference: f32,
+    ) -> Vec<MatchedSegment> {
+        match_hash_segments(
+            &self.hash_fingerprint.hashes,
+            &self.hash_fingerprint.time_offsets,
+            &other.hash_fingerprint.hashes,
+            &other.hash_fingerprint.time_offsets,
+            minimum_segment_duration,
+            maximum_difference,
+        )
+    }
+}
+
+fn bit_difference_ratio(a: u64, b: u64) -> f32 {
+    (a ^ b).count_ones() as f32 / u64::BITS as f32
+}
+
+fn match_hash_segments(
+    query_hashes: &[u64],
+    query_offsets: &[f32],
+    reference_hashes: &[u64],
+    reference_offsets: &[f32],
+    minimum_segment_duration: f32,
+    maximum_difference: f32,
+) -> Vec<MatchedSegment> {
+    if query_hashes.is_empty() || reference_hashes.is_empty() {
+        return Vec::new();
+    }
+
+    let query_len = query_hashes.len() as isize;
+    let reference_len = reference_hashes.len() as isize;
+    let mut segments = Vec::new();
+
+    for shift in -(query_len - 1)..reference_len {
+        let mut run_start: Option<usize> = None;
+        let mut run_diffs: Vec<f32> = Vec::new();
+
+        for query_idx in 0..query_hashes.len() {
+            let reference_idx = query_idx as isize + shift;
+            let diff = if reference_idx >= 0 && (reference_idx as usize) < reference_hashes.len() {
+                Some(bit_difference_ratio(query_hashes[query_idx], reference_hashes[reference_idx as usize]))
+            } else {
+                None
+            };
+
+            match diff {
+                Some(d) if d <= maximum_difference => {
+                    if run_start.is_none() {
+                        run_start = Some(query_idx);
+                    }
+                    run_diffs.push(d);
+                }
+                _ => {
+                    if let Some(start) = run_start.take() {
+                        try_emit_segment(
+                            start, query_idx, shift, &run_diffs,
+                            query_offsets, reference_offsets,
+                            minimum_segment_duration, maximum_difference,
+                            &mut segments,
+                        );
+                    }
+                    run_diffs.clear();
+                }
+            }
+        }
+
+        if let Some(start) = run_start.take() {
+            try_emit_segment(
+                start, query_hashes.len(), shift, &run_diffs,
+                query_offsets, reference_offsets,
+                minimum_segment_duration, maximum_difference,
+                &mut segments,
+            );
+        }
+    }
+
+    segments
+}
+
+#[allow(clippy::too_many_arguments)]
+fn try_emit_segment(
+    start: usize,
+    end_exclusive: usize,
+    shift: isize,
+    run_diffs: &[f32],
+    query_offsets: &[f32],
+    reference_offsets: &[f32],
+    minimum_segment_duration: f32,
+    maximum_difference: f32,
+    segments: &mut Vec<MatchedSegment>,
+) {
+    if run_diffs.is_empty() {
+        return;
+    }
+
+    let mean_diff = run_diffs.iter().sum::<f32>() / run_diffs.len() as f32;
+    if mean_diff > maximum_difference {
+        return;
+    }
+
+    let start_time = query_offsets.get(start).copied().unwrap_or(0.0);
+    let end_time = query_offsets.get(end_exclusive.saturating_sub(1)).copied().unwrap_or(start_time);
+    let duration = (end_time - start_time).max(0.0);
+    if duration < minimum_segment_duration {
+        return;
+    }
+
+    let reference_idx = (start as isize + shift).max(0) as usize;
+    segments.push(MatchedSegment {
+        query_start: start_time,
+        reference_start: reference_offsets.get(reference_idx).copied().unwrap_or(0.0),
+        duration,
+        score: (1.0 - mean_diff).max(0.0),
+    });
+}
+
+/// Number of MFCC coefficients retained per frame
+const NUM_MFCC: usize = 13;
+
+/// Extract per-frame MFCC (Mel-Frequency Cepstral Coefficients) vectors
+fn extract_mfcc_frames(audio_data: &[f32], sample_rate: u32) -> Result<Vec<Vec<f32>>> {
     let window_size = 1024;
     let hop_size = 512;
-    let num_mfcc = 13;
-    
+
     // Compute spectrogram
     let spectrogram = compute_spectrogram(audio_data, window_size, hop_size, sample_rate)?;
-    
+
     // Apply mel filter bank
     let mel_filters = create_mel_filter_bank(sample_rate, window_size, 26);
     let mel_spectrogram = apply_mel_filters(&spectrogram, &mel_filters);
-    
+
     // Apply log and DCT to get MFCC
     let log_mel = mel_spectrogram.mapv(|x| (x + 1e-10).ln());
-    let mfcc = apply_dct(&log_mel, num_mfcc);
-    
-    // Flatten and return
-    Ok(mfcc.iter().cloned().collect())
+    let mfcc = apply_dct(&log_mel, NUM_MFCC);
+
+    // Split the flattened per-frame coefficients back into individual frames
+    let num_frames = mel_spectrogram.ncols();
+    let mut frames = Vec::with_capacity(num_frames);
+    for frame_idx in 0..num_frames {
+        let start = frame_idx * NUM_MFCC;
+        frames.push(mfcc.slice(ndarray::s![start..start + NUM_MFCC]).to_vec());
+    }
+    Ok(frames)
 }
 
 /// Extract chroma features for harmonic analysis
@@ -235,14 +675,12 @@ fn extract_rhythm_features(audio_data: &[f32], sample_rate: u32) -> Result<Vec<f
     
     // Compute spectrogram
     let spectrogram = compute_spectrogram(audio_data, window_size, hop_size, sample_rate)?;
-    
-    // Focus on percussion frequencies (typically 80-200 Hz)
-    let percussion_bins = get_percussion_bins(sample_rate, window_size);
-    let percussion_spectrum = spectrogram.select(Axis(0), &percussion_bins);
-    
-    // Calculate onset strength
-    let onset_strength = calculate_onset_strength(&percussion_spectrum);
-    
+
+    // Calculate onset strength via half-wave-rectified spectral flux over the
+    // full spectrum, which catches sharp percussive attacks that a
+    // percussion-band energy envelope smooths away
+    let onset_strength = calculate_onset_strength(&spectrogram);
+
     // Estimate tempo using autocorrelation
     let tempo = estimate_tempo(&onset_strength, sample_rate, hop_size);
     
@@ -256,19 +694,19 @@ fn extract_rhythm_features(audio_data: &[f32], sample_rate: u32) -> Result<Vec<f
 }
 
 /// Extract language-specific features for Hindi/Bhojpuri music
-fn extract_language_features(audio_data: &[f32], sample_rate: u32) -> Result<LanguageFeatures> {
+fn extract_language_features(audio_data: &[f32], sample_rate: u32, chroma_features: &[f32]) -> Result<LanguageFeatures> {
     // Extract vocal characteristics
     let vocal_characteristics = extract_vocal_characteristics(audio_data, sample_rate)?;
-    
+
     // Extract instrumental patterns
     let instrumental_patterns = extract_instrumental_patterns(audio_data, sample_rate)?;
-    
+
     // Extract rhythmic patterns
     let rhythmic_patterns = extract_rhythmic_patterns_detailed(audio_data, sample_rate)?;
-    
+
     // Extract melodic characteristics
-    let melodic_characteristics = extract_melodic_characteristics(audio_data, sample_rate)?;
-    
+    let melodic_characteristics = extract_melodic_characteristics(audio_data, sample_rate, chroma_features)?;
+
     Ok(LanguageFeatures {
         vocal_characteristics,
         instrumental_patterns,
@@ -279,29 +717,36 @@ fn extract_language_features(audio_data: &[f32], sample_rate: u32) -> Result<Lan
 
 /// Extract vocal characteristics
 fn extract_vocal_characteristics(audio_data: &[f32], sample_rate: u32) -> Result<VocalCharacteristics> {
-    // Estimate pitch using autocorrelation
+    // Estimate pitch using the YIN normalized-difference method; unvoiced frames come
+    // back as 0.0 and are excluded below so silence/noise doesn't skew the range
     let pitch_contour = estimate_pitch_contour(audio_data, sample_rate);
-    
+    let voiced_contour: Vec<f32> = pitch_contour.iter().cloned().filter(|&p| p > 0.0).collect();
+
     // Calculate pitch range
-    let pitch_range = (
-        pitch_contour.iter().cloned().fold(f32::INFINITY, f32::min),
-        pitch_contour.iter().cloned().fold(f32::NEG_INFINITY, f32::max),
-    );
-    
+    let pitch_range = if voiced_contour.is_empty() {
+        (0.0, 0.0)
+    } else {
+        (
+            voiced_contour.iter().cloned().fold(f32::INFINITY, f32::min),
+            voiced_contour.iter().cloned().fold(f32::NEG_INFINITY, f32::max),
+        )
+    };
+
     // Estimate vibrato frequency
-    let vibrato_frequency = estimate_vibrato_frequency(&pitch_contour);
-    
+    let vibrato_frequency = estimate_vibrato_frequency(&voiced_contour);
+
     // Calculate ornamentation intensity
-    let ornamentation_intensity = calculate_ornamentation_intensity(&pitch_contour);
-    
-    // Estimate nasal resonance
-    let nasal_resonance = estimate_nasal_resonance(audio_data, sample_rate);
-    
+    let ornamentation_intensity = calculate_ornamentation_intensity(&voiced_contour);
+
+    // Estimate nasal resonance and formant tracks via LPC
+    let (nasal_resonance, formants) = estimate_nasal_resonance(audio_data, sample_rate);
+
     Ok(VocalCharacteristics {
         pitch_range,
         vibrato_frequency,
         ornamentation_intensity,
         nasal_resonance,
+        formants,
     })
 }
 
@@ -346,31 +791,36 @@ fn extract_rhythmic_patterns_detailed(audio_data: &[f32], sample_rate: u32) -> R
     
     // Extract laya variations
     let laya_variations = extract_laya_variations(audio_data, sample_rate);
-    
+
+    // Tempo-robust rhythm-periodicity signature via diagonal-averaged self-similarity
+    let beat_spectrum = compute_beat_spectrum(audio_data, sample_rate);
+
     Ok(RhythmicPatterns {
         primary_tempo,
         secondary_tempo,
         taal_cycle,
         laya_variations,
+        beat_spectrum,
     })
 }
 
 /// Extract melodic characteristics
-fn extract_melodic_characteristics(audio_data: &[f32], sample_rate: u32) -> Result<MelodicCharacteristics> {
-    // Detect scale/mode
-    let scale_type = detect_scale_type(audio_data, sample_rate);
-    
+fn extract_melodic_characteristics(audio_data: &[f32], sample_rate: u32, chroma_features: &[f32]) -> Result<MelodicCharacteristics> {
+    // Detect scale/mode via Krumhansl-Schmuckler key-profile correlation
+    let (scale_type, key_correlations) = detect_scale_type(chroma_features);
+
     // Extract melodic contour
     let melodic_contour = extract_melodic_contour(audio_data, sample_rate);
-    
+
     // Detect ornamentation patterns
     let ornamentation_patterns = detect_ornamentation_patterns(audio_data, sample_rate);
-    
+
     // Extract microtonal features
     let microtonal_features = extract_microtonal_features(audio_data, sample_rate);
-    
+
     Ok(MelodicCharacteristics {
         scale_type,
+        key_correlations,
         melodic_contour,
         ornamentation_patterns,
         microtonal_features,
@@ -531,17 +981,115 @@ fn get_percussion_bins(sample_rate: u32, window_size: usize) -> Vec<usize> {
     (low_bin..=high_bin).collect()
 }
 
-fn calculate_onset_strength(percussion_spectrum: &Array2<f32>) -> Array1<f32> {
-    let num_frames = percussion_spectrum.ncols();
-    let mut onset_strength = Array1::zeros(num_frames);
-    
-    for frame_idx in 0..num_frames {
-        let frame = percussion_spectrum.column(frame_idx);
-        let energy = frame.iter().map(|&x| x * x).sum::<f32>();
-        onset_strength[frame_idx] = energy;
+/// Half-wave-rectified spectral flux onset detection over the full magnitude
+/// spectrogram: flux[t] = sum_k max(0, log(1+lambda|X[t,k]|) - log(1+lambda|X[t-1,k]|)),
+/// followed by adaptive-median thresholding (subtracting the running median
+/// over a ~±100ms window) so only sharp local attacks survive. This catches
+/// percussive onsets (e.g. tabla/dholak strikes) that a plain energy envelope
+/// smears out, which in turn sharpens the autocorrelation-based tempo estimate.
+fn calculate_onset_strength(spectrogram: &Array2<f32>) -> Array1<f32> {
+    const LOG_COMPRESSION_LAMBDA: f32 = 1.0;
+    const MEDIAN_WINDOW_SECONDS: f32 = 0.1; // ~100ms on each side
+    const FRAME_RATE_HZ: f32 = 44100.0 / 256.0; // matches extract_rhythm_features' window/hop
+
+    let num_frames = spectrogram.ncols();
+    let mut flux = Array1::zeros(num_frames);
+
+    let compressed = spectrogram.mapv(|x| (1.0 + LOG_COMPRESSION_LAMBDA * x).ln());
+
+    for frame_idx in 1..num_frames {
+        let prev = compressed.column(frame_idx - 1);
+        let curr = compressed.column(frame_idx);
+        let sum: f32 = curr
+            .iter()
+            .zip(prev.iter())
+            .map(|(&c, &p)| (c - p).max(0.0))
+            .sum();
+        flux[frame_idx] = sum;
     }
-    
-    onset_strength
+
+    let median_window = (MEDIAN_WINDOW_SECONDS * FRAME_RATE_HZ).round() as usize;
+    adaptive_median_threshold(&flux, median_window)
+}
+
+/// Subtract the running median over a `±half_window` frame neighborhood from
+/// each value, then clamp negatives to zero so only values that stand out
+/// above their local baseline remain.
+fn adaptive_median_threshold(values: &Array1<f32>, half_window: usize) -> Array1<f32> {
+    let n = values.len();
+    let mut thresholded = Array1::zeros(n);
+
+    for i in 0..n {
+        let start = i.saturating_sub(half_window);
+        let end = (i + half_window + 1).min(n);
+        let mut window: Vec<f32> = values.slice(ndarray::s![start..end]).to_vec();
+        window.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let median = window[window.len() / 2];
+        thresholded[i] = (values[i] - median).max(0.0);
+    }
+
+    thresholded
+}
+
+/// Onset-strength envelope shared by tempo and laya-variation analysis: a
+/// half-wave-rectified spectral-flux curve at the same ~5.8ms hop (1024-sample
+/// window, 256-sample hop at 44.1 kHz) `extract_rhythm_features` uses. Errors
+/// when the audio is shorter than one window.
+fn compute_onset_envelope(audio_data: &[f32], sample_rate: u32) -> Result<(Array1<f32>, f32)> {
+    const WINDOW_SIZE: usize = 1024;
+    const HOP_SIZE: usize = 256;
+
+    if audio_data.len() < WINDOW_SIZE {
+        return Err(anyhow::anyhow!("audio shorter than one onset analysis window"));
+    }
+
+    let spectrogram = compute_spectrogram(audio_data, WINDOW_SIZE, HOP_SIZE, sample_rate)?;
+    let onset_strength = calculate_onset_strength(&spectrogram);
+    let frame_rate = sample_rate as f32 / HOP_SIZE as f32;
+    Ok((onset_strength, frame_rate))
+}
+
+/// Autocorrelation of the onset-strength envelope over lags spanning roughly
+/// 0-4 seconds, normalized by overlap length. This self-similarity curve
+/// characterizes periodicity/tempo independent of instrumentation; its peaks
+/// are read off by `estimate_primary_tempo`/`estimate_secondary_tempo`, and
+/// the curve itself (L2-normalized) becomes `laya_variations`.
+fn onset_autocorrelation(onset_strength: &Array1<f32>, frame_rate: f32) -> Vec<f32> {
+    const MAX_LAG_SECONDS: f32 = 4.0;
+
+    let n = onset_strength.len();
+    if n < 2 {
+        return Vec::new();
+    }
+    let max_lag = ((MAX_LAG_SECONDS * frame_rate) as usize).min(n - 1).max(1);
+
+    (0..=max_lag)
+        .map(|lag| {
+            let overlap = n - lag;
+            onset_strength
+                .iter()
+                .take(overlap)
+                .zip(onset_strength.iter().skip(lag))
+                .map(|(a, b)| a * b)
+                .sum::<f32>()
+                / overlap as f32
+        })
+        .collect()
+}
+
+/// Local-maximum lags of an autocorrelation curve within `[min_lag, max_lag]`,
+/// sorted strongest-first.
+fn autocorrelation_peaks(curve: &[f32], min_lag: usize, max_lag: usize) -> Vec<usize> {
+    let max_lag = max_lag.min(curve.len().saturating_sub(1));
+    if min_lag.max(1) >= max_lag {
+        return Vec::new();
+    }
+
+    let mut peaks: Vec<usize> = (min_lag.max(1)..max_lag)
+        .filter(|&lag| curve[lag] > curve[lag - 1] && curve[lag] >= curve[lag + 1])
+        .collect();
+    peaks.sort_by(|&a, &b| curve[b].partial_cmp(&curve[a]).unwrap_or(std::cmp::Ordering::Equal));
+    peaks
 }
 
 fn estimate_tempo(onset_strength: &Array1<f32>, sample_rate: u32, hop_size: usize) -> f32 {
@@ -599,83 +1147,664 @@ fn extract_rhythmic_patterns(onset_strength: &Array1<f32>, tempo: f32) -> Vec<f3
     patterns
 }
 
-// Placeholder implementations for complex feature extraction
-fn estimate_pitch_contour(audio_data: &[f32], sample_rate: u32) -> Vec<f32> {
-    // Simplified pitch estimation
-    vec![440.0; audio_data.len() / 1024]
-}
+/// Build a tempo-robust rhythm-periodicity signature (a "beat spectrum", after
+/// Foote & Cooper): take the loudest ~4s clip, compute its per-frame MFCC
+/// sequence, form the frame-to-frame self-similarity matrix via cosine
+/// similarity, and average along each diagonal to get b[lag] = mean over i of
+/// S[i, i+lag]. Unlike a single BPM estimate, this captures the whole taal
+/// periodicity structure and survives small tempo drift between recordings.
+fn compute_beat_spectrum(audio_data: &[f32], sample_rate: u32) -> Vec<f32> {
+    const HOP_SIZE: usize = 512;
+    const CLIP_SECONDS: f32 = 4.0;
+    const MAX_LAG_SECONDS: f32 = 2.0;
 
-fn estimate_vibrato_frequency(pitch_contour: &[f32]) -> f32 {
-    // Simplified vibrato estimation
-    5.0
-}
+    let clip = loudest_clip(audio_data, sample_rate, CLIP_SECONDS);
+    let frames = match extract_mfcc_frames(&clip, sample_rate) {
+        Ok(frames) if frames.len() > 1 => frames,
+        _ => return Vec::new(),
+    };
 
-fn calculate_ornamentation_intensity(pitch_contour: &[f32]) -> f32 {
-    // Calculate pitch variation as ornamentation measure
-    if pitch_contour.len() < 2 {
-        return 0.0;
+    let frame_rate = sample_rate as f32 / HOP_SIZE as f32;
+    let max_lag = ((MAX_LAG_SECONDS * frame_rate) as usize)
+        .min(frames.len() - 1)
+        .max(1);
+
+    let mut beat_spectrum = vec![0.0f32; max_lag + 1];
+    for (lag, entry) in beat_spectrum.iter_mut().enumerate() {
+        let mut sum = 0.0f32;
+        let mut count = 0usize;
+        for i in 0..frames.len() - lag {
+            sum += cosine_similarity(&frames[i], &frames[i + lag]);
+            count += 1;
+        }
+        *entry = if count > 0 { sum / count as f32 } else { 0.0 };
     }
-    
-    let mean_pitch = pitch_contour.iter().sum::<f32>() / pitch_contour.len() as f32;
-    let variance = pitch_contour.iter()
-        .map(|&p| (p - mean_pitch).powi(2))
-        .sum::<f32>() / pitch_contour.len() as f32;
-    
-    variance.sqrt() / mean_pitch
-}
 
-fn estimate_nasal_resonance(audio_data: &[f32], sample_rate: u32) -> f32 {
-    // Simplified nasal resonance estimation
-    0.5
+    l2_normalize(&mut beat_spectrum);
+    beat_spectrum
 }
 
-fn detect_tabla_patterns(spectrogram: &Array2<f32>, sample_rate: u32, window_size: usize) -> Vec<f32> {
-    // Simplified tabla pattern detection
-    vec![0.5; 10]
-}
+/// Return the `clip_seconds`-long window of `audio_data` with the highest RMS
+/// energy, used so the beat spectrum is built from the most rhythmically
+/// active part of the track rather than an intro/outro fade.
+fn loudest_clip(audio_data: &[f32], sample_rate: u32, clip_seconds: f32) -> Vec<f32> {
+    let clip_len = ((sample_rate as f32 * clip_seconds) as usize).min(audio_data.len());
+    if clip_len == 0 || audio_data.len() <= clip_len {
+        return audio_data.to_vec();
+    }
 
-fn detect_harmonium_features(spectrogram: &Array2<f32>, sample_rate: u32, window_size: usize) -> Vec<f32> {
-    // Simplified harmonium feature detection
-    vec![0.3; 8]
-}
+    const BLOCK_SECONDS: f32 = 0.5;
+    let block_len = ((sample_rate as f32 * BLOCK_SECONDS) as usize).max(1);
+    let block_energy: Vec<f32> = audio_data
+        .chunks(block_len)
+        .map(|block| block.iter().map(|&s| s * s).sum::<f32>())
+        .collect();
 
-fn detect_string_features(spectrogram: &Array2<f32>, sample_rate: u32, window_size: usize) -> Vec<f32> {
-    // Simplified string instrument detection
-    vec![0.4; 6]
-}
+    let blocks_per_clip = (clip_len / block_len).max(1);
+    let mut best_start_block = 0;
+    let mut best_energy = f32::NEG_INFINITY;
+    for start_block in 0..block_energy.len() {
+        if start_block + blocks_per_clip > block_energy.len() {
+            break;
+        }
+        let energy: f32 = block_energy[start_block..start_block + blocks_per_clip].iter().sum();
+        if energy > best_energy {
+            best_energy = energy;
+            best_start_block = start_block;
+        }
+    }
 
-fn calculate_percussion_intensity(spectrogram: &Array2<f32>, sample_rate: u32, window_size: usize) -> f32 {
-    // Calculate overall percussion intensity
-    let percussion_bins = get_percussion_bins(sample_rate, window_size);
-    let percussion_energy = spectrogram.select(Axis(0), &percussion_bins).sum();
-    let total_energy = spectrogram.sum();
-    
-    percussion_energy / total_energy
+    let start_sample = (best_start_block * block_len).min(audio_data.len() - clip_len);
+    audio_data[start_sample..start_sample + clip_len].to_vec()
 }
 
-fn estimate_primary_tempo(audio_data: &[f32], sample_rate: u32) -> f32 {
-    // Simplified primary tempo estimation
-    120.0
+fn l2_normalize(values: &mut [f32]) {
+    let norm = values.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for v in values.iter_mut() {
+            *v /= norm;
+        }
+    }
 }
 
-fn estimate_secondary_tempo(audio_data: &[f32], sample_rate: u32) -> Option<f32> {
-    // Check for secondary tempo (polyrhythmic patterns)
-    None
-}
+// Placeholder implementations for complex feature extraction
+/// Estimate a per-frame fundamental frequency contour using the YIN
+/// normalized-difference method, restricted to the vocal range (~80-1000 Hz).
+/// Unvoiced or silent frames are reported as `0.0`.
+fn estimate_pitch_contour(audio_data: &[f32], sample_rate: u32) -> Vec<f32> {
+    const FRAME_SIZE: usize = 2048;
+    const HOP_SIZE: usize = 512;
+    const MIN_FREQ: f32 = 80.0;
+    const MAX_FREQ: f32 = 1000.0;
+    const THRESHOLD: f32 = 0.1;
 
-fn estimate_taal_cycle(audio_data: &[f32], sample_rate: u32, tempo: f32) -> f32 {
-    // Estimate taal cycle length
-    16.0 // Common 16-beat cycle
+    if audio_data.len() < FRAME_SIZE || sample_rate == 0 {
+        return Vec::new();
+    }
+
+    let min_tau = ((sample_rate as f32 / MAX_FREQ).floor().max(2.0)) as usize;
+    let max_tau = ((sample_rate as f32 / MIN_FREQ).ceil() as usize).min(FRAME_SIZE / 2 - 1);
+
+    let mut contour = Vec::new();
+    let mut frame_start = 0;
+    while frame_start + FRAME_SIZE <= audio_data.len() {
+        let frame = &audio_data[frame_start..frame_start + FRAME_SIZE];
+        contour.push(yin_pitch_estimate(frame, sample_rate, min_tau, max_tau, THRESHOLD));
+        frame_start += HOP_SIZE;
+    }
+
+    contour
+}
+
+/// Estimate the fundamental frequency of a single frame via YIN: compute the
+/// difference function, normalize it into the cumulative mean normalized
+/// difference, pick the first dip below `threshold`, and refine the lag with
+/// parabolic interpolation. Returns `0.0` for frames with no clear periodicity.
+fn yin_pitch_estimate(frame: &[f32], sample_rate: u32, min_tau: usize, max_tau: usize, threshold: f32) -> f32 {
+    if max_tau <= min_tau || max_tau >= frame.len() {
+        return 0.0;
+    }
+
+    let mean = frame.iter().sum::<f32>() / frame.len() as f32;
+
+    let mut diff = vec![0.0f32; max_tau + 1];
+    for tau in 1..=max_tau {
+        let mut sum = 0.0;
+        for i in 0..frame.len() - tau {
+            let delta = (frame[i] - mean) - (frame[i + tau] - mean);
+            sum += delta * delta;
+        }
+        diff[tau] = sum;
+    }
+
+    let mut cmnd = vec![1.0f32; max_tau + 1];
+    let mut running_sum = 0.0;
+    for tau in 1..=max_tau {
+        running_sum += diff[tau];
+        cmnd[tau] = if running_sum > 0.0 {
+            diff[tau] * tau as f32 / running_sum
+        } else {
+            1.0
+        };
+    }
+
+    let mut chosen_tau = None;
+    for tau in min_tau..max_tau {
+        if cmnd[tau] < threshold && cmnd[tau] <= cmnd[tau - 1] && cmnd[tau] <= cmnd[tau + 1] {
+            chosen_tau = Some(tau);
+            break;
+        }
+    }
+
+    let tau = match chosen_tau {
+        Some(t) => t,
+        None => return 0.0,
+    };
+
+    let refined_tau = {
+        let (s0, s1, s2) = (cmnd[tau - 1], cmnd[tau], cmnd[tau + 1]);
+        let denom = s0 - 2.0 * s1 + s2;
+        if denom.abs() > 1e-10 {
+            tau as f32 + 0.5 * (s0 - s2) / denom
+        } else {
+            tau as f32
+        }
+    };
+
+    if refined_tau <= 0.0 {
+        0.0
+    } else {
+        sample_rate as f32 / refined_tau
+    }
+}
+
+/// Estimate vibrato rate (Hz) from a voiced pitch contour by locating the
+/// dominant modulation frequency in the detrended contour via autocorrelation.
+/// Vibrato typically falls in the 4-8 Hz range for sustained vocal notes.
+fn estimate_vibrato_frequency(pitch_contour: &[f32]) -> f32 {
+    const HOP_SIZE: usize = 512;
+    const CONTOUR_FRAME_RATE_HZ: f32 = 44100.0 / HOP_SIZE as f32;
+
+    if pitch_contour.len() < 8 {
+        return 0.0;
+    }
+
+    let mean = pitch_contour.iter().sum::<f32>() / pitch_contour.len() as f32;
+    let detrended: Vec<f32> = pitch_contour.iter().map(|&p| p - mean).collect();
+
+    let min_lag = (CONTOUR_FRAME_RATE_HZ / 8.0).floor().max(1.0) as usize;
+    let max_lag = ((CONTOUR_FRAME_RATE_HZ / 4.0).ceil() as usize).min(detrended.len() - 1);
+
+    if max_lag <= min_lag {
+        return 0.0;
+    }
+
+    let mut best_lag = 0;
+    let mut best_corr = 0.0f32;
+    for lag in min_lag..=max_lag {
+        let corr: f32 = (0..detrended.len() - lag)
+            .map(|i| detrended[i] * detrended[i + lag])
+            .sum();
+        if corr > best_corr {
+            best_corr = corr;
+            best_lag = lag;
+        }
+    }
+
+    if best_lag == 0 {
+        0.0
+    } else {
+        CONTOUR_FRAME_RATE_HZ / best_lag as f32
+    }
+}
+
+fn calculate_ornamentation_intensity(pitch_contour: &[f32]) -> f32 {
+    // Calculate pitch variation as ornamentation measure
+    if pitch_contour.len() < 2 {
+        return 0.0;
+    }
+    
+    let mean_pitch = pitch_contour.iter().sum::<f32>() / pitch_contour.len() as f32;
+    let variance = pitch_contour.iter()
+        .map(|&p| (p - mean_pitch).powi(2))
+        .sum::<f32>() / pitch_contour.len() as f32;
+    
+    variance.sqrt() / mean_pitch
+}
+
+/// LPC order used for formant estimation
+const LPC_ORDER: usize = 14;
+/// Number of formants averaged into `VocalCharacteristics::formants`
+const NUM_TRACKED_FORMANTS: usize = 3;
+
+/// Estimate nasal resonance and mean formant tracks via linear predictive
+/// coding: for each windowed voiced frame, fit an LPC filter (autocorrelation
+/// + Levinson-Durbin), find its formants from the roots of the LPC
+/// polynomial, and score nasality from a low F1 plus energy in the
+/// ~250-450 Hz/~1 kHz bands where nasalized vowels carry extra poles.
+/// Returns `(mean nasal score, mean formants)` averaged over voiced frames.
+fn estimate_nasal_resonance(audio_data: &[f32], sample_rate: u32) -> (f32, Vec<f32>) {
+    const FRAME_SIZE: usize = 2048;
+    const HOP_SIZE: usize = 1024;
+    const VOICING_RMS_THRESHOLD: f32 = 0.01;
+
+    if audio_data.len() < FRAME_SIZE {
+        return (0.0, Vec::new());
+    }
+
+    let mut nasal_scores = Vec::new();
+    let mut formant_sums = vec![0.0f32; NUM_TRACKED_FORMANTS];
+    let mut formant_counts = vec![0usize; NUM_TRACKED_FORMANTS];
+
+    let mut frame_start = 0;
+    while frame_start + FRAME_SIZE <= audio_data.len() {
+        let frame = &audio_data[frame_start..frame_start + FRAME_SIZE];
+        frame_start += HOP_SIZE;
+
+        let rms = (frame.iter().map(|&s| s * s).sum::<f32>() / frame.len() as f32).sqrt();
+        if rms < VOICING_RMS_THRESHOLD {
+            continue;
+        }
+
+        let windowed: Vec<f32> = frame
+            .iter()
+            .enumerate()
+            .map(|(i, &s)| {
+                let w = 0.54 - 0.46 * (2.0 * PI * i as f32 / (frame.len() - 1) as f32).cos();
+                s * w
+            })
+            .collect();
+
+        let autocorr = autocorrelation(&windowed, LPC_ORDER);
+        let lpc_coeffs = match levinson_durbin(&autocorr, LPC_ORDER) {
+            Some(coeffs) => coeffs,
+            None => continue,
+        };
+
+        let formants = estimate_formants(&lpc_coeffs, sample_rate);
+        if formants.is_empty() {
+            continue;
+        }
+
+        nasal_scores.push(nasal_resonance_score(&formants));
+        for (i, slot) in formant_sums.iter_mut().enumerate() {
+            if let Some(&f) = formants.get(i) {
+                *slot += f;
+                formant_counts[i] += 1;
+            }
+        }
+    }
+
+    if nasal_scores.is_empty() {
+        return (0.0, Vec::new());
+    }
+
+    let mean_nasal = nasal_scores.iter().sum::<f32>() / nasal_scores.len() as f32;
+    let mean_formants: Vec<f32> = formant_sums
+        .iter()
+        .zip(formant_counts.iter())
+        .filter(|(_, &count)| count > 0)
+        .map(|(&sum, &count)| sum / count as f32)
+        .collect();
+
+    (mean_nasal, mean_formants)
+}
+
+/// Biased autocorrelation r[0..=max_lag] of a signal, as used by Levinson-Durbin
+fn autocorrelation(frame: &[f32], max_lag: usize) -> Vec<f32> {
+    let n = frame.len();
+    (0..=max_lag)
+        .map(|lag| (0..n - lag).map(|i| frame[i] * frame[i + lag]).sum::<f32>())
+        .collect()
+}
+
+/// Solve the Toeplitz normal equations via Levinson-Durbin recursion to get
+/// LPC coefficients a[1..=order] (a[0] = 1 is implicit). Returns `None` if the
+/// signal is silent (zero energy).
+fn levinson_durbin(autocorr: &[f32], order: usize) -> Option<Vec<f32>> {
+    if autocorr[0].abs() < 1e-12 {
+        return None;
+    }
+
+    let mut error = autocorr[0];
+    let mut coeffs = vec![0.0f32; order + 1];
+    coeffs[0] = 1.0;
+
+    for i in 1..=order {
+        let mut acc = autocorr[i];
+        for j in 1..i {
+            acc += coeffs[j] * autocorr[i - j];
+        }
+        let reflection = -acc / error;
+
+        let mut updated = coeffs.clone();
+        for j in 1..i {
+            updated[j] = coeffs[j] + reflection * coeffs[i - j];
+        }
+        updated[i] = reflection;
+        coeffs = updated;
+
+        error *= 1.0 - reflection * reflection;
+        if error.abs() < 1e-12 {
+            break;
+        }
+    }
+
+    Some(coeffs[1..=order].to_vec())
+}
+
+/// Find formant frequencies from the roots of the LPC polynomial
+/// A(z) = 1 + a1 z^-1 + ... + ap z^-p, equivalently the roots of
+/// z^p + a1 z^(p-1) + ... + ap, found via the Durand-Kerner method. Keeps
+/// only roots in the upper half-plane (each formant's conjugate pair) whose
+/// bandwidth is tight enough to represent a genuine resonance, sorted by frequency.
+fn estimate_formants(lpc_coeffs: &[f32], sample_rate: u32) -> Vec<f32> {
+    const MAX_BANDWIDTH_HZ: f32 = 400.0;
+    const MIN_FORMANT_HZ: f32 = 90.0;
+    const MAX_FORMANT_HZ: f32 = 5000.0;
+
+    let mut poly_coeffs = vec![1.0f32];
+    poly_coeffs.extend_from_slice(lpc_coeffs);
+
+    let roots = find_polynomial_roots(&poly_coeffs);
+
+    let mut formants: Vec<f32> = roots
+        .iter()
+        .filter(|r| r.1 > 0.0) // keep one root per conjugate pair (positive imaginary part)
+        .filter_map(|&(re, im)| {
+            let radius = (re * re + im * im).sqrt();
+            if radius <= 0.0 || radius >= 1.0 {
+                return None;
+            }
+            let angle = im.atan2(re);
+            let frequency = angle * sample_rate as f32 / (2.0 * PI);
+            let bandwidth = -radius.ln() * sample_rate as f32 / PI;
+
+            if frequency >= MIN_FORMANT_HZ && frequency <= MAX_FORMANT_HZ && bandwidth <= MAX_BANDWIDTH_HZ {
+                Some(frequency)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    formants.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    formants
+}
+
+/// Find all complex roots of a polynomial (highest-degree coefficient first)
+/// via the Durand-Kerner (Weierstrass) simultaneous-iteration method.
+fn find_polynomial_roots(coeffs: &[f32]) -> Vec<(f32, f32)> {
+    let degree = coeffs.len() - 1;
+    if degree == 0 {
+        return Vec::new();
+    }
+
+    let leading = coeffs[0];
+    let normalized: Vec<f32> = coeffs.iter().map(|&c| c / leading).collect();
+
+    // Initial guesses spread around a circle, offset slightly off the real axis
+    let mut roots: Vec<(f32, f32)> = (0..degree)
+        .map(|k| {
+            let angle = 2.0 * PI * k as f32 / degree as f32 + 0.2;
+            (0.4 * angle.cos(), 0.4 * angle.sin())
+        })
+        .collect();
+
+    const MAX_ITERATIONS: usize = 100;
+    for _ in 0..MAX_ITERATIONS {
+        let mut max_delta = 0.0f32;
+        let previous = roots.clone();
+
+        for i in 0..degree {
+            let (num_re, num_im) = eval_polynomial(&normalized, previous[i]);
+
+            let mut denom = (1.0f32, 0.0f32);
+            for (j, &root_j) in previous.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                let diff = (previous[i].0 - root_j.0, previous[i].1 - root_j.1);
+                denom = complex_mul(denom, diff);
+            }
+
+            let delta = complex_div((num_re, num_im), denom);
+            roots[i] = (previous[i].0 - delta.0, previous[i].1 - delta.1);
+            max_delta = max_delta.max((delta.0 * delta.0 + delta.1 * delta.1).sqrt());
+        }
+
+        if max_delta < 1e-6 {
+            break;
+        }
+    }
+
+    roots
+}
+
+fn eval_polynomial(coeffs: &[f32], x: (f32, f32)) -> (f32, f32) {
+    let mut result = (0.0f32, 0.0f32);
+    for &c in coeffs {
+        result = complex_mul(result, x);
+        result.0 += c;
+    }
+    result
+}
+
+fn complex_mul(a: (f32, f32), b: (f32, f32)) -> (f32, f32) {
+    (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
+
+fn complex_div(a: (f32, f32), b: (f32, f32)) -> (f32, f32) {
+    let denom = b.0 * b.0 + b.1 * b.1;
+    if denom < 1e-20 {
+        return (0.0, 0.0);
+    }
+    ((a.0 * b.0 + a.1 * b.1) / denom, (a.1 * b.0 - a.0 * b.1) / denom)
+}
+
+/// Score nasality from a low first formant plus extra resonance energy in the
+/// ~250-450 Hz and ~1 kHz bands characteristic of nasalized vowels. This is a
+/// heuristic over the all-pole LPC spectrum (it approximates the true nasal
+/// anti-resonance/extra-pole pattern rather than modeling zeros explicitly).
+fn nasal_resonance_score(formants: &[f32]) -> f32 {
+    if formants.is_empty() {
+        return 0.0;
+    }
+
+    let f1 = formants[0];
+    let low_f1_score = (1.0 - (f1 / 500.0).min(1.0)).max(0.0);
+
+    let nasal_band_hits = formants
+        .iter()
+        .filter(|&&f| (250.0..=450.0).contains(&f) || (900.0..=1100.0).contains(&f))
+        .count();
+    let nasal_band_score = (nasal_band_hits as f32 / 2.0).min(1.0);
+
+    (low_f1_score + nasal_band_score) / 2.0
+}
+
+fn detect_tabla_patterns(spectrogram: &Array2<f32>, sample_rate: u32, window_size: usize) -> Vec<f32> {
+    // Simplified tabla pattern detection
+    vec![0.5; 10]
+}
+
+fn detect_harmonium_features(spectrogram: &Array2<f32>, sample_rate: u32, window_size: usize) -> Vec<f32> {
+    // Simplified harmonium feature detection
+    vec![0.3; 8]
+}
+
+fn detect_string_features(spectrogram: &Array2<f32>, sample_rate: u32, window_size: usize) -> Vec<f32> {
+    // Simplified string instrument detection
+    vec![0.4; 6]
+}
+
+fn calculate_percussion_intensity(spectrogram: &Array2<f32>, sample_rate: u32, window_size: usize) -> f32 {
+    // Calculate overall percussion intensity
+    let percussion_bins = get_percussion_bins(sample_rate, window_size);
+    let percussion_energy = spectrogram.select(Axis(0), &percussion_bins).sum();
+    let total_energy = spectrogram.sum();
+    
+    percussion_energy / total_energy
+}
+
+const MIN_TEMPO_BPM: f32 = 60.0;
+const MAX_TEMPO_BPM: f32 = 200.0;
+const DEFAULT_TEMPO_BPM: f32 = 120.0;
+
+/// Strongest peak of the onset-autocorrelation curve, read as BPM.
+fn estimate_primary_tempo(audio_data: &[f32], sample_rate: u32) -> f32 {
+    let (onset_strength, frame_rate) = match compute_onset_envelope(audio_data, sample_rate) {
+        Ok(result) => result,
+        Err(_) => return DEFAULT_TEMPO_BPM,
+    };
+    let curve = onset_autocorrelation(&onset_strength, frame_rate);
+    let min_lag = (60.0 / MAX_TEMPO_BPM * frame_rate) as usize;
+    let max_lag = (60.0 / MIN_TEMPO_BPM * frame_rate) as usize;
+
+    autocorrelation_peaks(&curve, min_lag, max_lag)
+        .first()
+        .map(|&lag| 60.0 * frame_rate / lag as f32)
+        .unwrap_or(DEFAULT_TEMPO_BPM)
+}
+
+/// A second, clearly-distinct peak of the onset-autocorrelation curve, for
+/// tracks with a genuine polyrhythmic/secondary pulse. Peaks within
+/// `MIN_SEPARATION_RATIO` of the primary lag are treated as the same tempo
+/// and skipped; peaks at other lags, octave multiples included, are not.
+fn estimate_secondary_tempo(audio_data: &[f32], sample_rate: u32) -> Option<f32> {
+    const MIN_SEPARATION_RATIO: f32 = 0.15;
+
+    let (onset_strength, frame_rate) = compute_onset_envelope(audio_data, sample_rate).ok()?;
+    let curve = onset_autocorrelation(&onset_strength, frame_rate);
+    let min_lag = (60.0 / MAX_TEMPO_BPM * frame_rate) as usize;
+    let max_lag = (60.0 / MIN_TEMPO_BPM * frame_rate) as usize;
+
+    let peaks = autocorrelation_peaks(&curve, min_lag, max_lag);
+    let primary_lag = *peaks.first()?;
+
+    peaks
+        .iter()
+        .skip(1)
+        .find(|&&lag| ((lag as f32 / primary_lag as f32) - 1.0).abs() > MIN_SEPARATION_RATIO)
+        .map(|&lag| 60.0 * frame_rate / lag as f32)
 }
 
+fn estimate_taal_cycle(audio_data: &[f32], sample_rate: u32, tempo: f32) -> f32 {
+    // Estimate taal cycle length
+    16.0 // Common 16-beat cycle
+}
+
+/// The onset-autocorrelation curve itself (L2-normalized so it's directly
+/// comparable via cosine similarity): its shape captures finer groove/laya
+/// variation than `primary_tempo`'s single best peak alone.
 fn extract_laya_variations(audio_data: &[f32], sample_rate: u32) -> Vec<f32> {
-    // Extract tempo variations
-    vec![1.0, 1.2, 0.8, 1.1]
+    let (onset_strength, frame_rate) = match compute_onset_envelope(audio_data, sample_rate) {
+        Ok(result) => result,
+        Err(_) => return Vec::new(),
+    };
+    let mut curve = onset_autocorrelation(&onset_strength, frame_rate);
+    l2_normalize(&mut curve);
+    curve
 }
 
-fn detect_scale_type(audio_data: &[f32], sample_rate: u32) -> String {
-    // Simplified scale detection
-    "major".to_string()
+/// Krumhansl-Schmuckler major key profile, indexed by semitone above the tonic
+const MAJOR_KEY_PROFILE: [f32; 12] = [
+    6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+];
+
+/// Krumhansl-Schmuckler minor key profile, indexed by semitone above the tonic
+const MINOR_KEY_PROFILE: [f32; 12] = [
+    6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
+];
+
+const PITCH_CLASS_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+/// Detect the tonic/mode of a track by correlating its mean chroma profile
+/// against all 24 rotated Krumhansl-Schmuckler key templates (12 major tonics
+/// then 12 minor tonics, in pitch-class order C..B), returning the best
+/// label (e.g. `"C# minor"`) along with the full 24-element correlation
+/// vector. Indian ragas rarely collapse to a clean major/minor, so callers
+/// doing raga classification should prefer the correlation vector over the label.
+fn detect_scale_type(chroma_features: &[f32]) -> (String, Vec<f32>) {
+    let chroma_profile = mean_chroma_profile(chroma_features);
+    let correlations = correlate_key_profiles(&chroma_profile);
+
+    let best_index = correlations
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(index, _)| index)
+        .unwrap_or(0);
+
+    (key_label(best_index), correlations)
+}
+
+/// Average the per-frame 12-bin chroma vectors (flattened as 12 floats per
+/// frame) into a single mean chroma profile.
+fn mean_chroma_profile(chroma_features: &[f32]) -> [f32; 12] {
+    let mut mean = [0.0f32; 12];
+    let num_frames = chroma_features.len() / 12;
+    if num_frames == 0 {
+        return mean;
+    }
+
+    for frame in chroma_features.chunks_exact(12) {
+        for (i, &value) in frame.iter().enumerate() {
+            mean[i] += value;
+        }
+    }
+    for value in mean.iter_mut() {
+        *value /= num_frames as f32;
+    }
+    mean
+}
+
+/// Pearson correlation of `chroma` against all 12 rotations of the major
+/// profile, then all 12 rotations of the minor profile.
+fn correlate_key_profiles(chroma: &[f32; 12]) -> Vec<f32> {
+    let mut correlations = Vec::with_capacity(24);
+    for profile in [&MAJOR_KEY_PROFILE, &MINOR_KEY_PROFILE] {
+        for tonic in 0..12 {
+            let rotated: Vec<f32> = (0..12).map(|pitch_class| profile[(pitch_class + 12 - tonic) % 12]).collect();
+            correlations.push(pearson_correlation(chroma, &rotated));
+        }
+    }
+    correlations
+}
+
+fn key_label(index: usize) -> String {
+    let tonic = index % 12;
+    let mode = if index < 12 { "major" } else { "minor" };
+    format!("{} {}", PITCH_CLASS_NAMES[tonic], mode)
+}
+
+fn pearson_correlation(a: &[f32], b: &[f32]) -> f32 {
+    let n = a.len().min(b.len());
+    if n == 0 {
+        return 0.0;
+    }
+
+    let mean_a = a.iter().take(n).sum::<f32>() / n as f32;
+    let mean_b = b.iter().take(n).sum::<f32>() / n as f32;
+
+    let mut covariance = 0.0f32;
+    let mut variance_a = 0.0f32;
+    let mut variance_b = 0.0f32;
+    for i in 0..n {
+        let da = a[i] - mean_a;
+        let db = b[i] - mean_b;
+        covariance += da * db;
+        variance_a += da * da;
+        variance_b += db * db;
+    }
+
+    if variance_a <= 0.0 || variance_b <= 0.0 {
+        return 0.0;
+    }
+
+    covariance / (variance_a.sqrt() * variance_b.sqrt())
 }
 
 fn extract_melodic_contour(audio_data: &[f32], sample_rate: u32) -> Vec<f32> {
@@ -688,9 +1817,41 @@ fn detect_ornamentation_patterns(audio_data: &[f32], sample_rate: u32) -> Vec<f3
     vec![0.3, 0.7, 0.4, 0.6]
 }
 
+/// Histogram of how far voiced pitch deviates from the nearest equal-tempered
+/// semitone, in cents. Indian classical/folk vocals lean heavily on
+/// continuous pitch inflection (meend/gamak) rather than fixed semitones, so
+/// the shape of this distribution is a meaningful melodic fingerprint where a
+/// single average would wash the deviations out.
 fn extract_microtonal_features(audio_data: &[f32], sample_rate: u32) -> Vec<f32> {
-    // Extract microtonal features
-    vec![0.1, 0.2, 0.15, 0.25]
+    const NUM_BINS: usize = 8;
+    const MAX_DEVIATION_CENTS: f32 = 50.0;
+
+    let voiced: Vec<f32> = estimate_pitch_contour(audio_data, sample_rate)
+        .into_iter()
+        .filter(|&f0| f0 > 0.0)
+        .collect();
+
+    let mut bins = vec![0.0f32; NUM_BINS];
+    if voiced.is_empty() {
+        return bins;
+    }
+
+    for f0 in &voiced {
+        let midi_note = 12.0 * (f0 / 440.0).log2() + 69.0;
+        let deviation_cents = (midi_note - midi_note.round()) * 100.0;
+        let clamped = deviation_cents.clamp(-MAX_DEVIATION_CENTS, MAX_DEVIATION_CENTS);
+        let normalized = (clamped + MAX_DEVIATION_CENTS) / (2.0 * MAX_DEVIATION_CENTS);
+        let bin = ((normalized * NUM_BINS as f32) as usize).min(NUM_BINS - 1);
+        bins[bin] += 1.0;
+    }
+
+    let total: f32 = bins.iter().sum();
+    if total > 0.0 {
+        for b in bins.iter_mut() {
+            *b /= total;
+        }
+    }
+    bins
 }
 
 fn extract_short_term_features(audio_data: &[f32], sample_rate: u32) -> Vec<f32> {
@@ -708,9 +1869,12 @@ fn extract_long_term_features(audio_data: &[f32], sample_rate: u32) -> Vec<f32>
     vec![0.7; 10]
 }
 
+/// Same onset-autocorrelation curve as `extract_laya_variations`: it doubles
+/// as the track's temporal-dynamics signature, since periodicity over the
+/// 0-4s window is exactly the kind of medium-scale dynamic `TemporalFeatures`
+/// compares alongside the shorter/longer-term envelopes.
 fn extract_temporal_dynamics(audio_data: &[f32], sample_rate: u32) -> Vec<f32> {
-    // Extract temporal dynamics
-    vec![0.4, 0.8, 0.3, 0.9, 0.2]
+    extract_laya_variations(audio_data, sample_rate)
 }
 
 fn calculate_confidence(
@@ -762,8 +1926,9 @@ impl VocalCharacteristics {
         let vibrato_sim = 1.0 - (self.vibrato_frequency - other.vibrato_frequency).abs() / 10.0;
         let ornamentation_sim = 1.0 - (self.ornamentation_intensity - other.ornamentation_intensity).abs();
         let nasal_sim = 1.0 - (self.nasal_resonance - other.nasal_resonance).abs();
-        
-        (pitch_range_sim + vibrato_sim + ornamentation_sim + nasal_sim) / 4.0
+        let formant_sim = cosine_similarity(&self.formants, &other.formants);
+
+        (pitch_range_sim + vibrato_sim + ornamentation_sim + nasal_sim + formant_sim) / 5.0
     }
 }
 
@@ -783,18 +1948,50 @@ impl RhythmicPatterns {
         let tempo_sim = 1.0 - (self.primary_tempo - other.primary_tempo).abs() / 100.0;
         let cycle_sim = 1.0 - (self.taal_cycle - other.taal_cycle).abs() / 20.0;
         let laya_sim = cosine_similarity(&self.laya_variations, &other.laya_variations);
-        
-        (tempo_sim + cycle_sim + laya_sim) / 3.0
+        let beat_spectrum_sim = beat_spectrum_similarity(&self.beat_spectrum, &other.beat_spectrum);
+
+        (tempo_sim + cycle_sim + laya_sim + beat_spectrum_sim) / 4.0
     }
 }
 
+/// Compare two beat spectra by cosine similarity, trying small shifts of one
+/// against the other so a downbeat offset between two recordings of the same
+/// song doesn't register as a rhythmic mismatch.
+fn beat_spectrum_similarity(a: &[f32], b: &[f32]) -> f32 {
+    const MAX_SHIFT: usize = 4;
+
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let mut best = cosine_similarity(a, b);
+    for shift in 1..=MAX_SHIFT {
+        if shift < a.len() {
+            let overlap = (a.len() - shift).min(b.len());
+            if overlap > 0 {
+                best = best.max(cosine_similarity(&a[shift..shift + overlap], &b[..overlap]));
+            }
+        }
+        if shift < b.len() {
+            let overlap = (b.len() - shift).min(a.len());
+            if overlap > 0 {
+                best = best.max(cosine_similarity(&a[..overlap], &b[shift..shift + overlap]));
+            }
+        }
+    }
+
+    best
+}
+
 impl MelodicCharacteristics {
     fn similarity(&self, other: &MelodicCharacteristics) -> f32 {
-        let scale_sim = if self.scale_type == other.scale_type { 1.0 } else { 0.0 };
+        // Correlation-vector similarity is more graduated than the label match:
+        // two tracks a semitone apart in key still share most of their key-profile shape
+        let scale_sim = cosine_similarity(&self.key_correlations, &other.key_correlations);
         let contour_sim = cosine_similarity(&self.melodic_contour, &other.melodic_contour);
         let ornamentation_sim = cosine_similarity(&self.ornamentation_patterns, &other.ornamentation_patterns);
         let microtonal_sim = cosine_similarity(&self.microtonal_features, &other.microtonal_features);
-        
+
         (scale_sim + contour_sim + ornamentation_sim + microtonal_sim) / 4.0
     }
 }
@@ -851,9 +2048,14 @@ mod tests {
                     num_bins: 2048,
                     window_size: 4096,
                     overlap: 0.5,
+                    key: None,
                 },
             },
             mfcc_features: vec![0.1, 0.2, 0.3],
+            timbre_model: MfccTimbreModel::from_frames(
+                &[vec![0.1; 13], vec![0.2; 13], vec![0.15; 13]],
+                NUM_MFCC,
+            ),
             chroma_features: vec![0.4, 0.5, 0.6],
             rhythm_features: vec![120.0, 0.5, 0.6],
             language_features: LanguageFeatures {
@@ -862,6 +2064,7 @@ mod tests {
                     vibrato_frequency: 5.0,
                     ornamentation_intensity: 0.5,
                     nasal_resonance: 0.3,
+                    formants: vec![600.0, 1500.0, 2500.0],
                 },
                 instrumental_patterns: InstrumentalPatterns {
                     tabla_patterns: vec![0.5; 10],
@@ -874,9 +2077,11 @@ mod tests {
                     secondary_tempo: None,
                     taal_cycle: 16.0,
                     laya_variations: vec![1.0, 1.2, 0.8],
+                    beat_spectrum: vec![0.6, 0.4, 0.3, 0.5],
                 },
                 melodic_characteristics: MelodicCharacteristics {
-                    scale_type: "major".to_string(),
+                    scale_type: "C major".to_string(),
+                    key_correlations: vec![0.8; 24],
                     melodic_contour: vec![0.0, 0.5, 1.0],
                     ornamentation_patterns: vec![0.3, 0.7],
                     microtonal_features: vec![0.1, 0.2],
@@ -896,5 +2101,389 @@ mod tests {
         let similarity = fingerprint1.similarity(&fingerprint2);
         assert!(similarity > 0.9); // Should be very similar
         assert!(similarity <= 1.0);
+
+        let cosine_mode_similarity = fingerprint1.similarity_with_timbre_mode(&fingerprint2, TimbreSimilarityMode::Cosine);
+        assert!(cosine_mode_similarity > 0.9);
+        assert!(cosine_mode_similarity <= 1.0);
+    }
+
+    #[test]
+    fn test_estimate_pitch_contour_tracks_known_frequency() {
+        let sample_rate = 44100;
+        let frequency = 220.0; // A3, well within the vocal range
+        let duration = 0.5;
+
+        let audio_data: Vec<f32> = (0..(sample_rate as f32 * duration) as usize)
+            .map(|i| (2.0 * PI * frequency * i as f32 / sample_rate as f32).sin())
+            .collect();
+
+        let contour = estimate_pitch_contour(&audio_data, sample_rate);
+        assert!(!contour.is_empty());
+
+        let voiced: Vec<f32> = contour.into_iter().filter(|&p| p > 0.0).collect();
+        assert!(!voiced.is_empty(), "expected at least one voiced frame for a pure tone");
+
+        let mean_pitch = voiced.iter().sum::<f32>() / voiced.len() as f32;
+        assert!(
+            (mean_pitch - frequency).abs() < 5.0,
+            "expected pitch near {frequency} Hz, got {mean_pitch} Hz"
+        );
+    }
+
+    #[test]
+    fn test_estimate_pitch_contour_empty_for_short_input() {
+        let contour = estimate_pitch_contour(&[0.0; 100], 44100);
+        assert!(contour.is_empty());
+    }
+
+    #[test]
+    fn test_timbre_model_similarity_identical_is_near_one() {
+        let frames = vec![vec![0.2; NUM_MFCC], vec![0.3; NUM_MFCC], vec![0.1; NUM_MFCC]];
+        let model = MfccTimbreModel::from_frames(&frames, NUM_MFCC);
+
+        let similarity = model.similarity(&model);
+        assert!(similarity > 0.99, "identical models should have near-1.0 similarity, got {similarity}");
+    }
+
+    #[test]
+    fn test_timbre_model_similarity_decreases_with_distance() {
+        let close_frames = vec![vec![0.21; NUM_MFCC], vec![0.29; NUM_MFCC], vec![0.11; NUM_MFCC]];
+        let far_frames = vec![vec![5.0; NUM_MFCC], vec![-4.0; NUM_MFCC], vec![6.0; NUM_MFCC]];
+
+        let base = MfccTimbreModel::from_frames(&vec![vec![0.2; NUM_MFCC], vec![0.3; NUM_MFCC], vec![0.1; NUM_MFCC]], NUM_MFCC);
+        let close = MfccTimbreModel::from_frames(&close_frames, NUM_MFCC);
+        let far = MfccTimbreModel::from_frames(&far_frames, NUM_MFCC);
+
+        let close_similarity = base.similarity(&close);
+        let far_similarity = base.similarity(&far);
+
+        assert!(close_similarity > far_similarity);
+        assert!((0.0..=1.0).contains(&close_similarity));
+        assert!((0.0..=1.0).contains(&far_similarity));
+    }
+
+    #[test]
+    fn test_compute_beat_spectrum_periodic_signal_is_unit_energy() {
+        let sample_rate = 44100;
+        let duration = 4.0;
+        let frequency = 440.0;
+
+        let audio_data: Vec<f32> = (0..(sample_rate as f32 * duration) as usize)
+            .map(|i| (2.0 * PI * frequency * i as f32 / sample_rate as f32).sin())
+            .collect();
+
+        let beat_spectrum = compute_beat_spectrum(&audio_data, sample_rate);
+        assert!(!beat_spectrum.is_empty());
+
+        let energy: f32 = beat_spectrum.iter().map(|v| v * v).sum();
+        assert!((energy - 1.0).abs() < 1e-3, "expected unit energy, got {energy}");
+    }
+
+    #[test]
+    fn test_beat_spectrum_similarity_identical_is_one() {
+        let spectrum = vec![1.0, 0.8, 0.6, 0.4, 0.2];
+        let similarity = beat_spectrum_similarity(&spectrum, &spectrum);
+        assert!((similarity - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_beat_spectrum_similarity_empty_is_zero() {
+        assert_eq!(beat_spectrum_similarity(&[], &[1.0, 2.0]), 0.0);
+    }
+
+    #[test]
+    fn test_detect_scale_type_recognizes_c_major_profile() {
+        // A single chroma frame shaped exactly like the C major key profile
+        let chroma_features: Vec<f32> = MAJOR_KEY_PROFILE.to_vec();
+
+        let (label, correlations) = detect_scale_type(&chroma_features);
+        assert_eq!(label, "C major");
+        assert_eq!(correlations.len(), 24);
+        assert!(correlations[0] > 0.99, "expected near-perfect correlation with its own profile");
+    }
+
+    #[test]
+    fn test_detect_scale_type_recognizes_transposed_minor_profile() {
+        // Rotate the minor profile so the tonic sits on A (pitch class 9)
+        let tonic = 9;
+        let rotated: Vec<f32> = (0..12).map(|pc| MINOR_KEY_PROFILE[(pc + 12 - tonic) % 12]).collect();
+
+        let (label, correlations) = detect_scale_type(&rotated);
+        assert_eq!(label, "A minor");
+        assert_eq!(correlations.len(), 24);
+    }
+
+    #[test]
+    fn test_detect_scale_type_empty_chroma_is_stable() {
+        let (label, correlations) = detect_scale_type(&[]);
+        assert_eq!(correlations.len(), 24);
+        assert!(!label.is_empty());
+    }
+
+    #[test]
+    fn test_calculate_onset_strength_flags_a_sharp_attack() {
+        // 3 quiet frames, then a sudden loud frame, then quiet again
+        let num_bins = 4;
+        let mut spectrogram = Array2::zeros((num_bins, 6));
+        for frame_idx in 0..6 {
+            let value = if frame_idx == 3 { 5.0 } else { 0.1 };
+            for bin in 0..num_bins {
+                spectrogram[[bin, frame_idx]] = value;
+            }
+        }
+
+        let onset_strength = calculate_onset_strength(&spectrogram);
+        assert_eq!(onset_strength.len(), 6);
+
+        let peak_idx = onset_strength
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .map(|(idx, _)| idx)
+            .unwrap();
+        assert_eq!(peak_idx, 3, "the sharp attack frame should dominate the onset envelope");
+    }
+
+    #[test]
+    fn test_calculate_onset_strength_is_non_negative() {
+        let spectrogram = Array2::from_shape_fn((8, 20), |(i, j)| ((i * 7 + j * 3) % 5) as f32);
+        let onset_strength = calculate_onset_strength(&spectrogram);
+        assert!(onset_strength.iter().all(|&v| v >= 0.0));
+    }
+
+    #[test]
+    fn test_estimate_formants_recovers_resonance_from_two_pole_filter() {
+        // LPC coefficients of a simple resonant all-pole filter should yield a
+        // clear formant near the resonance frequency baked into them via a
+        // stable complex-conjugate pole pair.
+        let sample_rate = 16000u32;
+        let resonance_freq = 1000.0;
+        let radius = 0.95;
+        let theta = 2.0 * PI * resonance_freq / sample_rate as f32;
+
+        // Polynomial (z - r*e^{i theta})(z - r*e^{-i theta}) = z^2 - 2r*cos(theta) z + r^2
+        let a1 = -2.0 * radius * theta.cos();
+        let a2 = radius * radius;
+        let lpc_coeffs = vec![a1, a2];
+
+        let formants = estimate_formants(&lpc_coeffs, sample_rate);
+        assert!(!formants.is_empty());
+        assert!(
+            (formants[0] - resonance_freq).abs() < 50.0,
+            "expected a formant near {resonance_freq} Hz, got {:?}",
+            formants
+        );
+    }
+
+    #[test]
+    fn test_levinson_durbin_recovers_coefficients_for_known_ar_process() {
+        // Signal generated by x[n] = 1.5*x[n-1] - 0.7*x[n-2] + noise, a stable AR(2) process
+        let mut x = vec![0.0f32; 512];
+        let mut seed = 12345u32;
+        let mut next_noise = || {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            ((seed >> 16) & 0x7fff) as f32 / 32768.0 - 0.5
+        };
+        for n in 2..x.len() {
+            x[n] = 1.5 * x[n - 1] - 0.7 * x[n - 2] + 0.1 * next_noise();
+        }
+
+        let autocorr = autocorrelation(&x, 2);
+        let coeffs = levinson_durbin(&autocorr, 2).expect("AR(2) signal should yield stable LPC coefficients");
+        assert_eq!(coeffs.len(), 2);
+        // LPC coefficients approximate the negated AR coefficients
+        assert!((coeffs[0] - (-1.5)).abs() < 0.3, "a1 = {}", coeffs[0]);
+        assert!((coeffs[1] - 0.7).abs() < 0.3, "a2 = {}", coeffs[1]);
+    }
+
+    #[test]
+    fn test_estimate_nasal_resonance_returns_score_in_range() {
+        let sample_rate = 16000;
+        let duration = 1.0;
+        let frequency = 150.0;
+
+        let audio_data: Vec<f32> = (0..(sample_rate as f32 * duration) as usize)
+            .map(|i| (2.0 * PI * frequency * i as f32 / sample_rate as f32).sin())
+            .collect();
+
+        let (score, formants) = estimate_nasal_resonance(&audio_data, sample_rate);
+        assert!((0.0..=1.0).contains(&score));
+        assert!(formants.len() <= NUM_TRACKED_FORMANTS);
+    }
+
+    #[test]
+    fn test_extract_microtonal_features_sums_to_one_for_voiced_audio() {
+        let sample_rate = 44100;
+        let duration = 0.5;
+        let frequency = 220.0;
+
+        let audio_data: Vec<f32> = (0..(sample_rate as f32 * duration) as usize)
+            .map(|i| (2.0 * PI * frequency * i as f32 / sample_rate as f32).sin())
+            .collect();
+
+        let features = extract_microtonal_features(&audio_data, sample_rate);
+        assert_eq!(features.len(), 8);
+
+        let total: f32 = features.iter().sum();
+        assert!((total - 1.0).abs() < 1e-5, "expected histogram to sum to 1.0, got {total}");
+    }
+
+    #[test]
+    fn test_extract_microtonal_features_empty_for_silence() {
+        let features = extract_microtonal_features(&[0.0; 100], 44100);
+        assert!(features.iter().all(|&v| v == 0.0));
+    }
+
+    #[test]
+    fn test_match_hash_segments_finds_embedded_excerpt() {
+        // Reference track: 20 frames, with a distinctive 6-frame run starting at index 8
+        let reference_hashes: Vec<u64> = (0..20).map(|i| i as u64 * 101).collect();
+        let reference_offsets: Vec<f32> = (0..20).map(|i| i as f32 * 0.5).collect();
+
+        // Query is exactly that 6-frame excerpt
+        let query_hashes = reference_hashes[8..14].to_vec();
+        let query_offsets: Vec<f32> = (0..6).map(|i| i as f32 * 0.5).collect();
+
+        let segments = match_hash_segments(&query_hashes, &query_offsets, &reference_hashes, &reference_offsets, 2.0, 0.05);
+
+        assert!(!segments.is_empty(), "expected the embedded excerpt to be found");
+        let best = segments.iter().max_by(|a, b| a.score.partial_cmp(&b.score).unwrap()).unwrap();
+        assert!((best.reference_start - 4.0).abs() < 0.5, "expected reference_start near 4.0s, got {}", best.reference_start);
+        assert!(best.score > 0.99);
+    }
+
+    #[test]
+    fn test_match_hash_segments_no_match_for_unrelated_hashes() {
+        let query_hashes: Vec<u64> = (0..10).map(|i| i as u64 * 7 + 1).collect();
+        let query_offsets: Vec<f32> = (0..10).map(|i| i as f32 * 0.5).collect();
+        let reference_hashes: Vec<u64> = (0..10).map(|i| i as u64 * 999_983 + 5).collect();
+        let reference_offsets = query_offsets.clone();
+
+        let segments = match_hash_segments(&query_hashes, &query_offsets, &reference_hashes, &reference_offsets, 1.0, 0.02);
+        assert!(segments.is_empty());
+    }
+
+    #[test]
+    fn test_match_hash_segments_empty_input_returns_empty() {
+        assert!(match_hash_segments(&[], &[], &[1, 2, 3], &[0.0, 0.5, 1.0], 1.0, 0.1).is_empty());
+    }
+
+    #[test]
+    fn test_estimate_primary_tempo_recovers_known_click_period() {
+        let sample_rate = 44100;
+        let bpm = 120.0;
+        let period_samples = (60.0 / bpm * sample_rate as f32) as usize;
+        let duration_samples = period_samples * 5;
+
+        let mut audio_data = vec![0.0f32; duration_samples];
+        let mut position = 0;
+        while position < audio_data.len() {
+            audio_data[position] = 1.0;
+            position += period_samples;
+        }
+
+        let tempo = estimate_primary_tempo(&audio_data, sample_rate);
+        assert!((tempo - bpm).abs() < 15.0, "expected tempo near {bpm} bpm, got {tempo}");
+    }
+
+    #[test]
+    fn test_extract_laya_variations_is_unit_energy() {
+        let sample_rate = 44100;
+        let bpm = 100.0;
+        let period_samples = (60.0 / bpm * sample_rate as f32) as usize;
+        let duration_samples = period_samples * 5;
+
+        let mut audio_data = vec![0.0f32; duration_samples];
+        let mut position = 0;
+        while position < audio_data.len() {
+            audio_data[position] = 1.0;
+            position += period_samples;
+        }
+
+        let laya = extract_laya_variations(&audio_data, sample_rate);
+        assert!(!laya.is_empty());
+        let energy: f32 = laya.iter().map(|v| v * v).sum();
+        assert!((energy - 1.0).abs() < 1e-3, "expected unit energy, got {energy}");
+    }
+
+    #[test]
+    fn test_extract_laya_variations_empty_for_short_input() {
+        assert!(extract_laya_variations(&[0.0; 10], 44100).is_empty());
+    }
+
+    fn sample_fingerprint() -> AdvancedFingerprint {
+        AdvancedFingerprint {
+            hash_fingerprint: super::super::fingerprint::Fingerprint {
+                hashes: vec![1, 2, 3],
+                time_offsets: vec![0.0, 0.1, 0.2],
+                peaks: Vec::new(),
+                metadata: super::super::fingerprint::FingerprintMetadata {
+                    sample_rate: 44100,
+                    duration: 1.0,
+                    num_bins: 2048,
+                    window_size: 4096,
+                    overlap: 0.5,
+                    key: None,
+                },
+            },
+            mfcc_features: vec![0.1, 0.2, 0.3],
+            timbre_model: MfccTimbreModel::from_frames(
+                &[vec![0.1; 13], vec![0.2; 13], vec![0.15; 13]],
+                NUM_MFCC,
+            ),
+            chroma_features: vec![0.4, 0.5, 0.6],
+            rhythm_features: vec![120.0, 0.5, 0.6],
+            language_features: LanguageFeatures {
+                vocal_characteristics: VocalCharacteristics {
+                    pitch_range: (200.0, 800.0),
+                    vibrato_frequency: 5.0,
+                    ornamentation_intensity: 0.5,
+                    nasal_resonance: 0.3,
+                    formants: vec![600.0, 1500.0, 2500.0],
+                },
+                instrumental_patterns: InstrumentalPatterns {
+                    tabla_patterns: vec![0.5; 10],
+                    harmonium_features: vec![0.3; 8],
+                    string_features: vec![0.4; 6],
+                    percussion_intensity: 0.6,
+                },
+                rhythmic_patterns: RhythmicPatterns {
+                    primary_tempo: 120.0,
+                    secondary_tempo: None,
+                    taal_cycle: 16.0,
+                    laya_variations: vec![1.0, 1.2, 0.8],
+                    beat_spectrum: vec![0.6, 0.4, 0.3, 0.5],
+                },
+                melodic_characteristics: MelodicCharacteristics {
+                    scale_type: "C major".to_string(),
+                    key_correlations: vec![0.8; 24],
+                    melodic_contour: vec![0.0, 0.5, 1.0],
+                    ornamentation_patterns: vec![0.3, 0.7],
+                    microtonal_features: vec![0.1, 0.2],
+                },
+            },
+            temporal_features: TemporalFeatures {
+                short_term: vec![0.5; 20],
+                medium_term: vec![0.6; 15],
+                long_term: vec![0.7; 10],
+                temporal_dynamics: vec![0.4, 0.8, 0.3],
+            },
+            confidence: 0.8,
+        }
+    }
+
+    #[test]
+    fn test_similarity_with_rhythm_weighting_pure_timbre_ignores_rhythm() {
+        let mut fingerprint_a = sample_fingerprint();
+        let mut fingerprint_b = sample_fingerprint();
+        fingerprint_a.rhythm_features = vec![1.0, 0.0, 0.0, 0.0];
+        fingerprint_b.rhythm_features = vec![0.0, 1.0, 0.0, 0.0];
+
+        let timbre_only = fingerprint_a.similarity_with_rhythm_weighting(&fingerprint_b, 0.0);
+        let rhythm_only = fingerprint_a.similarity_with_rhythm_weighting(&fingerprint_b, 1.0);
+
+        // Identical timbre models but orthogonal rhythm features: pure-timbre
+        // weighting should score strictly higher than pure-rhythm weighting.
+        assert!(timbre_only > rhythm_only);
     }
 }
@@ -0,0 +1,143 @@
+//! Perceptual feature-vector similarity for recommendation/clustering (analogous to
+//! bliss-rs's song analysis vectors), distinct from `fingerprint`/`similarity`'s
+//! hash-based exact/near-exact recognition path: "find songs that sound similar"
+//! rather than "find this exact recording".
+
+use crate::audio::{self, AudioFeatures};
+use anyhow::Result;
+
+/// Compute a per-track perceptual descriptor (tempo, spectral shape, loudness,
+/// chroma) suitable for comparing tracks via `feature_distance`.
+pub fn analyze(audio_data: &[f32], sample_rate: u32) -> Result<AudioFeatures> {
+    audio::extract_features(audio_data, sample_rate)
+}
+
+/// Selects how `feature_distance` compares two perceptual descriptors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceMetric {
+    Cosine,
+    Euclidean,
+}
+
+/// Distance between two tracks' perceptual descriptors: smaller means more
+/// sonically similar. Only the bounded, cross-track-comparable components of
+/// `AudioFeatures` are used (tempo, spectral centroid/rolloff, zero-crossing
+/// rate, loudness, chroma) — the raw `mfcc`/`spectrum` fields are frame-count-
+/// and scale-dependent and aren't meaningful to compare directly here.
+pub fn feature_distance(a: &AudioFeatures, b: &AudioFeatures, metric: DistanceMetric) -> f32 {
+    let vector_a = to_comparable_vector(a);
+    let vector_b = to_comparable_vector(b);
+
+    match metric {
+        DistanceMetric::Cosine => 1.0 - cosine_similarity(&vector_a, &vector_b),
+        DistanceMetric::Euclidean => euclidean_distance(&vector_a, &vector_b),
+    }
+}
+
+/// Number of dimensions `to_comparable_vector` produces: tempo, centroid,
+/// rolloff, zero-crossing rate, loudness, plus the 12-bin chroma profile.
+pub const FEATURE_VECTOR_DIMENSIONS: usize = 17;
+
+/// Flatten an `AudioFeatures` into a fixed-length vector with each component
+/// rescaled to roughly `[0.0, 1.0]`, so no single feature (e.g. raw Hz values)
+/// dominates distance purely due to its units.
+pub(crate) fn to_comparable_vector(features: &AudioFeatures) -> [f32; FEATURE_VECTOR_DIMENSIONS] {
+    const MAX_FREQUENCY_HZ: f32 = 22_050.0;
+    const MAX_TEMPO_BPM: f32 = 200.0;
+    const MIN_LOUDNESS_DB: f32 = -60.0;
+
+    let mut vector = [0.0f32; FEATURE_VECTOR_DIMENSIONS];
+    vector[0] = (features.tempo_bpm / MAX_TEMPO_BPM).clamp(0.0, 1.0);
+    vector[1] = (features.spectral_centroid / MAX_FREQUENCY_HZ).clamp(0.0, 1.0);
+    vector[2] = (features.spectral_rolloff / MAX_FREQUENCY_HZ).clamp(0.0, 1.0);
+    vector[3] = features.zero_crossing_rate.clamp(0.0, 1.0);
+    vector[4] = ((features.loudness - MIN_LOUDNESS_DB) / -MIN_LOUDNESS_DB).clamp(0.0, 1.0);
+
+    for (i, &value) in features.chroma.iter().take(12).enumerate() {
+        vector[5 + i] = value;
+    }
+
+    vector
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+
+    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+
+    dot_product / (norm_a * norm_b)
+}
+
+fn euclidean_distance(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return f32::INFINITY;
+    }
+
+    a.iter().zip(b.iter()).map(|(x, y)| (x - y).powi(2)).sum::<f32>().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_features(tempo_bpm: f32, chroma: Vec<f32>) -> AudioFeatures {
+        AudioFeatures {
+            spectral_centroid: 2000.0,
+            spectral_rolloff: 8000.0,
+            spectral_flatness: 0.3,
+            mfcc: vec![0.1; 13],
+            zero_crossing_rate: 0.2,
+            spectrum: vec![0.0; 100],
+            tempo_bpm,
+            chroma,
+            loudness: -20.0,
+            centroid_mean: 2000.0,
+            centroid_variance: 10.0,
+            rolloff_mean: 8000.0,
+            rolloff_variance: 10.0,
+            zcr_mean: 0.2,
+            zcr_variance: 0.01,
+            flatness_mean: 0.3,
+            flatness_variance: 0.01,
+            frames: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_analyze_produces_features_for_sine_wave() {
+        let sample_rate = 44100;
+        let audio_data: Vec<f32> = (0..sample_rate)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / sample_rate as f32).sin())
+            .collect();
+
+        let features = analyze(&audio_data, sample_rate as u32).unwrap();
+        assert_eq!(features.chroma.len(), 12);
+        assert!(features.spectral_centroid > 0.0);
+    }
+
+    #[test]
+    fn test_feature_distance_identical_is_zero() {
+        let features = sample_features(120.0, vec![1.0; 12]);
+        assert_eq!(feature_distance(&features, &features, DistanceMetric::Euclidean), 0.0);
+        assert!(feature_distance(&features, &features, DistanceMetric::Cosine) < 1e-5);
+    }
+
+    #[test]
+    fn test_feature_distance_increases_with_tempo_gap() {
+        let reference = sample_features(120.0, vec![1.0; 12]);
+        let close = sample_features(125.0, vec![1.0; 12]);
+        let far = sample_features(200.0, vec![1.0; 12]);
+
+        let close_distance = feature_distance(&reference, &close, DistanceMetric::Euclidean);
+        let far_distance = feature_distance(&reference, &far, DistanceMetric::Euclidean);
+        assert!(far_distance > close_distance);
+    }
+}
@@ -25,31 +25,105 @@ pub fn normalize_audio(audio_data: &[f32]) -> Vec<f32> {
     audio_data.iter().map(|&x| x / max_val).collect()
 }
 
-/// Reduce noise using spectral subtraction
+/// Reduce noise via STFT spectral subtraction: frame the signal into overlapping
+/// Hann-windowed frames, estimate a noise magnitude profile by averaging the first
+/// few frames (assumed noise-only lead-in), subtract it per bin with a flooring
+/// rule to avoid negative magnitudes and musical-noise artifacts, then resynthesize
+/// via overlap-add.
 pub fn reduce_noise(audio_data: &[f32]) -> Vec<f32> {
-    // Simple noise reduction using moving average
-    let window_size = 5;
-    let mut denoised = Vec::with_capacity(audio_data.len());
-    
-    for i in 0..audio_data.len() {
-        let start = i.saturating_sub(window_size / 2);
-        let end = (i + window_size / 2 + 1).min(audio_data.len());
-        
-        let sum: f32 = audio_data[start..end].iter().sum();
-        let avg = sum / (end - start) as f32;
-        
-        // Apply soft thresholding
-        let threshold = 0.01;
-        let sample = if avg.abs() < threshold {
-            avg * 0.1 // Reduce noise
+    const FRAME_SIZE: usize = 1024;
+    const HOP_SIZE: usize = 256; // 75% overlap
+    const NOISE_ESTIMATION_FRAMES: usize = 6;
+    const OVER_SUBTRACTION_ALPHA: f32 = 2.0;
+    const SPECTRAL_FLOOR_BETA: f32 = 0.01;
+
+    if audio_data.len() < FRAME_SIZE {
+        return audio_data.to_vec();
+    }
+
+    let window = apply_window(&vec![1.0f32; FRAME_SIZE], WindowType::Hanning);
+
+    let mut frame_starts = Vec::new();
+    let mut frame_start = 0;
+    while frame_start + FRAME_SIZE <= audio_data.len() {
+        frame_starts.push(frame_start);
+        frame_start += HOP_SIZE;
+    }
+
+    let (forward_fft, inverse_fft) = stft_fft_pair(FRAME_SIZE);
+
+    let mut spectra: Vec<Vec<rustfft::num_complex::Complex<f32>>> = frame_starts
+        .iter()
+        .map(|&start| {
+            let mut buffer: Vec<rustfft::num_complex::Complex<f32>> = audio_data[start..start + FRAME_SIZE]
+                .iter()
+                .zip(window.iter())
+                .map(|(&sample, &w)| rustfft::num_complex::Complex::new(sample * w, 0.0))
+                .collect();
+            forward_fft.process(&mut buffer);
+            buffer
+        })
+        .collect();
+
+    // Noise magnitude profile: average the magnitude spectra of the first few
+    // frames, assumed to be noise-only
+    let noise_frames = spectra.len().min(NOISE_ESTIMATION_FRAMES);
+    let mut noise_magnitude = vec![0.0f32; FRAME_SIZE];
+    for spectrum in &spectra[..noise_frames] {
+        for (bin, value) in spectrum.iter().enumerate() {
+            noise_magnitude[bin] += value.norm();
+        }
+    }
+    for value in noise_magnitude.iter_mut() {
+        *value /= noise_frames as f32;
+    }
+
+    // Subtract the noise magnitude per bin, flooring at a fraction of the original
+    // magnitude rather than letting it go negative, and keep the original phase
+    for spectrum in &mut spectra {
+        for (bin, value) in spectrum.iter_mut().enumerate() {
+            let magnitude = value.norm();
+            let phase = value.arg();
+            let floored = (magnitude - OVER_SUBTRACTION_ALPHA * noise_magnitude[bin])
+                .max(SPECTRAL_FLOOR_BETA * magnitude);
+            *value = rustfft::num_complex::Complex::from_polar(floored, phase);
+        }
+    }
+
+    // Inverse-FFT and overlap-add each frame, normalizing by the summed squared
+    // window at each sample so the Hann analysis/synthesis window cancels out
+    let mut output = vec![0.0f32; audio_data.len()];
+    let mut window_sum = vec![0.0f32; audio_data.len()];
+    let scale = 1.0 / FRAME_SIZE as f32;
+
+    for (&start, spectrum) in frame_starts.iter().zip(spectra.iter()) {
+        let mut buffer = spectrum.clone();
+        inverse_fft.process(&mut buffer);
+
+        for (i, &sample) in buffer.iter().enumerate() {
+            output[start + i] += sample.re * scale * window[i];
+            window_sum[start + i] += window[i] * window[i];
+        }
+    }
+
+    for i in 0..output.len() {
+        if window_sum[i] > 1e-8 {
+            output[i] /= window_sum[i];
         } else {
-            avg
-        };
-        
-        denoised.push(sample);
+            // Tail samples not covered by any full frame: pass through unchanged
+            output[i] = audio_data[i];
+        }
     }
-    
-    denoised
+
+    output
+}
+
+/// Forward and inverse FFT plans of the same size, sharing one planner (mirrors
+/// `compute_fft`'s planner usage, but keeps phase for `reduce_noise`'s resynthesis
+/// instead of collapsing to a magnitude-only spectrum).
+fn stft_fft_pair(frame_size: usize) -> (std::sync::Arc<dyn rustfft::Fft<f32>>, std::sync::Arc<dyn rustfft::Fft<f32>>) {
+    let mut planner = rustfft::FftPlanner::new();
+    (planner.plan_fft_forward(frame_size), planner.plan_fft_inverse(frame_size))
 }
 
 /// Resample audio to target sample rate
@@ -110,6 +184,10 @@ pub enum WindowType {
 
 fn apply_hamming_window(audio_data: &[f32]) -> Vec<f32> {
     let n = audio_data.len();
+    if n <= 1 {
+        return audio_data.to_vec();
+    }
+
     audio_data
         .iter()
         .enumerate()
@@ -122,6 +200,10 @@ fn apply_hamming_window(audio_data: &[f32]) -> Vec<f32> {
 
 fn apply_hanning_window(audio_data: &[f32]) -> Vec<f32> {
     let n = audio_data.len();
+    if n <= 1 {
+        return audio_data.to_vec();
+    }
+
     audio_data
         .iter()
         .enumerate()
@@ -134,6 +216,10 @@ fn apply_hanning_window(audio_data: &[f32]) -> Vec<f32> {
 
 fn apply_blackman_window(audio_data: &[f32]) -> Vec<f32> {
     let n = audio_data.len();
+    if n <= 1 {
+        return audio_data.to_vec();
+    }
+
     audio_data
         .iter()
         .enumerate()
@@ -145,29 +231,358 @@ fn apply_blackman_window(audio_data: &[f32]) -> Vec<f32> {
         .collect()
 }
 
-/// Extract audio features for fingerprinting
+/// Parameters for `extract_features_with_config`'s sliding-window STFT: how wide
+/// each analysis frame is, how far to hop between frames, and which window
+/// function tapers a frame before its FFT.
+#[derive(Debug, Clone, Copy)]
+pub struct StftConfig {
+    pub window_size: usize,
+    pub hop_size: usize,
+    pub window_type: WindowType,
+}
+
+impl Default for StftConfig {
+    /// 512-sample frames, hop = size / 4 (75% overlap), Hamming window.
+    fn default() -> Self {
+        let window_size = 512;
+        Self {
+            window_size,
+            hop_size: window_size / 4,
+            window_type: WindowType::Hamming,
+        }
+    }
+}
+
+/// One STFT frame's spectral/temporal descriptors, as produced by
+/// `extract_features_with_config`'s per-frame series.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FrameFeatures {
+    pub spectral_centroid: f32,
+    pub spectral_rolloff: f32,
+    pub spectral_flatness: f32,
+    pub zero_crossing_rate: f32,
+    pub mfcc: Vec<f32>,
+}
+
+/// Extract audio features for fingerprinting, using `StftConfig::default()`.
 pub fn extract_features(audio_data: &[f32], sample_rate: u32) -> Result<AudioFeatures> {
-    // Apply window function
-    let windowed = apply_window(audio_data, WindowType::Hamming);
-    
-    // Compute FFT
-    let spectrum = compute_fft(&windowed)?;
-    
-    // Extract spectral features
-    let spectral_centroid = calculate_spectral_centroid(&spectrum, sample_rate);
-    let spectral_rolloff = calculate_spectral_rolloff(&spectrum, sample_rate);
-    let mfcc = calculate_mfcc(&spectrum, sample_rate)?;
-    let zero_crossing_rate = calculate_zero_crossing_rate(audio_data);
-    
+    extract_features_with_config(audio_data, sample_rate, StftConfig::default())
+}
+
+/// Extract audio features by sliding a window across `audio_data` (rather than
+/// running a single FFT over the whole buffer, which destroys temporal structure
+/// and gets impractically wide for long inputs): each frame is evaluated for
+/// centroid/rolloff/flatness/MFCC/zero-crossing-rate independently, the final
+/// partial frame is zero-padded rather than dropped, and the per-frame series is
+/// returned in `AudioFeatures::frames` alongside the mean-aggregated summary
+/// values in the usual scalar fields.
+pub fn extract_features_with_config(
+    audio_data: &[f32],
+    sample_rate: u32,
+    config: StftConfig,
+) -> Result<AudioFeatures> {
+    let (frames, representative_spectrum) = stft_frame_features(audio_data, sample_rate, &config)?;
+
+    let spectral_centroid = mean(frames.iter().map(|frame| frame.spectral_centroid));
+    let spectral_rolloff = mean(frames.iter().map(|frame| frame.spectral_rolloff));
+    let spectral_flatness = mean(frames.iter().map(|frame| frame.spectral_flatness));
+    let zero_crossing_rate = mean(frames.iter().map(|frame| frame.zero_crossing_rate));
+    let mfcc = mean_mfcc(&frames);
+
+    // Music-similarity descriptors (bliss-rs style), windowed/hopped independently of
+    // the STFT frames above and averaged into fixed-length summaries
+    const DEFAULT_WINDOW_SIZE: usize = 4096;
+    const DEFAULT_HOP_SIZE: usize = 2048;
+    let (tempo_bpm, chroma, loudness) =
+        calculate_similarity_descriptors(audio_data, sample_rate, DEFAULT_WINDOW_SIZE, DEFAULT_HOP_SIZE);
+
+    // Per-frame timbral descriptors, summarized by mean/variance across frames for
+    // `AudioFeatures::to_vector()`, since a single whole-buffer value per song is too
+    // coarse a similarity signal
+    let frame_summary =
+        calculate_frame_descriptor_summary(audio_data, sample_rate, DEFAULT_WINDOW_SIZE, DEFAULT_HOP_SIZE);
+
     Ok(AudioFeatures {
         spectral_centroid,
         spectral_rolloff,
+        spectral_flatness,
         mfcc,
         zero_crossing_rate,
-        spectrum: spectrum.to_vec(),
+        spectrum: representative_spectrum,
+        tempo_bpm,
+        chroma,
+        loudness,
+        centroid_mean: frame_summary.centroid_mean,
+        centroid_variance: frame_summary.centroid_variance,
+        rolloff_mean: frame_summary.rolloff_mean,
+        rolloff_variance: frame_summary.rolloff_variance,
+        zcr_mean: frame_summary.zcr_mean,
+        zcr_variance: frame_summary.zcr_variance,
+        flatness_mean: frame_summary.flatness_mean,
+        flatness_variance: frame_summary.flatness_variance,
+        frames,
     })
 }
 
+/// Slide `config.window_size`-sample frames (hopping by `config.hop_size`) across
+/// `audio_data`, zero-padding the final partial frame rather than dropping it, and
+/// evaluate each frame's spectral/temporal descriptors. Also returns the first
+/// frame's magnitude spectrum as a representative snapshot for `AudioFeatures::spectrum`.
+fn stft_frame_features(
+    audio_data: &[f32],
+    sample_rate: u32,
+    config: &StftConfig,
+) -> Result<(Vec<FrameFeatures>, Vec<f32>)> {
+    let mut frames = Vec::new();
+    let mut representative_spectrum = Vec::new();
+
+    if audio_data.is_empty() {
+        return Ok((frames, representative_spectrum));
+    }
+
+    let hop_size = config.hop_size.max(1);
+    let mut frame_start = 0;
+
+    loop {
+        let frame_end = (frame_start + config.window_size).min(audio_data.len());
+
+        let mut frame = audio_data[frame_start..frame_end].to_vec();
+        frame.resize(config.window_size, 0.0);
+
+        let windowed = apply_window(&frame, config.window_type);
+        let spectrum = compute_fft(&windowed)?;
+
+        if representative_spectrum.is_empty() {
+            representative_spectrum = spectrum.to_vec();
+        }
+
+        frames.push(FrameFeatures {
+            spectral_centroid: calculate_spectral_centroid(&spectrum, sample_rate),
+            spectral_rolloff: calculate_spectral_rolloff(&spectrum, sample_rate),
+            spectral_flatness: calculate_spectral_flatness(&spectrum),
+            zero_crossing_rate: calculate_zero_crossing_rate(&audio_data[frame_start..frame_end]),
+            mfcc: calculate_mfcc(&spectrum, sample_rate)?,
+        });
+
+        if frame_end == audio_data.len() {
+            break;
+        }
+        frame_start += hop_size;
+    }
+
+    Ok((frames, representative_spectrum))
+}
+
+/// Mean of an `f32` iterator; `0.0` for an empty iterator.
+fn mean(values: impl Iterator<Item = f32> + Clone) -> f32 {
+    let count = values.clone().count();
+    if count == 0 {
+        return 0.0;
+    }
+
+    values.sum::<f32>() / count as f32
+}
+
+/// Element-wise mean of every frame's (fixed-length) MFCC vector; empty if there are no frames.
+fn mean_mfcc(frames: &[FrameFeatures]) -> Vec<f32> {
+    let Some(first) = frames.first() else {
+        return Vec::new();
+    };
+
+    let mut sums = vec![0.0f32; first.mfcc.len()];
+    for frame in frames {
+        for (sum, &value) in sums.iter_mut().zip(frame.mfcc.iter()) {
+            *sum += value;
+        }
+    }
+
+    for sum in sums.iter_mut() {
+        *sum /= frames.len() as f32;
+    }
+
+    sums
+}
+
+/// Mean/variance of each per-frame timbral descriptor, across every STFT frame of a
+/// track (bliss-rs-style coarse timbral summarization), feeding `AudioFeatures::to_vector()`
+struct FrameDescriptorSummary {
+    centroid_mean: f32,
+    centroid_variance: f32,
+    rolloff_mean: f32,
+    rolloff_variance: f32,
+    zcr_mean: f32,
+    zcr_variance: f32,
+    flatness_mean: f32,
+    flatness_variance: f32,
+}
+
+/// Frame `audio_data` into `window_size`/`hop_size` windows and summarize each of
+/// spectral centroid, rolloff, zero-crossing-rate, and flatness by mean/variance
+/// across frames
+fn calculate_frame_descriptor_summary(
+    audio_data: &[f32],
+    sample_rate: u32,
+    window_size: usize,
+    hop_size: usize,
+) -> FrameDescriptorSummary {
+    let mut centroids = Vec::new();
+    let mut rolloffs = Vec::new();
+    let mut zcrs = Vec::new();
+    let mut flatnesses = Vec::new();
+
+    let mut frame_start = 0;
+    while frame_start + window_size <= audio_data.len() {
+        let frame = &audio_data[frame_start..frame_start + window_size];
+        let windowed = apply_window(frame, WindowType::Hamming);
+        if let Ok(spectrum) = compute_fft(&windowed) {
+            centroids.push(calculate_spectral_centroid(&spectrum, sample_rate));
+            rolloffs.push(calculate_spectral_rolloff(&spectrum, sample_rate));
+            flatnesses.push(calculate_spectral_flatness(&spectrum));
+        }
+        zcrs.push(calculate_zero_crossing_rate(frame));
+
+        frame_start += hop_size;
+    }
+
+    let (centroid_mean, centroid_variance) = mean_and_variance(&centroids);
+    let (rolloff_mean, rolloff_variance) = mean_and_variance(&rolloffs);
+    let (zcr_mean, zcr_variance) = mean_and_variance(&zcrs);
+    let (flatness_mean, flatness_variance) = mean_and_variance(&flatnesses);
+
+    FrameDescriptorSummary {
+        centroid_mean,
+        centroid_variance,
+        rolloff_mean,
+        rolloff_variance,
+        zcr_mean,
+        zcr_variance,
+        flatness_mean,
+        flatness_variance,
+    }
+}
+
+fn mean_and_variance(values: &[f32]) -> (f32, f32) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    let mean = values.iter().sum::<f32>() / values.len() as f32;
+    let variance = values.iter().map(|&v| (v - mean).powi(2)).sum::<f32>() / values.len() as f32;
+
+    (mean, variance)
+}
+
+/// Compute tempo (BPM), a 12-bin chroma profile, and integrated loudness by framing
+/// `audio_data` into `window_size`/`hop_size` windows, matching the windowing already
+/// used elsewhere in the fingerprinting pipeline
+fn calculate_similarity_descriptors(
+    audio_data: &[f32],
+    sample_rate: u32,
+    window_size: usize,
+    hop_size: usize,
+) -> (f32, Vec<f32>, f32) {
+    let mut chroma = vec![0.0f32; 12];
+    let mut onset_envelope = Vec::new();
+    let mut previous_energy = 0.0f32;
+
+    if audio_data.len() >= window_size {
+        let mut frame_start = 0;
+        while frame_start + window_size <= audio_data.len() {
+            let frame = &audio_data[frame_start..frame_start + window_size];
+            let windowed = apply_window(frame, WindowType::Hamming);
+            if let Ok(frame_spectrum) = compute_fft(&windowed) {
+                accumulate_chroma(&frame_spectrum, sample_rate, &mut chroma);
+
+                let energy: f32 = frame_spectrum.iter().map(|&x| x * x).sum();
+                // Spectral-flux onset strength: positive energy increase between frames
+                onset_envelope.push((energy - previous_energy).max(0.0));
+                previous_energy = energy;
+            }
+
+            frame_start += hop_size;
+        }
+    }
+
+    normalize_l1(&mut chroma);
+
+    let frame_rate = sample_rate as f32 / hop_size as f32;
+    let tempo_bpm = estimate_tempo_bpm(&onset_envelope, frame_rate);
+    let loudness = calculate_loudness(audio_data);
+
+    (tempo_bpm, chroma, loudness)
+}
+
+/// Accumulate a frame's spectral energy into a 12-bin pitch-class (chroma) profile,
+/// mapping each FFT bin's frequency to the nearest pitch class via
+/// `note = 12 * log2(f / 440) + 69 mod 12`
+fn accumulate_chroma(spectrum: &Array1<f32>, sample_rate: u32, chroma: &mut [f32]) {
+    for (bin, &magnitude) in spectrum.iter().enumerate().skip(1) {
+        let frequency = bin as f32 * sample_rate as f32 / (2.0 * spectrum.len() as f32);
+        if frequency <= 0.0 {
+            continue;
+        }
+
+        let note = 12.0 * (frequency / 440.0).log2() + 69.0;
+        let pitch_class = (((note.round() as i64) % 12 + 12) % 12) as usize;
+        chroma[pitch_class] += magnitude;
+    }
+}
+
+/// Estimate tempo in BPM from a spectral-flux onset-strength envelope via
+/// autocorrelation peak picking over a plausible 60-200 BPM range
+fn estimate_tempo_bpm(onset_envelope: &[f32], frame_rate: f32) -> f32 {
+    if onset_envelope.len() < 2 || frame_rate <= 0.0 {
+        return 0.0;
+    }
+
+    let min_bpm = 60.0;
+    let max_bpm = 200.0;
+
+    let min_lag = (60.0 / max_bpm * frame_rate).max(1.0) as usize;
+    let max_lag = ((60.0 / min_bpm * frame_rate) as usize)
+        .min(onset_envelope.len() / 2)
+        .max(min_lag + 1);
+
+    let mut best_lag = min_lag;
+    let mut best_correlation = 0.0;
+
+    for lag in min_lag..max_lag {
+        let correlation: f32 = (0..onset_envelope.len() - lag)
+            .map(|i| onset_envelope[i] * onset_envelope[i + lag])
+            .sum();
+
+        if correlation > best_correlation {
+            best_correlation = correlation;
+            best_lag = lag;
+        }
+    }
+
+    if best_correlation <= 0.0 {
+        return 0.0;
+    }
+
+    60.0 * frame_rate / best_lag as f32
+}
+
+/// Integrated loudness measure in decibels, derived from the RMS level of the signal
+fn calculate_loudness(audio_data: &[f32]) -> f32 {
+    if audio_data.is_empty() {
+        return f32::NEG_INFINITY;
+    }
+
+    let rms = (audio_data.iter().map(|&x| x * x).sum::<f32>() / audio_data.len() as f32).sqrt();
+    20.0 * rms.max(1e-10).log10()
+}
+
+fn normalize_l1(values: &mut [f32]) {
+    let sum: f32 = values.iter().sum();
+    if sum > 0.0 {
+        for v in values.iter_mut() {
+            *v /= sum;
+        }
+    }
+}
+
 /// Compute FFT using SIMD optimizations
 fn compute_fft(audio_data: &[f32]) -> Result<Array1<f32>> {
     use rustfft::{FftPlanner, num_complex::Complex};
@@ -230,6 +645,29 @@ fn calculate_spectral_rolloff(spectrum: &Array1<f32>, sample_rate: u32) -> f32 {
     sample_rate as f32 / 2.0
 }
 
+/// Spectral flatness: ratio of the geometric mean to the arithmetic mean of the
+/// magnitude spectrum, in `[0, 1]` (near 1 = noise-like/flat spectrum, near 0 =
+/// tonal/peaky spectrum). The geometric mean is computed in log-space
+/// (`exp(mean(ln(x + eps)))`) over nonzero bins to avoid underflow on quiet bins.
+fn calculate_spectral_flatness(spectrum: &Array1<f32>) -> f32 {
+    const EPSILON: f32 = 1e-10;
+
+    let nonzero: Vec<f32> = spectrum.iter().copied().filter(|&x| x > 0.0).collect();
+    if nonzero.is_empty() {
+        return 0.0;
+    }
+
+    let log_mean: f32 = nonzero.iter().map(|&x| (x + EPSILON).ln()).sum::<f32>() / nonzero.len() as f32;
+    let geometric_mean = log_mean.exp();
+    let arithmetic_mean: f32 = nonzero.iter().sum::<f32>() / nonzero.len() as f32;
+
+    if arithmetic_mean > 0.0 {
+        geometric_mean / arithmetic_mean
+    } else {
+        0.0
+    }
+}
+
 /// Calculate MFCC (Mel-frequency cepstral coefficients)
 fn calculate_mfcc(spectrum: &Array1<f32>, sample_rate: u32) -> Result<Vec<f32>> {
     // Simplified MFCC calculation
@@ -303,13 +741,85 @@ fn calculate_zero_crossing_rate(audio_data: &[f32]) -> f32 {
     crossings as f32 / (audio_data.len() - 1) as f32
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct AudioFeatures {
     pub spectral_centroid: f32,
     pub spectral_rolloff: f32,
+    /// Mean over STFT frames of each frame's geometric-to-arithmetic-mean ratio of
+    /// its magnitude spectrum; near 1 for noise-like audio, near 0 for tonal audio
+    pub spectral_flatness: f32,
     pub mfcc: Vec<f32>,
     pub zero_crossing_rate: f32,
     pub spectrum: Vec<f32>,
+    /// Estimated tempo in beats per minute
+    pub tempo_bpm: f32,
+    /// 12-bin pitch-class (chroma) energy profile, L1-normalized
+    pub chroma: Vec<f32>,
+    /// Integrated loudness in decibels, derived from the signal's RMS level
+    pub loudness: f32,
+    /// Mean/variance of per-frame spectral centroid, rolloff, zero-crossing-rate,
+    /// and flatness across the track (see `calculate_frame_descriptor_summary`),
+    /// feeding `to_vector()`
+    pub centroid_mean: f32,
+    pub centroid_variance: f32,
+    pub rolloff_mean: f32,
+    pub rolloff_variance: f32,
+    pub zcr_mean: f32,
+    pub zcr_variance: f32,
+    pub flatness_mean: f32,
+    pub flatness_variance: f32,
+    /// Per-frame descriptor series from `extract_features_with_config`'s sliding-window
+    /// STFT, in frame order; `spectral_centroid`, `spectral_rolloff`, `spectral_flatness`,
+    /// `zero_crossing_rate` and `mfcc` above are this series' mean.
+    pub frames: Vec<FrameFeatures>,
+}
+
+/// Stable ordering of slots in `AudioFeatures::to_vector()`'s output, so the
+/// similarity search and playlist code agree on what each index means even as
+/// descriptors are added or reordered in the future
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(usize)]
+pub enum AnalysisIndex {
+    CentroidMean = 0,
+    CentroidVariance = 1,
+    RolloffMean = 2,
+    RolloffVariance = 3,
+    ZcrMean = 4,
+    ZcrVariance = 5,
+    FlatnessMean = 6,
+    FlatnessVariance = 7,
+}
+
+impl AnalysisIndex {
+    /// Total number of slots in the vector produced by `AudioFeatures::to_vector()`
+    pub const COUNT: usize = 8;
+}
+
+impl AudioFeatures {
+    /// Fixed-length, `[-1, 1]`-normalized descriptor vector combining this track's
+    /// per-frame timbral summary (see `AnalysisIndex` for slot ordering), suitable
+    /// for feeding similarity search and playlist-generation features.
+    ///
+    /// Each raw descriptor is squashed into `(-1, 1)` via `x / (1 + |x|)` rather
+    /// than scaled against corpus-wide min/max statistics, which aren't available
+    /// at the point a single track's features are extracted.
+    pub fn to_vector(&self) -> Vec<f32> {
+        let mut vector = vec![0.0; AnalysisIndex::COUNT];
+        vector[AnalysisIndex::CentroidMean as usize] = self.centroid_mean;
+        vector[AnalysisIndex::CentroidVariance as usize] = self.centroid_variance;
+        vector[AnalysisIndex::RolloffMean as usize] = self.rolloff_mean;
+        vector[AnalysisIndex::RolloffVariance as usize] = self.rolloff_variance;
+        vector[AnalysisIndex::ZcrMean as usize] = self.zcr_mean;
+        vector[AnalysisIndex::ZcrVariance as usize] = self.zcr_variance;
+        vector[AnalysisIndex::FlatnessMean as usize] = self.flatness_mean;
+        vector[AnalysisIndex::FlatnessVariance as usize] = self.flatness_variance;
+
+        for value in vector.iter_mut() {
+            *value /= 1.0 + value.abs();
+        }
+
+        vector
+    }
 }
 
 #[cfg(test)]
@@ -353,5 +863,115 @@ mod tests {
         let features = features.unwrap();
         assert!(!features.mfcc.is_empty());
         assert!(features.spectral_centroid > 0.0);
+        assert_eq!(features.chroma.len(), 12);
+        assert!(features.tempo_bpm >= 0.0);
+        assert!(features.loudness.is_finite());
+        assert!(features.spectral_flatness >= 0.0 && features.spectral_flatness <= 1.0);
+        assert!(!features.frames.is_empty());
+    }
+
+    #[test]
+    fn test_extract_features_with_config_zero_pads_final_partial_frame() {
+        let sample_rate = 44100;
+        let config = StftConfig {
+            window_size: 512,
+            hop_size: 512,
+            window_type: WindowType::Hamming,
+        };
+
+        // 1.5 frames' worth of samples: the second frame is a partial frame that
+        // must be zero-padded rather than dropped.
+        let audio_data = vec![0.2f32; 768];
+
+        let features = extract_features_with_config(&audio_data, sample_rate, config).unwrap();
+        assert_eq!(features.frames.len(), 2);
+        assert_eq!(features.frames[1].mfcc.len(), features.frames[0].mfcc.len());
+    }
+
+    #[test]
+    fn test_extract_features_with_config_scalar_fields_are_frame_means() {
+        let sample_rate = 44100;
+        let config = StftConfig::default();
+        let audio_data: Vec<f32> = (0..8192)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / sample_rate as f32).sin())
+            .collect();
+
+        let features = extract_features_with_config(&audio_data, sample_rate, config).unwrap();
+
+        let expected_centroid = features.frames.iter().map(|frame| frame.spectral_centroid).sum::<f32>()
+            / features.frames.len() as f32;
+        assert!((features.spectral_centroid - expected_centroid).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_to_vector_has_fixed_length_and_is_bounded() {
+        let sample_rate = 44100;
+        let audio_data: Vec<f32> = (0..sample_rate)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / sample_rate as f32).sin())
+            .collect();
+
+        let features = extract_features(&audio_data, sample_rate).unwrap();
+        let vector = features.to_vector();
+
+        assert_eq!(vector.len(), AnalysisIndex::COUNT);
+        assert!(vector.iter().all(|&x| x > -1.0 && x < 1.0));
+    }
+
+    #[test]
+    fn test_spectral_flatness_is_higher_for_noise_than_a_pure_tone() {
+        let sample_rate = 44100;
+        let tone: Vec<f32> = (0..4096)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / sample_rate as f32).sin())
+            .collect();
+
+        let mut seed: u32 = 12345;
+        let noise: Vec<f32> = (0..4096)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (seed as f32 / u32::MAX as f32) * 2.0 - 1.0
+            })
+            .collect();
+
+        let tone_spectrum = compute_fft(&apply_window(&tone, WindowType::Hamming)).unwrap();
+        let noise_spectrum = compute_fft(&apply_window(&noise, WindowType::Hamming)).unwrap();
+
+        assert!(calculate_spectral_flatness(&noise_spectrum) > calculate_spectral_flatness(&tone_spectrum));
+    }
+
+    #[test]
+    fn test_reduce_noise_preserves_length() {
+        let sample_rate = 44100;
+        let audio_data: Vec<f32> = (0..sample_rate)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / sample_rate as f32).sin())
+            .collect();
+
+        let denoised = reduce_noise(&audio_data);
+        assert_eq!(denoised.len(), audio_data.len());
+    }
+
+    #[test]
+    fn test_reduce_noise_attenuates_noise_matching_the_estimated_profile() {
+        let mut seed: u32 = 42;
+        let mut next_noise = || {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (seed as f32 / u32::MAX as f32) * 2.0 - 1.0
+        };
+
+        // Noise-only signal throughout: the first frames (used to estimate the
+        // noise profile) look like every later frame, so subtraction should drive
+        // the resynthesized energy well below the original.
+        let audio_data: Vec<f32> = (0..8192).map(|_| next_noise() * 0.1).collect();
+
+        let denoised = reduce_noise(&audio_data);
+        let input_energy: f32 = audio_data.iter().map(|&x| x * x).sum();
+        let output_energy: f32 = denoised.iter().map(|&x| x * x).sum();
+
+        assert!(output_energy < input_energy);
+    }
+
+    #[test]
+    fn test_reduce_noise_passes_short_audio_through_unchanged() {
+        let audio_data = vec![0.1, -0.2, 0.3];
+        assert_eq!(reduce_noise(&audio_data), audio_data);
     }
 }
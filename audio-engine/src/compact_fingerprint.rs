@@ -0,0 +1,112 @@
+//! Compact 128-bit fingerprint for cheap indexing, in the style of
+//! `rustc_data_structures`' own `Fingerprint`: two `u64` lanes that can be
+//! folded together and serialized endian-stably.
+
+use std::fmt;
+
+/// A compact 128-bit fingerprint usable as a cheap index key. The two `u64`
+/// lanes are mixed independently by `combine` (a multiply-add on the first
+/// lane, an XOR-rotate on the second), so folding several fingerprints
+/// together is deterministic but sensitive to the order they're combined in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CompactFingerprint(u64, u64);
+
+/// Odd multiplicative constant used by `combine`'s first lane; the same
+/// golden-ratio-derived constant Fibonacci/multiplicative hashing commonly
+/// uses, chosen for good bit dispersion
+const COMBINE_MULTIPLIER: u64 = 0x9e3779b97f4a7c15;
+/// Rotation applied to the second lane before XOR-ing in `combine`
+const COMBINE_ROTATION: u32 = 17;
+
+impl CompactFingerprint {
+    /// Build a fingerprint from a single hash, copying it into both lanes
+    pub fn from_smaller_hash(hash: u64) -> Self {
+        CompactFingerprint(hash, hash)
+    }
+
+    /// The two `u64` lanes making up this fingerprint
+    pub fn as_value(&self) -> (u64, u64) {
+        (self.0, self.1)
+    }
+
+    /// Mix `self` and `other` into a new fingerprint: a wrapping
+    /// multiply-add on the first lane, and an XOR-rotate on the second.
+    pub fn combine(self, other: CompactFingerprint) -> Self {
+        let lane0 = self.0.wrapping_mul(COMBINE_MULTIPLIER).wrapping_add(other.0);
+        let lane1 = self.1.rotate_left(COMBINE_ROTATION) ^ other.1;
+
+        CompactFingerprint(lane0, lane1)
+    }
+
+    /// Serialize to 16 bytes, each `u64` lane little-endian, so a fingerprint
+    /// survives a round-trip across machines with different endianness
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        bytes[..8].copy_from_slice(&self.0.to_le_bytes());
+        bytes[8..].copy_from_slice(&self.1.to_le_bytes());
+        bytes
+    }
+
+    /// Deserialize from 16 little-endian bytes produced by `to_bytes`
+    pub fn from_bytes(bytes: [u8; 16]) -> Self {
+        let lane0 = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+        let lane1 = u64::from_le_bytes(bytes[8..].try_into().unwrap());
+
+        CompactFingerprint(lane0, lane1)
+    }
+}
+
+impl fmt::Display for CompactFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:x}-{:x}", self.0, self.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_smaller_hash_copies_into_both_lanes() {
+        let fingerprint = CompactFingerprint::from_smaller_hash(0xdead_beef);
+        assert_eq!(fingerprint.as_value(), (0xdead_beef, 0xdead_beef));
+    }
+
+    #[test]
+    fn test_combine_is_deterministic() {
+        let a = CompactFingerprint::from_smaller_hash(1);
+        let b = CompactFingerprint::from_smaller_hash(2);
+
+        assert_eq!(a.combine(b), a.combine(b));
+    }
+
+    #[test]
+    fn test_combine_is_order_sensitive() {
+        let a = CompactFingerprint::from_smaller_hash(1);
+        let b = CompactFingerprint::from_smaller_hash(2);
+
+        assert_ne!(a.combine(b), b.combine(a));
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let fingerprint = CompactFingerprint::from_smaller_hash(123).combine(CompactFingerprint::from_smaller_hash(456));
+        let bytes = fingerprint.to_bytes();
+
+        assert_eq!(CompactFingerprint::from_bytes(bytes), fingerprint);
+    }
+
+    #[test]
+    fn test_to_bytes_is_little_endian() {
+        let fingerprint = CompactFingerprint(0x0102_0304_0506_0708, 0);
+        let bytes = fingerprint.to_bytes();
+
+        assert_eq!(&bytes[..8], &[0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01]);
+    }
+
+    #[test]
+    fn test_display_formats_both_lanes_as_hex() {
+        let fingerprint = CompactFingerprint(0xff, 0x10);
+        assert_eq!(format!("{}", fingerprint), "ff-10");
+    }
+}
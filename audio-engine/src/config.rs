@@ -17,6 +17,8 @@ pub struct Config {
     pub recognition: RecognitionConfig,
     /// Server configuration
     pub server: ServerConfig,
+    /// Fingerprint similarity scoring configuration
+    pub similarity: SimilarityConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,6 +75,44 @@ pub struct ServerConfig {
     pub timeout: u64,
 }
 
+/// Tunable parameters for `similarity::calculate_similarity_with`, so
+/// recognition can be retuned for noisy microphone captures vs. clean files
+/// without a recompile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarityConfig {
+    /// Weight of the hash-based (Jaccard) similarity score in the blend
+    pub hash_weight: f32,
+    /// Weight of the peak-based similarity score in the blend
+    pub peak_weight: f32,
+    /// Weight of the spectral-histogram similarity score in the blend
+    pub spectral_weight: f32,
+    /// Maximum frequency difference (Hz) for two peaks to be considered a match
+    pub freq_tolerance: f32,
+    /// Maximum time difference (s) for two peaks to be considered a match
+    pub time_tolerance: f32,
+    /// Number of frequency bins used when building the spectral histogram
+    pub spectral_freq_bins: usize,
+    /// Number of time bins used when building the spectral histogram
+    pub spectral_time_bins: usize,
+    /// Minimum combined similarity `calculate_batch_similarity_with` keeps a candidate at
+    pub batch_filter_cutoff: f32,
+}
+
+impl Default for SimilarityConfig {
+    fn default() -> Self {
+        Self {
+            hash_weight: 0.5,
+            peak_weight: 0.3,
+            spectral_weight: 0.2,
+            freq_tolerance: 50.0,
+            time_tolerance: 0.1,
+            spectral_freq_bins: 20,
+            spectral_time_bins: 10,
+            batch_filter_cutoff: 0.1,
+        }
+    }
+}
+
 impl Config {
     /// Load configuration from environment variables and config files
     pub fn load() -> Result<Self, config::ConfigError> {
@@ -156,6 +196,7 @@ impl Config {
                 max_request_size: 10 * 1024 * 1024, // 10MB
                 timeout: 30,
             },
+            similarity: SimilarityConfig::default(),
         }
     }
 }
@@ -192,5 +233,11 @@ mod tests {
         assert!(config.server.port > 0);
         assert!(config.server.workers > 0);
         assert!(config.server.max_request_size > 0);
+
+        // Validate similarity config
+        let weight_sum = config.similarity.hash_weight
+            + config.similarity.peak_weight
+            + config.similarity.spectral_weight;
+        assert!((weight_sum - 1.0).abs() < 1e-6);
     }
 }
@@ -0,0 +1,171 @@
+//! Parser for CUE sheets, so a single audio file plus a `.cue` sidecar
+//! describing an album rip's track boundaries can be split into per-track
+//! fingerprints instead of requiring callers to pre-split the audio.
+
+use anyhow::{anyhow, Result};
+
+/// Number of CUE-sheet index frames per second, per the Red Book CD-DA spec
+const CUE_FRAMES_PER_SECOND: u32 = 75;
+
+/// A single track parsed from a CUE sheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct CueTrack {
+    /// 1-based track number, as declared by `TRACK nn AUDIO`
+    pub number: u32,
+    pub title: String,
+    /// Per-track `PERFORMER`, falling back to the sheet's global performer if absent
+    pub performer: String,
+    /// `INDEX 01` start offset, in samples at `sample_rate`
+    pub start_sample: u64,
+}
+
+/// A parsed CUE sheet: the global `TITLE`/`PERFORMER` (album name and album
+/// artist) plus every `TRACK`'s start offset
+#[derive(Debug, Clone, PartialEq)]
+pub struct CueSheet {
+    pub album: Option<String>,
+    pub performer: Option<String>,
+    pub tracks: Vec<CueTrack>,
+}
+
+impl CueSheet {
+    /// Parse CUE-sheet text, converting each track's `INDEX 01 mm:ss:ff` into
+    /// a start offset in samples at `sample_rate`.
+    ///
+    /// Only the fields this crate needs are recognized (`TITLE`, `PERFORMER`,
+    /// `TRACK`, `INDEX 01`); everything else (`FILE`, `INDEX 00` pre-gaps,
+    /// `REM` comments, `FLAGS`, ...) is ignored rather than rejected, since a
+    /// real-world CUE sheet commonly carries fields this importer has no use for.
+    pub fn parse(text: &str, sample_rate: u32) -> Result<Self> {
+        let mut album: Option<String> = None;
+        let mut global_performer: Option<String> = None;
+        let mut tracks: Vec<CueTrack> = Vec::new();
+
+        let mut current_number: Option<u32> = None;
+        let mut current_title: Option<String> = None;
+        let mut current_performer: Option<String> = None;
+
+        for raw_line in text.lines() {
+            let line = raw_line.trim();
+
+            if let Some(rest) = line.strip_prefix("TITLE ") {
+                let title = parse_quoted_field(rest);
+                if current_number.is_some() {
+                    current_title = Some(title);
+                } else {
+                    album = Some(title);
+                }
+            } else if let Some(rest) = line.strip_prefix("PERFORMER ") {
+                let performer = parse_quoted_field(rest);
+                if current_number.is_some() {
+                    current_performer = Some(performer);
+                } else {
+                    global_performer = Some(performer);
+                }
+            } else if let Some(rest) = line.strip_prefix("TRACK ") {
+                if let Some(previous_number) = current_number.take() {
+                    return Err(anyhow!(
+                        "TRACK {} has no INDEX 01 before TRACK {} begins",
+                        previous_number,
+                        rest
+                    ));
+                }
+
+                let number_token = rest.split_whitespace().next().ok_or_else(|| anyhow!("malformed TRACK line: {line}"))?;
+                current_number = Some(number_token.parse().map_err(|_| anyhow!("invalid track number: {number_token}"))?);
+                current_title = None;
+                current_performer = None;
+            } else if let Some(rest) = line.strip_prefix("INDEX 01 ") {
+                let number = current_number.take().ok_or_else(|| anyhow!("INDEX 01 outside of a TRACK: {line}"))?;
+                let start_sample = parse_cue_timestamp(rest.trim())? * sample_rate as u64 / CUE_FRAMES_PER_SECOND as u64;
+
+                tracks.push(CueTrack {
+                    number,
+                    title: current_title.take().unwrap_or_default(),
+                    performer: current_performer.take().or_else(|| global_performer.clone()).unwrap_or_default(),
+                    start_sample,
+                });
+            }
+        }
+
+        if tracks.is_empty() {
+            return Err(anyhow!("CUE sheet contains no tracks with an INDEX 01"));
+        }
+
+        Ok(CueSheet { album, performer: global_performer, tracks })
+    }
+}
+
+/// Strip the surrounding double quotes a CUE sheet wraps string fields in
+fn parse_quoted_field(field: &str) -> String {
+    field.trim().trim_matches('"').to_string()
+}
+
+/// Parse a CUE `mm:ss:ff` timestamp into a frame count (at `CUE_FRAMES_PER_SECOND`)
+fn parse_cue_timestamp(timestamp: &str) -> Result<u64> {
+    let parts: Vec<&str> = timestamp.split(':').collect();
+    if parts.len() != 3 {
+        return Err(anyhow!("expected mm:ss:ff, got: {timestamp}"));
+    }
+
+    let minutes: u64 = parts[0].parse().map_err(|_| anyhow!("invalid minutes in timestamp: {timestamp}"))?;
+    let seconds: u64 = parts[1].parse().map_err(|_| anyhow!("invalid seconds in timestamp: {timestamp}"))?;
+    let frames: u64 = parts[2].parse().map_err(|_| anyhow!("invalid frames in timestamp: {timestamp}"))?;
+
+    Ok((minutes * 60 + seconds) * CUE_FRAMES_PER_SECOND as u64 + frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_CUE: &str = r#"
+REM GENRE Rock
+PERFORMER "Example Artist"
+TITLE "Example Album"
+FILE "album.wav" WAVE
+  TRACK 01 AUDIO
+    TITLE "First Song"
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE "Second Song"
+    PERFORMER "Featured Artist"
+    INDEX 00 01:59:50
+    INDEX 01 02:00:00
+"#;
+
+    #[test]
+    fn test_parse_extracts_album_and_performer() {
+        let sheet = CueSheet::parse(SAMPLE_CUE, 44100).unwrap();
+        assert_eq!(sheet.album.as_deref(), Some("Example Album"));
+        assert_eq!(sheet.performer.as_deref(), Some("Example Artist"));
+    }
+
+    #[test]
+    fn test_parse_extracts_each_track() {
+        let sheet = CueSheet::parse(SAMPLE_CUE, 44100).unwrap();
+        assert_eq!(sheet.tracks.len(), 2);
+        assert_eq!(sheet.tracks[0].title, "First Song");
+        assert_eq!(sheet.tracks[0].performer, "Example Artist");
+        assert_eq!(sheet.tracks[1].title, "Second Song");
+        assert_eq!(sheet.tracks[1].performer, "Featured Artist");
+    }
+
+    #[test]
+    fn test_parse_converts_index_01_to_samples_ignoring_index_00() {
+        let sheet = CueSheet::parse(SAMPLE_CUE, 44100).unwrap();
+        assert_eq!(sheet.tracks[0].start_sample, 0);
+        // 2:00:00 = 120 seconds at 44100 samples/sec, the INDEX 00 pre-gap is ignored
+        assert_eq!(sheet.tracks[1].start_sample, 120 * 44100);
+    }
+
+    #[test]
+    fn test_parse_rejects_sheet_with_no_tracks() {
+        assert!(CueSheet::parse("TITLE \"Empty\"", 44100).is_err());
+    }
+
+    #[test]
+    fn test_parse_cue_timestamp_counts_frames() {
+        assert_eq!(parse_cue_timestamp("00:01:37").unwrap(), 75 + 37);
+    }
+}
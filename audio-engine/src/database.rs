@@ -1,12 +1,21 @@
 //! Database operations for audio fingerprints and songs
 
+use crate::audio::{self, AudioFeatures};
+use crate::distance::DistanceMetric;
+use crate::embedding::AudioEmbedding;
 use crate::fingerprint::Fingerprint;
 use crate::error::AudioEngineError;
+use pgvector::Vector;
 use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Row};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
+/// Dimensionality of the acoustic embedding stored in `songs.embedding` and
+/// matched by `search_similar`'s `<=>` cosine-distance query. Must match the
+/// `vector(N)` column width declared in the `add_song_embeddings` migration.
+const EMBEDDING_DIMENSIONS: usize = 128;
+
 /// Song information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Song {
@@ -42,6 +51,14 @@ pub struct MatchedSegment {
     pub confidence: f32,
 }
 
+/// A song returned by `Database::search_similar`, paired with its raw
+/// pgvector cosine distance from the query so callers can threshold it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarSong {
+    pub song: Song,
+    pub distance: f32,
+}
+
 /// Database operations
 pub struct Database {
     pool: PgPool,
@@ -64,14 +81,25 @@ impl Database {
     }
     
     /// Add new song to database
-    pub async fn add_song(&self, song: Song, fingerprint: Fingerprint) -> Result<(), AudioEngineError> {
+    ///
+    /// `features` is the caller's already-extracted perceptual descriptor for this
+    /// track; its `to_vector()` is stored in `songs.analysis_vector` so
+    /// `generate_playlist`/`closest_to_seed` can compare this song against others
+    /// without re-extracting features from raw audio on every query.
+    pub async fn add_song(&self, song: Song, fingerprint: Fingerprint, features: &AudioFeatures) -> Result<(), AudioEngineError> {
         let mut tx = self.pool.begin().await?;
-        
+
+        // Derive the same fixed-length acoustic embedding `search_similar` later
+        // compares query fingerprints against, so content-based retrieval works
+        // as soon as a song is inserted.
+        let embedding = Vector::from(AudioEmbedding::from_fingerprint(&fingerprint, EMBEDDING_DIMENSIONS));
+        let analysis_vector = features.to_vector();
+
         // Insert song
         let song_id = sqlx::query!(
             r#"
-            INSERT INTO songs (id, title, artist, album, language, genre, duration, release_year, audio_url, artwork_url, popularity_score)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            INSERT INTO songs (id, title, artist, album, language, genre, duration, release_year, audio_url, artwork_url, popularity_score, embedding, analysis_vector)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
             RETURNING id
             "#,
             song.id,
@@ -84,7 +112,9 @@ impl Database {
             song.release_year,
             song.audio_url,
             song.artwork_url,
-            song.popularity_score
+            song.popularity_score,
+            embedding as _,
+            &analysis_vector
         )
         .fetch_one(&mut *tx)
         .await?;
@@ -107,10 +137,79 @@ impl Database {
         
         // Cache the song
         self.cache_song(&song).await?;
-        
+
         Ok(())
     }
-    
+
+    /// Split an audio file into per-track songs using a CUE sheet, fingerprinting and
+    /// inserting each track via `add_song`.
+    ///
+    /// Decodes `audio_path` once and resamples to `config.sample_rate`, then slices the
+    /// PCM between consecutive `INDEX 01` offsets (the last track runs to EOF) so each
+    /// track is fingerprinted over its own time range rather than the whole album.
+    /// Returns each inserted `Song` alongside a `MatchedSegment` giving its start/end
+    /// within the source file, so recognitions against individual tracks can report
+    /// which track of the album they matched.
+    pub async fn add_album_from_cue(
+        &self,
+        audio_path: &std::path::Path,
+        cue_text: &str,
+        language: &str,
+        config: &crate::config::AudioConfig,
+    ) -> Result<Vec<(Song, MatchedSegment)>, AudioEngineError> {
+        let sheet = crate::cue::CueSheet::parse(cue_text, config.sample_rate)?;
+
+        let decoded = crate::decode::decode_file(audio_path)?;
+        let samples = decoded.resampled_to(config.sample_rate)?;
+
+        let mut inserted = Vec::with_capacity(sheet.tracks.len());
+
+        for (index, track) in sheet.tracks.iter().enumerate() {
+            let start_sample = (track.start_sample as usize).min(samples.len());
+            let end_sample = sheet
+                .tracks
+                .get(index + 1)
+                .map(|next_track| (next_track.start_sample as usize).min(samples.len()))
+                .unwrap_or(samples.len());
+
+            if start_sample >= end_sample {
+                continue;
+            }
+
+            let track_samples = &samples[start_sample..end_sample];
+            let fingerprint = Fingerprint::generate_with_config(track_samples, config)?;
+            let features = audio::extract_features(track_samples, config.sample_rate)?;
+
+            let now = Utc::now();
+            let song = Song {
+                id: Uuid::new_v4(),
+                title: track.title.clone(),
+                artist: track.performer.clone(),
+                album: sheet.album.clone(),
+                language: language.to_string(),
+                genre: None,
+                duration: Some((track_samples.len() as f32 / config.sample_rate as f32) as i32),
+                release_year: None,
+                audio_url: None,
+                artwork_url: None,
+                popularity_score: 0.0,
+                created_at: now,
+                updated_at: now,
+            };
+
+            let segment = MatchedSegment {
+                start_time: start_sample as f32 / config.sample_rate as f32,
+                end_time: end_sample as f32 / config.sample_rate as f32,
+                confidence: 1.0,
+            };
+
+            self.add_song(song.clone(), fingerprint, &features).await?;
+            inserted.push((song, segment));
+        }
+
+        Ok(inserted)
+    }
+
     /// Get song by ID
     pub async fn get_song(&self, song_id: &Uuid) -> Result<Option<Song>, AudioEngineError> {
         // Try cache first
@@ -156,42 +255,150 @@ impl Database {
         }
     }
     
-    /// Search for similar songs
-    pub async fn search_similar(&self, fingerprint: &Fingerprint, limit: usize) -> Result<Vec<Song>, AudioEngineError> {
-        // This is a simplified implementation
-        // In production, you would use a vector database like Pinecone or Weaviate
-        
+    /// Find songs acoustically similar to `fingerprint` via approximate nearest-neighbor
+    /// search over the `songs.embedding` pgvector column.
+    ///
+    /// Serializes the query fingerprint into the same embedding layout `add_song` stores
+    /// at insert time (`AudioEmbedding::from_fingerprint`, `EMBEDDING_DIMENSIONS` wide),
+    /// then ranks candidates by pgvector's `<=>` cosine-distance operator against the
+    /// `hnsw` index created in the `add_song_embeddings` migration. The raw
+    /// distance is returned alongside each song so callers can threshold it themselves.
+    pub async fn search_similar(&self, fingerprint: &Fingerprint, limit: usize) -> Result<Vec<SimilarSong>, AudioEngineError> {
+        let embedding = Vector::from(AudioEmbedding::from_fingerprint(fingerprint, EMBEDDING_DIMENSIONS));
+
         let rows = sqlx::query!(
             r#"
-            SELECT s.id, s.title, s.artist, s.album, s.language, s.genre, s.duration, s.release_year, s.audio_url, s.artwork_url, s.popularity_score, s.created_at, s.updated_at
+            SELECT s.id, s.title, s.artist, s.album, s.language, s.genre, s.duration, s.release_year, s.audio_url, s.artwork_url, s.popularity_score, s.created_at, s.updated_at,
+                   s.embedding <=> $1 AS "distance!"
             FROM songs s
-            ORDER BY s.popularity_score DESC
-            LIMIT $1
+            WHERE s.embedding IS NOT NULL
+            ORDER BY s.embedding <=> $1
+            LIMIT $2
             "#,
+            embedding as _,
             limit as i64
         )
         .fetch_all(&self.pool)
         .await?;
-        
-        let songs = rows.into_iter().map(|row| Song {
-            id: row.id,
-            title: row.title,
-            artist: row.artist,
-            album: row.album,
-            language: row.language,
-            genre: row.genre,
-            duration: row.duration,
-            release_year: row.release_year,
-            audio_url: row.audio_url,
-            artwork_url: row.artwork_url,
-            popularity_score: row.popularity_score,
-            created_at: row.created_at,
-            updated_at: row.updated_at,
-        }).collect();
-        
+
+        let songs = rows
+            .into_iter()
+            .map(|row| SimilarSong {
+                song: Song {
+                    id: row.id,
+                    title: row.title,
+                    artist: row.artist,
+                    album: row.album,
+                    language: row.language,
+                    genre: row.genre,
+                    duration: row.duration,
+                    release_year: row.release_year,
+                    audio_url: row.audio_url,
+                    artwork_url: row.artwork_url,
+                    popularity_score: row.popularity_score,
+                    created_at: row.created_at,
+                    updated_at: row.updated_at,
+                },
+                distance: row.distance as f32,
+            })
+            .collect();
+
         Ok(songs)
     }
-    
+
+    /// Fetch every song's id alongside its `analysis_vector`, for the in-memory
+    /// nearest-neighbor search in `generate_playlist`/`closest_to_seed`.
+    ///
+    /// The greedy playlist walk re-anchors to a different song at every step, so
+    /// unlike `search_similar` it can't be expressed as a single `ORDER BY`; songs
+    /// with no analysis vector yet (inserted before this column existed) are skipped.
+    async fn fetch_analysis_vectors(&self) -> Result<Vec<(Uuid, Vec<f32>)>, AudioEngineError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, analysis_vector AS "analysis_vector!: Vec<f32>"
+            FROM songs
+            WHERE analysis_vector IS NOT NULL
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| (row.id, row.analysis_vector)).collect())
+    }
+
+    /// Generate a playlist of up to `limit` songs starting at `seed`: at each step,
+    /// pick the not-yet-chosen song closest (under `metric`) to the *current* track
+    /// rather than the seed, so the sequence transitions smoothly from one song to
+    /// the next instead of jumping straight to whatever is nearest the start.
+    ///
+    /// Each returned `SimilarSong::distance` is the distance from the *previous*
+    /// track in the playlist (the seed itself is not included in the result).
+    pub async fn generate_playlist(&self, seed: &Uuid, limit: usize, metric: DistanceMetric) -> Result<Vec<SimilarSong>, AudioEngineError> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut candidates = self.fetch_analysis_vectors().await?;
+        let seed_index = candidates
+            .iter()
+            .position(|(id, _)| id == seed)
+            .ok_or_else(|| AudioEngineError::SongNotFound { song_id: seed.to_string() })?;
+        let (_, mut current_vector) = candidates.remove(seed_index);
+
+        let mut playlist = Vec::with_capacity(limit.min(candidates.len()));
+
+        while playlist.len() < limit && !candidates.is_empty() {
+            let (nearest_index, distance) = candidates
+                .iter()
+                .enumerate()
+                .map(|(index, (_, vector))| (index, metric.distance(&current_vector, vector)))
+                .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+                .expect("candidates is non-empty");
+
+            let (song_id, vector) = candidates.remove(nearest_index);
+            let song = self
+                .get_song(&song_id)
+                .await?
+                .ok_or_else(|| AudioEngineError::SongNotFound { song_id: song_id.to_string() })?;
+
+            playlist.push(SimilarSong { song, distance });
+            current_vector = vector;
+        }
+
+        Ok(playlist)
+    }
+
+    /// Simpler "top-k closest to seed" mode: unlike `generate_playlist`'s walk,
+    /// every result is compared directly against `seed` rather than the previous
+    /// pick, so the results read like a radius/similarity search around one track
+    /// instead of a smoothly transitioning sequence.
+    pub async fn closest_to_seed(&self, seed: &Uuid, limit: usize, metric: DistanceMetric) -> Result<Vec<SimilarSong>, AudioEngineError> {
+        let mut candidates = self.fetch_analysis_vectors().await?;
+        let seed_index = candidates
+            .iter()
+            .position(|(id, _)| id == seed)
+            .ok_or_else(|| AudioEngineError::SongNotFound { song_id: seed.to_string() })?;
+        let (_, seed_vector) = candidates.remove(seed_index);
+
+        let mut scored: Vec<(Uuid, f32)> = candidates
+            .into_iter()
+            .map(|(song_id, vector)| (song_id, metric.distance(&seed_vector, &vector)))
+            .collect();
+        scored.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(limit);
+
+        let mut results = Vec::with_capacity(scored.len());
+        for (song_id, distance) in scored {
+            let song = self
+                .get_song(&song_id)
+                .await?
+                .ok_or_else(|| AudioEngineError::SongNotFound { song_id: song_id.to_string() })?;
+            results.push(SimilarSong { song, distance });
+        }
+
+        Ok(results)
+    }
+
     /// Get fingerprint for a song
     pub async fn get_fingerprint(&self, song_id: &Uuid) -> Result<Fingerprint, AudioEngineError> {
         let row = sqlx::query!(
@@ -0,0 +1,129 @@
+//! Audio decoding front-end for ingesting real files (MP3/FLAC/WAV/OGG) into the
+//! fingerprinting pipeline, instead of requiring callers to hand in pre-decoded PCM.
+
+use anyhow::{anyhow, Result};
+use std::path::Path;
+use symphonia::core::audio::{SampleBuffer, SignalSpec};
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::audio::resample_audio;
+
+/// Decoded, downmixed-to-mono audio at its native sample rate
+#[derive(Debug, Clone)]
+pub struct DecodedAudio {
+    /// Mono PCM samples in `[-1.0, 1.0]`
+    pub samples: Vec<f32>,
+    /// Sample rate of `samples` as decoded from the source container
+    pub sample_rate: u32,
+}
+
+/// Decode an audio file from disk, demuxing/decoding whatever container+codec
+/// symphonia supports and downmixing to mono.
+pub fn decode_file(path: &Path) -> Result<DecodedAudio> {
+    let file = std::fs::File::open(path)?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    decode_mss(mss, hint)
+}
+
+/// Decode audio already held in memory, optionally hinting at the container
+/// extension (e.g. `"mp3"`, `"wav"`) to speed up format probing.
+pub fn decode_bytes(data: &[u8], extension_hint: Option<&str>) -> Result<DecodedAudio> {
+    let cursor = std::io::Cursor::new(data.to_vec());
+    let mss = MediaSourceStream::new(Box::new(cursor), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = extension_hint {
+        hint.with_extension(extension);
+    }
+
+    decode_mss(mss, hint)
+}
+
+fn decode_mss(mss: MediaSourceStream, hint: Hint) -> Result<DecodedAudio> {
+    let format_opts = FormatOptions::default();
+    let metadata_opts = MetadataOptions::default();
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, mss, &format_opts, &metadata_opts)
+        .map_err(|e| anyhow!("Failed to probe audio format: {e}"))?;
+
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != symphonia::core::codecs::CODEC_TYPE_NULL)
+        .ok_or_else(|| anyhow!("No decodable audio track found"))?
+        .clone();
+
+    let decoder_opts = DecoderOptions::default();
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &decoder_opts)
+        .map_err(|e| anyhow!("Failed to create decoder: {e}"))?;
+
+    let track_id = track.id;
+    let mut samples: Vec<f32> = Vec::new();
+    let mut sample_rate: u32 = track.codec_params.sample_rate.unwrap_or(44100);
+    let mut spec: Option<SignalSpec> = None;
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(symphonia::core::errors::Error::IoError(_)) => break,
+            Err(e) => return Err(anyhow!("Error reading packet: {e}")),
+        };
+
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                if spec.is_none() {
+                    spec = Some(*decoded.spec());
+                    sample_rate = decoded.spec().rate;
+                }
+
+                let mut sample_buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, *decoded.spec());
+                sample_buf.copy_interleaved_ref(decoded);
+
+                let channels = decoded.spec().channels.count().max(1);
+                downmix_to_mono(sample_buf.samples(), channels, &mut samples);
+            }
+            Err(symphonia::core::errors::Error::DecodeError(_)) => continue,
+            Err(e) => return Err(anyhow!("Error decoding packet: {e}")),
+        }
+    }
+
+    Ok(DecodedAudio { samples, sample_rate })
+}
+
+/// Downmix interleaved multi-channel samples to mono by averaging channels
+fn downmix_to_mono(interleaved: &[f32], channels: usize, out: &mut Vec<f32>) {
+    if channels <= 1 {
+        out.extend_from_slice(interleaved);
+        return;
+    }
+
+    for frame in interleaved.chunks(channels) {
+        let sum: f32 = frame.iter().sum();
+        out.push(sum / channels as f32);
+    }
+}
+
+impl DecodedAudio {
+    /// Resample this audio to the target sample rate, consuming self
+    pub fn resampled_to(self, target_sample_rate: u32) -> Result<Vec<f32>> {
+        resample_audio(&self.samples, target_sample_rate)
+    }
+}
@@ -0,0 +1,102 @@
+//! Generic distance metrics over fixed-length numeric vectors, used by
+//! `Database::generate_playlist`/`Database::closest_to_seed` to compare songs'
+//! `audio::AudioFeatures::to_vector()` descriptors.
+
+/// Which metric a vector-distance query should use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceMetric {
+    Euclidean,
+    Cosine,
+}
+
+impl DistanceMetric {
+    /// Distance between `a` and `b` under this metric; smaller means more similar.
+    pub fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            DistanceMetric::Euclidean => euclidean_distance(a, b),
+            DistanceMetric::Cosine => cosine_distance(a, b),
+        }
+    }
+}
+
+/// Straight-line distance between two equal-length vectors; `f32::INFINITY`
+/// if the lengths differ, since distances between mismatched descriptors are meaningless.
+pub fn euclidean_distance(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return f32::INFINITY;
+    }
+
+    a.iter().zip(b.iter()).map(|(x, y)| (x - y).powi(2)).sum::<f32>().sqrt()
+}
+
+/// `1 - cosine similarity`, in `[0, 2]`; `f32::INFINITY` if the lengths differ
+/// or either vector is all-zero, since direction is undefined for a zero vector.
+pub fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return f32::INFINITY;
+    }
+
+    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return f32::INFINITY;
+    }
+
+    1.0 - dot_product / (norm_a * norm_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_euclidean_distance_identical_vectors_is_zero() {
+        let a = vec![1.0, 2.0, 3.0];
+        assert_eq!(euclidean_distance(&a, &a), 0.0);
+    }
+
+    #[test]
+    fn test_euclidean_distance_known_value() {
+        let a = vec![0.0, 0.0];
+        let b = vec![3.0, 4.0];
+        assert_eq!(euclidean_distance(&a, &b), 5.0);
+    }
+
+    #[test]
+    fn test_euclidean_distance_mismatched_lengths_is_infinite() {
+        let a = vec![1.0, 2.0];
+        let b = vec![1.0, 2.0, 3.0];
+        assert_eq!(euclidean_distance(&a, &b), f32::INFINITY);
+    }
+
+    #[test]
+    fn test_cosine_distance_identical_direction_is_zero() {
+        let a = vec![1.0, 2.0, 3.0];
+        let b = vec![2.0, 4.0, 6.0];
+        assert!(cosine_distance(&a, &b) < 1e-5);
+    }
+
+    #[test]
+    fn test_cosine_distance_opposite_direction_is_two() {
+        let a = vec![1.0, 0.0];
+        let b = vec![-1.0, 0.0];
+        assert!((cosine_distance(&a, &b) - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_cosine_distance_zero_vector_is_infinite() {
+        let a = vec![0.0, 0.0];
+        let b = vec![1.0, 1.0];
+        assert_eq!(cosine_distance(&a, &b), f32::INFINITY);
+    }
+
+    #[test]
+    fn test_distance_metric_dispatches_to_matching_function() {
+        let a = vec![0.0, 0.0];
+        let b = vec![3.0, 4.0];
+        assert_eq!(DistanceMetric::Euclidean.distance(&a, &b), euclidean_distance(&a, &b));
+        assert_eq!(DistanceMetric::Cosine.distance(&a, &b), cosine_distance(&a, &b));
+    }
+}
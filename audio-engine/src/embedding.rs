@@ -0,0 +1,342 @@
+//! Bliss-style dense feature-vector embeddings for acoustic similarity search
+//!
+//! Unlike `fingerprint::Fingerprint`, which produces discrete landmark hashes for exact
+//! recognition, `AudioEmbedding` produces a fixed-length descriptor vector capturing
+//! global acoustic characteristics (timbre, pitch-class balance, rhythm), suitable for
+//! storing in the configured vector database for "find acoustically similar songs"
+//! queries.
+
+use anyhow::Result;
+
+use crate::audio;
+use crate::config::AudioConfig;
+use crate::fingerprint::Fingerprint;
+
+/// A fixed-length, L2-normalized acoustic descriptor for a track
+pub struct AudioEmbedding;
+
+impl AudioEmbedding {
+    /// Derive a fixed-length embedding directly from a `Fingerprint`'s spectral
+    /// peaks, for callers (like `Database::add_song`/`search_similar`) that only
+    /// have the fingerprint on hand rather than the original decoded audio.
+    ///
+    /// Each peak contributes its magnitude to a log-frequency histogram bucket,
+    /// so the resulting vector is a coarse spectral-energy profile comparable
+    /// across tracks of different lengths via cosine distance.
+    pub fn from_fingerprint(fingerprint: &Fingerprint, dimensions: usize) -> Vec<f32> {
+        let mut histogram = vec![0.0f32; dimensions.max(1)];
+
+        for peak in &fingerprint.peaks {
+            if peak.frequency <= 0.0 {
+                continue;
+            }
+
+            let bucket = log_frequency_bucket(peak.frequency, histogram.len());
+            histogram[bucket] += peak.magnitude;
+        }
+
+        let mut vector = histogram;
+        vector.resize(dimensions, 0.0);
+        l2_normalize(&mut vector);
+
+        vector
+    }
+    /// Extract a dense embedding from decoded audio, sized to `config.vector_db.dimensions`
+    /// isn't available here (that config lives one level up), so the caller passes the
+    /// target dimensionality explicitly.
+    pub fn extract(audio_data: &[f32], config: &AudioConfig, dimensions: usize) -> Result<Vec<f32>> {
+        let window_size = config.window_size;
+        let hop_size = config.hop_size;
+
+        let mut centroids = Vec::new();
+        let mut rolloffs = Vec::new();
+        let mut zcrs = Vec::new();
+        let mut chroma_sum = vec![0.0f32; 12];
+        let mut onset_envelope = Vec::new();
+
+        let mut frame_start = 0;
+        while frame_start + window_size <= audio_data.len() {
+            let frame = &audio_data[frame_start..frame_start + window_size];
+            let windowed = audio::apply_window(frame, audio::WindowType::Hamming);
+            let spectrum = compute_magnitude_spectrum(&windowed);
+
+            centroids.push(spectral_centroid(&spectrum, config.sample_rate));
+            rolloffs.push(spectral_rolloff(&spectrum, config.sample_rate));
+            zcrs.push(zero_crossing_rate(frame));
+            accumulate_chroma(&spectrum, config.sample_rate, &mut chroma_sum);
+
+            let energy: f32 = spectrum.iter().map(|&x| x * x).sum();
+            onset_envelope.push(energy);
+
+            frame_start += hop_size;
+        }
+
+        let (centroid_mean, centroid_var) = mean_and_variance(&centroids);
+        let (rolloff_mean, rolloff_var) = mean_and_variance(&rolloffs);
+        let (zcr_mean, zcr_var) = mean_and_variance(&zcrs);
+        let tempo = estimate_tempo_bpm(&onset_envelope, config.sample_rate, hop_size);
+
+        normalize_l1(&mut chroma_sum);
+
+        let mut vector = Vec::with_capacity(dimensions.max(32));
+        vector.push(centroid_mean);
+        vector.push(centroid_var);
+        vector.push(rolloff_mean);
+        vector.push(rolloff_var);
+        vector.push(zcr_mean);
+        vector.push(zcr_var);
+        vector.push(tempo);
+        vector.extend_from_slice(&chroma_sum);
+
+        // Pad or truncate to the requested dimensionality
+        vector.resize(dimensions, 0.0);
+
+        l2_normalize(&mut vector);
+
+        Ok(vector)
+    }
+
+    /// Cosine similarity between two embeddings, as used by the cosine-metric vector DB
+    pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+        if a.len() != b.len() || a.is_empty() {
+            return 0.0;
+        }
+
+        let dot: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+        let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+        let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+        if norm_a == 0.0 || norm_b == 0.0 {
+            0.0
+        } else {
+            dot / (norm_a * norm_b)
+        }
+    }
+}
+
+/// Map a frequency in Hz onto one of `num_buckets` log-spaced bins spanning
+/// roughly 20 Hz-20 kHz, mirroring how pitch perception is roughly logarithmic
+fn log_frequency_bucket(frequency_hz: f32, num_buckets: usize) -> usize {
+    const MIN_HZ: f32 = 20.0;
+    const MAX_HZ: f32 = 20_000.0;
+
+    let clamped = frequency_hz.clamp(MIN_HZ, MAX_HZ);
+    let position = (clamped / MIN_HZ).log2() / (MAX_HZ / MIN_HZ).log2();
+
+    ((position * num_buckets as f32) as usize).min(num_buckets - 1)
+}
+
+fn compute_magnitude_spectrum(windowed: &[f32]) -> Vec<f32> {
+    use rustfft::{num_complex::Complex, FftPlanner};
+
+    let mut planner = FftPlanner::new();
+    let fft = planner.plan_fft_forward(windowed.len());
+
+    let mut complex_data: Vec<Complex<f32>> = windowed.iter().map(|&x| Complex::new(x, 0.0)).collect();
+    fft.process(&mut complex_data);
+
+    complex_data.iter().take(windowed.len() / 2 + 1).map(|c| c.norm()).collect()
+}
+
+fn spectral_centroid(spectrum: &[f32], sample_rate: u32) -> f32 {
+    let weighted_sum: f32 = spectrum
+        .iter()
+        .enumerate()
+        .map(|(bin, &mag)| mag * bin as f32 * sample_rate as f32 / (2.0 * spectrum.len() as f32))
+        .sum();
+    let magnitude_sum: f32 = spectrum.iter().sum();
+
+    if magnitude_sum > 0.0 {
+        weighted_sum / magnitude_sum
+    } else {
+        0.0
+    }
+}
+
+fn spectral_rolloff(spectrum: &[f32], sample_rate: u32) -> f32 {
+    let total_energy: f32 = spectrum.iter().map(|&x| x * x).sum();
+    let threshold = 0.85 * total_energy;
+
+    let mut cumulative = 0.0;
+    for (bin, &mag) in spectrum.iter().enumerate() {
+        cumulative += mag * mag;
+        if cumulative >= threshold {
+            return bin as f32 * sample_rate as f32 / (2.0 * spectrum.len() as f32);
+        }
+    }
+
+    sample_rate as f32 / 2.0
+}
+
+fn zero_crossing_rate(frame: &[f32]) -> f32 {
+    if frame.len() < 2 {
+        return 0.0;
+    }
+
+    let crossings = frame.windows(2).filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0)).count();
+    crossings as f32 / (frame.len() - 1) as f32
+}
+
+/// Accumulate a frame's spectral energy into a 12-bin pitch-class (chroma) profile
+fn accumulate_chroma(spectrum: &[f32], sample_rate: u32, chroma: &mut [f32]) {
+    for (bin, &mag) in spectrum.iter().enumerate().skip(1) {
+        let frequency = bin as f32 * sample_rate as f32 / (2.0 * spectrum.len() as f32);
+        if frequency <= 0.0 {
+            continue;
+        }
+
+        let pitch_class = (12.0 * (frequency / 440.0).log2()).round();
+        let bin_idx = (((pitch_class as i64 % 12) + 12) % 12) as usize;
+        chroma[bin_idx] += mag;
+    }
+}
+
+/// Estimate tempo in BPM from an onset-strength envelope via autocorrelation
+fn estimate_tempo_bpm(onset_envelope: &[f32], sample_rate: u32, hop_size: usize) -> f32 {
+    if onset_envelope.len() < 2 {
+        return 0.0;
+    }
+
+    let frame_rate = sample_rate as f32 / hop_size as f32;
+    let min_bpm = 60.0;
+    let max_bpm = 200.0;
+
+    let min_lag = (60.0 / max_bpm * frame_rate).max(1.0) as usize;
+    let max_lag = ((60.0 / min_bpm * frame_rate) as usize).min(onset_envelope.len() / 2).max(min_lag + 1);
+
+    let mut best_lag = min_lag;
+    let mut best_correlation = 0.0;
+
+    for lag in min_lag..max_lag {
+        let correlation: f32 = (0..onset_envelope.len() - lag)
+            .map(|i| onset_envelope[i] * onset_envelope[i + lag])
+            .sum();
+
+        if correlation > best_correlation {
+            best_correlation = correlation;
+            best_lag = lag;
+        }
+    }
+
+    60.0 * frame_rate / best_lag as f32
+}
+
+fn mean_and_variance(values: &[f32]) -> (f32, f32) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    let mean = values.iter().sum::<f32>() / values.len() as f32;
+    let variance = values.iter().map(|&v| (v - mean).powi(2)).sum::<f32>() / values.len() as f32;
+
+    (mean, variance)
+}
+
+fn normalize_l1(values: &mut [f32]) {
+    let sum: f32 = values.iter().sum();
+    if sum > 0.0 {
+        for v in values.iter_mut() {
+            *v /= sum;
+        }
+    }
+}
+
+fn l2_normalize(values: &mut [f32]) {
+    let norm: f32 = values.iter().map(|&x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for v in values.iter_mut() {
+            *v /= norm;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedding_extraction() {
+        let config = AudioConfig {
+            sample_rate: 44100,
+            window_size: 4096,
+            hop_size: 2048,
+            overlap: 0.5,
+            min_duration: 3.0,
+            max_duration: 30.0,
+            noise_threshold: 0.01,
+        };
+
+        let mut audio_data = Vec::new();
+        for i in 0..44100 {
+            let sample = (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 44100.0).sin();
+            audio_data.push(sample);
+        }
+
+        let embedding = AudioEmbedding::extract(&audio_data, &config, 1024).unwrap();
+        assert_eq!(embedding.len(), 1024);
+
+        let norm: f32 = embedding.iter().map(|&x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-3 || norm == 0.0);
+    }
+
+    #[test]
+    fn test_cosine_similarity_identical() {
+        let a = vec![0.5, 0.5, 0.0, 0.0];
+        assert!((AudioEmbedding::cosine_similarity(&a, &a) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_from_fingerprint_is_fixed_length_and_l2_normalized() {
+        let fingerprint = crate::fingerprint::Fingerprint {
+            hashes: vec![1, 2],
+            time_offsets: vec![0.0, 0.1],
+            peaks: vec![
+                crate::fingerprint::SpectralPeak { frequency: 440.0, time: 0.0, magnitude: 1.0 },
+                crate::fingerprint::SpectralPeak { frequency: 880.0, time: 0.1, magnitude: 0.5 },
+            ],
+            metadata: crate::fingerprint::FingerprintMetadata {
+                sample_rate: 44100,
+                duration: 1.0,
+                num_bins: 2048,
+                window_size: 4096,
+                overlap: 0.5,
+                key: None,
+            },
+        };
+
+        let embedding = AudioEmbedding::from_fingerprint(&fingerprint, 128);
+        assert_eq!(embedding.len(), 128);
+
+        let norm: f32 = embedding.iter().map(|&x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_from_fingerprint_with_no_peaks_is_all_zero() {
+        let fingerprint = crate::fingerprint::Fingerprint {
+            hashes: Vec::new(),
+            time_offsets: Vec::new(),
+            peaks: Vec::new(),
+            metadata: crate::fingerprint::FingerprintMetadata {
+                sample_rate: 44100,
+                duration: 1.0,
+                num_bins: 2048,
+                window_size: 4096,
+                overlap: 0.5,
+                key: None,
+            },
+        };
+
+        let embedding = AudioEmbedding::from_fingerprint(&fingerprint, 32);
+        assert!(embedding.iter().all(|&x| x == 0.0));
+    }
+
+    #[test]
+    fn test_log_frequency_bucket_is_monotonic_and_in_range() {
+        let low = log_frequency_bucket(50.0, 64);
+        let high = log_frequency_bucket(10_000.0, 64);
+
+        assert!(low < high);
+        assert!(high < 64);
+    }
+}
@@ -45,6 +45,12 @@ pub enum AudioEngineError {
     
     #[error("Internal error: {0}")]
     Internal(String),
+
+    #[error("Unsupported fingerprint format version {version} (highest known: {max_known})")]
+    UnsupportedFingerprintVersion { version: u32, max_known: u32 },
+
+    #[error("Fingerprint checksum mismatch: expected {expected:x}, computed {actual:x}")]
+    ChecksumMismatch { expected: u32, actual: u32 },
 }
 
 impl AudioEngineError {
@@ -61,6 +67,8 @@ impl AudioEngineError {
             AudioEngineError::Database(_) => 500,
             AudioEngineError::Redis(_) => 500,
             AudioEngineError::Internal(_) => 500,
+            AudioEngineError::UnsupportedFingerprintVersion { .. } => 500,
+            AudioEngineError::ChecksumMismatch { .. } => 500,
             _ => 500,
         }
     }
@@ -78,6 +86,8 @@ impl AudioEngineError {
             AudioEngineError::Database(_) => "DATABASE_ERROR",
             AudioEngineError::Redis(_) => "CACHE_ERROR",
             AudioEngineError::Internal(_) => "INTERNAL_ERROR",
+            AudioEngineError::UnsupportedFingerprintVersion { .. } => "UNSUPPORTED_FINGERPRINT_VERSION",
+            AudioEngineError::ChecksumMismatch { .. } => "CHECKSUM_MISMATCH",
             _ => "UNKNOWN_ERROR",
         }
     }
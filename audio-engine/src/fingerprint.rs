@@ -4,6 +4,8 @@ use std::collections::HashMap;
 use ndarray::{Array1, Array2};
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
+use crate::error::AudioEngineError;
+use crc::{Crc, CRC_32_ISO_HDLC};
 
 /// Audio fingerprint for music recognition
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,36 +44,141 @@ pub struct FingerprintMetadata {
     pub window_size: usize,
     /// Overlap between windows
     pub overlap: f32,
+    /// Estimated musical key, used to penalize similarity between fingerprints
+    /// in different keys that happen to share hashes. `None` for fingerprints
+    /// generated before key estimation was added, or when estimation couldn't
+    /// find a confident pitch contour.
+    pub key: Option<KeyClass>,
+}
+
+/// One of the 12 pitch classes (semitones), independent of octave
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PitchClass {
+    C,
+    CSharp,
+    D,
+    DSharp,
+    E,
+    F,
+    FSharp,
+    G,
+    GSharp,
+    A,
+    ASharp,
+    B,
+}
+
+impl PitchClass {
+    fn from_index(index: usize) -> Self {
+        match index % 12 {
+            0 => PitchClass::C,
+            1 => PitchClass::CSharp,
+            2 => PitchClass::D,
+            3 => PitchClass::DSharp,
+            4 => PitchClass::E,
+            5 => PitchClass::F,
+            6 => PitchClass::FSharp,
+            7 => PitchClass::G,
+            8 => PitchClass::GSharp,
+            9 => PitchClass::A,
+            10 => PitchClass::ASharp,
+            _ => PitchClass::B,
+        }
+    }
+}
+
+/// Estimated musical key: a tonic pitch class plus major/minor mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyClass {
+    Major(PitchClass),
+    Minor(PitchClass),
+}
+
+/// Current on-disk/wire layout version written by `to_bytes_versioned`.
+/// Bump this and add a match arm to `deserialize_any_version` whenever a
+/// serialized field is added or removed, filling the gap for older versions
+/// with a sensible default so previously-stored fingerprints keep loading.
+const FINGERPRINT_FORMAT_VERSION: u32 = 1;
+
+/// CRC-32 algorithm backing the integrity checksum stored in
+/// `VersionedFingerprint::checksum`. Pinned explicitly to `ISO-HDLC` (rather
+/// than left to whatever the `crc` crate defaults to) because a silent
+/// algorithm/polynomial change would make every previously-stored
+/// fingerprint fail checksum validation with no indication why; the
+/// polynomial is also stored in the envelope itself so such a change is
+/// detectable rather than producing a confusing mismatch.
+const FINGERPRINT_CHECKSUM: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+/// Versioned envelope around a serialized `Fingerprint`. `format_version` is
+/// always the first field so `deserialize_any_version` can dispatch on it
+/// before interpreting the rest of the payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VersionedFingerprint {
+    format_version: u32,
+    /// CRC-32 polynomial `checksum` was computed with, so a future change to
+    /// `FINGERPRINT_CHECKSUM` is detected instead of silently mismatching
+    checksum_polynomial: u32,
+    /// CRC-32 checksum over the canonical byte encoding of `hashes`,
+    /// `time_offsets`, and `peaks`, checked on load in `deserialize_any_version`
+    checksum: u32,
+    hashes: Vec<u64>,
+    time_offsets: Vec<f32>,
+    peaks: Vec<SpectralPeak>,
+    metadata: FingerprintMetadata,
+}
+
+/// Compute the integrity checksum covering a fingerprint's hash/peak data,
+/// using the canonical bincode byte encoding so the same logical data always
+/// produces the same checksum regardless of caller
+fn compute_fingerprint_checksum(hashes: &[u64], time_offsets: &[f32], peaks: &[SpectralPeak]) -> Result<u32> {
+    let canonical_bytes = bincode::serialize(&(hashes, time_offsets, peaks))?;
+    Ok(FINGERPRINT_CHECKSUM.checksum(&canonical_bytes))
 }
 
 impl Fingerprint {
-    /// Generate fingerprint from audio data
+    /// Generate fingerprint from audio data using default DSP parameters
+    ///
+    /// Thin wrapper over `generate_with_config` using `Config::default().audio`.
     pub fn generate(audio_data: &[f32]) -> Result<Self> {
-        let sample_rate = 44100;
-        let window_size = 4096;
-        let overlap = 0.5;
-        let hop_size = (window_size as f32 * (1.0 - overlap)) as usize;
-        
+        Self::generate_with_config(audio_data, &crate::config::Config::default().audio)
+    }
+
+    /// Generate fingerprint from audio data using the supplied `AudioConfig`
+    ///
+    /// Threads `sample_rate`, `window_size`, `hop_size`, and `noise_threshold`
+    /// through `compute_spectrogram`/`find_spectral_peaks`/`generate_hash_pairs` instead
+    /// of relying on hardcoded constants, so fingerprints can be generated at whatever
+    /// rate/resolution a deployment is configured for; `overlap` is only recorded in
+    /// the metadata.
+    pub fn generate_with_config(audio_data: &[f32], config: &crate::config::AudioConfig) -> Result<Self> {
+        let sample_rate = config.sample_rate;
+        let window_size = config.window_size;
+        let hop_size = config.hop_size;
+        let overlap = config.overlap;
+
         // Compute spectrogram
         let spectrogram = compute_spectrogram(audio_data, window_size, hop_size, sample_rate)?;
-        
+
         // Find spectral peaks
-        let peaks = find_spectral_peaks(&spectrogram, sample_rate, hop_size)?;
-        
+        let peaks = find_spectral_peaks(&spectrogram, sample_rate, hop_size, config.noise_threshold)?;
+
         // Generate hash pairs
         let (hashes, time_offsets) = generate_hash_pairs(&peaks)?;
-        
+
         let duration = audio_data.len() as f32 / sample_rate as f32;
         let num_bins = spectrogram.nrows();
-        
+        let (key, key_confidence) = estimate_key(audio_data, sample_rate);
+        let key = if key_confidence > 0.0 { Some(key) } else { None };
+
         let metadata = FingerprintMetadata {
             sample_rate,
             duration,
             num_bins,
             window_size,
             overlap,
+            key,
         };
-        
+
         Ok(Fingerprint {
             hashes,
             time_offsets,
@@ -79,7 +186,18 @@ impl Fingerprint {
             metadata,
         })
     }
-    
+
+    /// Decode an audio file from disk and generate its fingerprint
+    ///
+    /// Chains the `decode` module's demux/decode step, resampling to the configured
+    /// sample rate, and fingerprint generation, so callers can hand in MP3/FLAC/WAV/OGG
+    /// files directly instead of pre-decoding PCM themselves.
+    pub fn from_file(path: &std::path::Path, config: &crate::config::AudioConfig) -> Result<Self> {
+        let decoded = crate::decode::decode_file(path)?;
+        let resampled = decoded.resampled_to(config.sample_rate)?;
+        Self::generate_with_config(&resampled, config)
+    }
+
     /// Calculate similarity with another fingerprint
     pub fn similarity(&self, other: &Fingerprint) -> f32 {
         if self.hashes.is_empty() || other.hashes.is_empty() {
@@ -131,6 +249,61 @@ impl Fingerprint {
     pub fn from_bytes(data: &[u8]) -> Result<Self> {
         Ok(bincode::deserialize(data)?)
     }
+
+    /// Serialize with an explicit `format_version` header so a future
+    /// layout change can still tell these blobs apart from newer ones, plus
+    /// a CRC-32 integrity checksum so corruption is caught on load rather
+    /// than silently trusted during matching
+    pub fn to_bytes_versioned(&self) -> Result<Vec<u8>> {
+        let checksum = compute_fingerprint_checksum(&self.hashes, &self.time_offsets, &self.peaks)?;
+
+        let envelope = VersionedFingerprint {
+            format_version: FINGERPRINT_FORMAT_VERSION,
+            checksum_polynomial: CRC_32_ISO_HDLC.poly,
+            checksum,
+            hashes: self.hashes.clone(),
+            time_offsets: self.time_offsets.clone(),
+            peaks: self.peaks.clone(),
+            metadata: self.metadata.clone(),
+        };
+
+        Ok(bincode::serialize(&envelope)?)
+    }
+
+    /// Decode a fingerprint written by `to_bytes_versioned` at any released
+    /// format version, upgrading older layouts to the current in-memory
+    /// `Fingerprint` by filling newly-added fields with defaults. Unknown
+    /// (future) versions fail loudly rather than silently misreading bytes,
+    /// as does a checksum computed under an algorithm other than the one
+    /// this build uses, or a checksum that simply doesn't match the payload.
+    pub fn deserialize_any_version(bytes: &[u8]) -> Result<Self> {
+        let envelope: VersionedFingerprint = bincode::deserialize(bytes)?;
+
+        if envelope.format_version <= FINGERPRINT_FORMAT_VERSION {
+            let actual = compute_fingerprint_checksum(&envelope.hashes, &envelope.time_offsets, &envelope.peaks)?;
+            if envelope.checksum_polynomial != CRC_32_ISO_HDLC.poly || actual != envelope.checksum {
+                return Err(AudioEngineError::ChecksumMismatch {
+                    expected: envelope.checksum,
+                    actual,
+                }
+                .into());
+            }
+        }
+
+        match envelope.format_version {
+            1 => Ok(Fingerprint {
+                hashes: envelope.hashes,
+                time_offsets: envelope.time_offsets,
+                peaks: envelope.peaks,
+                metadata: envelope.metadata,
+            }),
+            other => Err(AudioEngineError::UnsupportedFingerprintVersion {
+                version: other,
+                max_known: FINGERPRINT_FORMAT_VERSION,
+            }
+            .into()),
+        }
+    }
 }
 
 /// Compute spectrogram from audio data
@@ -188,6 +361,7 @@ fn find_spectral_peaks(
     spectrogram: &Array2<f32>,
     sample_rate: u32,
     hop_size: usize,
+    noise_threshold: f32,
 ) -> Result<Vec<SpectralPeak>> {
     let mut peaks = Vec::new();
     let num_bins = spectrogram.nrows();
@@ -214,8 +388,8 @@ fn find_spectral_peaks(
             // Check if it's a local maximum
             if magnitude > frame_spectrum[bin_idx - 1] && magnitude > frame_spectrum[bin_idx + 1] {
                 // Check if magnitude is above threshold
-                let threshold = calculate_adaptive_threshold(&frame_spectrum, bin_idx);
-                
+                let threshold = calculate_adaptive_threshold(&frame_spectrum, bin_idx, noise_threshold);
+
                 if magnitude > threshold {
                     peaks.push(SpectralPeak {
                         frequency: bin_to_freq(bin_idx),
@@ -237,65 +411,277 @@ fn find_spectral_peaks(
 }
 
 /// Calculate adaptive threshold for peak detection
-fn calculate_adaptive_threshold(spectrum: &Array1<f32>, bin_idx: usize) -> f32 {
+fn calculate_adaptive_threshold(spectrum: &Array1<f32>, bin_idx: usize, noise_threshold: f32) -> f32 {
     let window_size = 10;
     let start = bin_idx.saturating_sub(window_size / 2);
     let end = (bin_idx + window_size / 2 + 1).min(spectrum.len());
-    
+
     let local_spectrum = &spectrum.slice(ndarray::s![start..end]);
     let mean = local_spectrum.mean().unwrap_or(0.0);
     let std = local_spectrum.std(1.0);
-    
-    mean + 2.0 * std
+
+    (mean + 2.0 * std).max(noise_threshold)
+}
+
+/// Tunable parameters for anchor/target-zone hashing
+#[derive(Debug, Clone, Copy)]
+pub struct TargetZoneConfig {
+    /// Number of strongest targets to pair with each anchor (fan-out)
+    pub fan_out: usize,
+    /// Minimum time gap between anchor and target, in seconds
+    pub min_delta_t: f32,
+    /// Maximum time gap between anchor and target, in seconds
+    pub max_delta_t: f32,
+    /// Maximum frequency distance between anchor and target, in Hz
+    pub max_delta_freq: f32,
+}
+
+impl Default for TargetZoneConfig {
+    fn default() -> Self {
+        Self {
+            fan_out: 5,
+            min_delta_t: 0.05,
+            max_delta_t: 2.0,
+            max_delta_freq: 1500.0,
+        }
+    }
 }
 
-/// Generate hash pairs from spectral peaks
+/// Generate hash pairs from spectral peaks using the anchor/target-zone scheme
+///
+/// Peaks are sorted by time; each anchor is paired with only the strongest `fan_out`
+/// peaks inside a bounded target zone ahead of it in time and frequency. This bounds
+/// the hash count to roughly `fan_out * num_peaks` (instead of the O(n²) full pairing)
+/// and yields far more specific 3-component `(f_anchor, f_target, Δt)` landmarks, which
+/// pair naturally with offset-histogram matching.
 fn generate_hash_pairs(peaks: &[SpectralPeak]) -> Result<(Vec<u64>, Vec<f32>)> {
+    generate_hash_pairs_with_config(peaks, &TargetZoneConfig::default())
+}
+
+/// Generate hash pairs with explicit target-zone tuning
+fn generate_hash_pairs_with_config(
+    peaks: &[SpectralPeak],
+    config: &TargetZoneConfig,
+) -> Result<(Vec<u64>, Vec<f32>)> {
+    let mut sorted_peaks = peaks.to_vec();
+    sorted_peaks.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
+
     let mut hashes = Vec::new();
     let mut time_offsets = Vec::new();
-    
-    // Generate hash pairs using time-frequency combinations
-    for i in 0..peaks.len() {
-        for j in (i + 1)..peaks.len() {
-            let peak1 = &peaks[i];
-            let peak2 = &peaks[j];
-            
-            // Skip if peaks are too far apart in time
-            let time_diff = (peak2.time - peak1.time).abs();
-            if time_diff > 10.0 {
-                break;
-            }
-            
-            // Create hash from frequency and time differences
-            let freq_diff = peak2.frequency - peak1.frequency;
-            let hash = create_hash(peak1.frequency, freq_diff, time_diff);
-            
+
+    for (anchor_idx, anchor) in sorted_peaks.iter().enumerate() {
+        // Collect candidate targets within the zone ahead of the anchor
+        let mut targets: Vec<&SpectralPeak> = sorted_peaks[anchor_idx + 1..]
+            .iter()
+            .take_while(|target| target.time - anchor.time <= config.max_delta_t)
+            .filter(|target| {
+                let delta_t = target.time - anchor.time;
+                let delta_freq = (target.frequency - anchor.frequency).abs();
+                delta_t >= config.min_delta_t && delta_freq <= config.max_delta_freq
+            })
+            .collect();
+
+        // Keep only the strongest `fan_out` targets in the zone
+        targets.sort_by(|a, b| b.magnitude.partial_cmp(&a.magnitude).unwrap());
+        targets.truncate(config.fan_out);
+
+        for target in targets {
+            let delta_t = target.time - anchor.time;
+            let hash = create_hash(anchor.frequency, target.frequency, delta_t);
+
             hashes.push(hash);
-            time_offsets.push(peak1.time);
+            time_offsets.push(anchor.time);
         }
     }
-    
+
     Ok((hashes, time_offsets))
 }
 
-/// Create hash from frequency and time information
-fn create_hash(freq1: f32, freq_diff: f32, time_diff: f32) -> u64 {
+/// Create a 3-component landmark hash from an anchor/target peak pair
+fn create_hash(freq_anchor: f32, freq_target: f32, delta_t: f32) -> u64 {
     // Quantize values to reduce noise sensitivity
-    let freq1_quantized = (freq1 / 10.0).round() as i32;
-    let freq_diff_quantized = (freq_diff / 10.0).round() as i32;
-    let time_diff_quantized = (time_diff * 100.0).round() as i32;
-    
+    let freq_anchor_quantized = (freq_anchor / 10.0).round() as i32;
+    let freq_target_quantized = (freq_target / 10.0).round() as i32;
+    let delta_t_quantized = (delta_t * 100.0).round() as i32;
+
     // Combine into hash
-    let hash = ((freq1_quantized as u64) << 32) 
-        | ((freq_diff_quantized as u64) << 16) 
-        | (time_diff_quantized as u64);
-    
+    let hash = ((freq_anchor_quantized as u64) << 32)
+        | ((freq_target_quantized as u64) << 16)
+        | (delta_t_quantized as u64);
+
     hash
 }
 
+const PITCH_FRAME_SIZE: usize = 4096;
+const PITCH_HOP_SIZE: usize = 2048;
+const MIN_PITCH_HZ: f32 = 80.0;
+const MAX_PITCH_HZ: f32 = 1000.0;
+const PITCH_CONFIDENCE_THRESHOLD: f32 = 0.6;
+
+/// Krumhansl-Schmuckler major key profile, indexed by semitone above the tonic
+const MAJOR_KEY_PROFILE: [f32; 12] = [
+    6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+];
+
+/// Krumhansl-Schmuckler minor key profile, indexed by semitone above the tonic
+const MINOR_KEY_PROFILE: [f32; 12] = [
+    6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
+];
+
+/// Estimate the musical key of `audio_data` from its fundamental-frequency
+/// contour, so `calculate_similarity` can penalize matches between fingerprints
+/// in clearly unrelated keys.
+///
+/// Each frame's dominant pitch is found via normalized autocorrelation and
+/// folded into a pitch class (octave-independent); the resulting 12-bin
+/// chroma histogram is then correlated against all 24 rotated
+/// Krumhansl-Schmuckler major/minor key profiles, and the best-correlating
+/// key is returned alongside that correlation as a confidence score.
+pub fn estimate_key(audio_data: &[f32], sample_rate: u32) -> (KeyClass, f32) {
+    let chroma_histogram = pitch_chroma_histogram(audio_data, sample_rate);
+    correlate_key_profiles(&chroma_histogram)
+}
+
+/// Aggregate each frame's dominant pitch class into a normalized 12-bin chroma
+/// histogram, using normalized autocorrelation to find each frame's pitch
+fn pitch_chroma_histogram(audio_data: &[f32], sample_rate: u32) -> [f32; 12] {
+    let mut histogram = [0.0f32; 12];
+
+    if audio_data.len() < PITCH_FRAME_SIZE {
+        return histogram;
+    }
+
+    let min_lag = (sample_rate as f32 / MAX_PITCH_HZ).floor().max(1.0) as usize;
+    let max_lag = ((sample_rate as f32 / MIN_PITCH_HZ).ceil() as usize).min(PITCH_FRAME_SIZE - 1);
+
+    let mut frame_start = 0;
+    while frame_start + PITCH_FRAME_SIZE <= audio_data.len() {
+        let frame = &audio_data[frame_start..frame_start + PITCH_FRAME_SIZE];
+
+        if let Some((period, confidence)) = dominant_pitch_period(frame, min_lag, max_lag) {
+            let frequency = sample_rate as f32 / period as f32;
+            histogram[frequency_to_pitch_class(frequency)] += confidence;
+        }
+
+        frame_start += PITCH_HOP_SIZE;
+    }
+
+    let total: f32 = histogram.iter().sum();
+    if total > 0.0 {
+        for value in &mut histogram {
+            *value /= total;
+        }
+    }
+
+    histogram
+}
+
+/// Normalized autocorrelation pitch detection for a single frame: computes
+/// `r(τ) = Σ x[n]·x[n+τ]` for lags spanning the musical pitch range, normalizes
+/// by `r(0)`, and returns the first lag whose normalized value clears
+/// `PITCH_CONFIDENCE_THRESHOLD` (ignoring τ=0), along with that value.
+fn dominant_pitch_period(frame: &[f32], min_lag: usize, max_lag: usize) -> Option<(usize, f32)> {
+    if max_lag <= min_lag || max_lag >= frame.len() {
+        return None;
+    }
+
+    let r0: f32 = frame.iter().map(|&x| x * x).sum();
+    if r0 <= 0.0 {
+        return None;
+    }
+
+    for lag in min_lag..=max_lag {
+        let r_tau: f32 = frame[..frame.len() - lag]
+            .iter()
+            .zip(&frame[lag..])
+            .map(|(&a, &b)| a * b)
+            .sum();
+        let normalized = r_tau / r0;
+
+        if normalized > PITCH_CONFIDENCE_THRESHOLD {
+            return Some((lag, normalized));
+        }
+    }
+
+    None
+}
+
+/// Fold a frequency into one of the 12 pitch classes, using the standard
+/// MIDI/A440 mapping (pitch class 0 = C)
+fn frequency_to_pitch_class(frequency: f32) -> usize {
+    if frequency <= 0.0 {
+        return 0;
+    }
+
+    let midi_note = 69.0 + 12.0 * (frequency / 440.0).log2();
+    let rounded = midi_note.round() as i64;
+    (rounded.rem_euclid(12)) as usize
+}
+
+/// Correlate a chroma histogram against all 24 rotated Krumhansl-Schmuckler
+/// key templates (12 major tonics, then 12 minor tonics, in pitch-class order
+/// C..B), returning the best-matching key and its correlation as a confidence
+fn correlate_key_profiles(chroma: &[f32; 12]) -> (KeyClass, f32) {
+    let mut best_index = 0;
+    let mut best_correlation = f32::NEG_INFINITY;
+
+    for (profile_index, profile) in [&MAJOR_KEY_PROFILE, &MINOR_KEY_PROFILE].iter().enumerate() {
+        for tonic in 0..12 {
+            let rotated: Vec<f32> = (0..12).map(|pitch_class| profile[(pitch_class + 12 - tonic) % 12]).collect();
+            let correlation = pearson_correlation(chroma, &rotated);
+
+            if correlation > best_correlation {
+                best_correlation = correlation;
+                best_index = profile_index * 12 + tonic;
+            }
+        }
+    }
+
+    let tonic = PitchClass::from_index(best_index % 12);
+    let key = if best_index < 12 {
+        KeyClass::Major(tonic)
+    } else {
+        KeyClass::Minor(tonic)
+    };
+
+    (key, best_correlation.max(0.0))
+}
+
+fn pearson_correlation(a: &[f32], b: &[f32]) -> f32 {
+    let n = a.len().min(b.len());
+    if n == 0 {
+        return 0.0;
+    }
+
+    let mean_a = a.iter().take(n).sum::<f32>() / n as f32;
+    let mean_b = b.iter().take(n).sum::<f32>() / n as f32;
+
+    let mut covariance = 0.0f32;
+    let mut variance_a = 0.0f32;
+    let mut variance_b = 0.0f32;
+
+    for i in 0..n {
+        let diff_a = a[i] - mean_a;
+        let diff_b = b[i] - mean_b;
+        covariance += diff_a * diff_b;
+        variance_a += diff_a * diff_a;
+        variance_b += diff_b * diff_b;
+    }
+
+    if variance_a <= 0.0 || variance_b <= 0.0 {
+        0.0
+    } else {
+        covariance / (variance_a.sqrt() * variance_b.sqrt())
+    }
+}
+
 /// Fast fingerprint matching using hash tables
+#[derive(Serialize, Deserialize)]
 pub struct FingerprintMatcher {
     hash_table: HashMap<u64, Vec<(usize, f32)>>, // hash -> [(song_id, time_offset)]
+    /// Number of hashes contributed by each song, kept so `find_matches` can normalize
+    /// scores; `remove_song` drops the song's entry along with its postings.
+    song_hash_counts: HashMap<usize, u32>,
 }
 
 impl FingerprintMatcher {
@@ -303,9 +689,10 @@ impl FingerprintMatcher {
     pub fn new() -> Self {
         Self {
             hash_table: HashMap::new(),
+            song_hash_counts: HashMap::new(),
         }
     }
-    
+
     /// Add fingerprint to matcher
     pub fn add_fingerprint(&mut self, song_id: usize, fingerprint: &Fingerprint) {
         for (hash, &time_offset) in fingerprint.hashes.iter().zip(fingerprint.time_offsets.iter()) {
@@ -314,42 +701,103 @@ impl FingerprintMatcher {
                 .or_insert_with(Vec::new)
                 .push((song_id, time_offset));
         }
+
+        *self.song_hash_counts.entry(song_id).or_insert(0) += fingerprint.hashes.len() as u32;
     }
-    
-    /// Find best matching songs
-    pub fn find_matches(&self, query_fingerprint: &Fingerprint, min_matches: usize) -> Vec<(usize, f32, usize)> {
-        let mut song_matches: HashMap<usize, Vec<f32>> = HashMap::new();
-        
-        // Find matching hashes
+
+    /// Add many fingerprints in one pass, so callers re-indexing a whole catalog don't
+    /// have to loop over `add_fingerprint` themselves.
+    pub fn add_fingerprints<'a, I>(&mut self, fingerprints: I)
+    where
+        I: IntoIterator<Item = (usize, &'a Fingerprint)>,
+    {
+        for (song_id, fingerprint) in fingerprints {
+            self.add_fingerprint(song_id, fingerprint);
+        }
+    }
+
+    /// Remove every posting belonging to `song_id`, so a deleted song doesn't leave
+    /// dangling entries that could still surface as a match.
+    pub fn remove_song(&mut self, song_id: usize) {
+        self.hash_table.retain(|_, postings| {
+            postings.retain(|&(id, _)| id != song_id);
+            !postings.is_empty()
+        });
+
+        self.song_hash_counts.remove(&song_id);
+    }
+
+    /// Serialize the index to bytes for storage, matching `Fingerprint::to_bytes`
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Deserialize an index previously produced by `to_bytes`
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        Ok(bincode::deserialize(data)?)
+    }
+
+    /// Persist the index to disk, so a real catalog doesn't need to be re-indexed from
+    /// scratch on every process start
+    pub fn save_to_path(&self, path: &std::path::Path) -> Result<()> {
+        let bytes = self.to_bytes()?;
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// Load a previously persisted index from disk
+    pub fn load_from_path(path: &std::path::Path) -> Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Find best matching songs using offset-histogram (constellation) scoring
+    ///
+    /// For every query hash that collides with a stored hash, the time offset between
+    /// the query occurrence and the reference occurrence is quantized into a bin. A
+    /// genuine match produces a sharp spike in one bin, because all true landmark pairs
+    /// share the same absolute time shift, while spurious hash collisions scatter their
+    /// offsets roughly uniformly. The tallest bin becomes the song's score.
+    pub fn find_matches(&self, query_fingerprint: &Fingerprint, min_matches: usize) -> Vec<(usize, f32, usize, f32)> {
+        const OFFSET_BIN_SIZE: f32 = 0.05;
+
+        let mut song_offset_histograms: HashMap<usize, HashMap<i64, u32>> = HashMap::new();
+
+        // Find matching hashes and accumulate per-song offset histograms
         for (hash, &query_time) in query_fingerprint.hashes.iter().zip(query_fingerprint.time_offsets.iter()) {
             if let Some(matches) = self.hash_table.get(hash) {
-                for &(song_id, song_time) in matches {
-                    let time_diff = (query_time - song_time).abs();
-                    song_matches
+                for &(song_id, db_time) in matches {
+                    let delta = db_time - query_time;
+                    let bin = (delta / OFFSET_BIN_SIZE).round() as i64;
+
+                    *song_offset_histograms
                         .entry(song_id)
-                        .or_insert_with(Vec::new)
-                        .push(time_diff);
+                        .or_insert_with(HashMap::new)
+                        .entry(bin)
+                        .or_insert(0) += 1;
                 }
             }
         }
-        
-        // Calculate scores for each song
+
+        // Find the tallest histogram bin per song and use it as the match score,
+        // normalized against the song's total indexed hash count rather than the
+        // (smaller, query-dependent) count of hashes that happened to collide
         let mut results = Vec::new();
-        for (song_id, time_diffs) in song_matches {
-            if time_diffs.len() >= min_matches {
-                // Calculate score based on number of matches and time consistency
-                let num_matches = time_diffs.len();
-                let avg_time_diff = time_diffs.iter().sum::<f32>() / num_matches as f32;
-                let time_consistency = if avg_time_diff < 0.1 { 1.0 } else { 0.5 };
-                let score = num_matches as f32 * time_consistency;
-                
-                results.push((song_id, score, num_matches));
+        for (song_id, histogram) in song_offset_histograms {
+            if let Some((&peak_bin, &peak_bin_count)) = histogram.iter().max_by_key(|(_, &count)| count) {
+                if peak_bin_count as usize >= min_matches {
+                    let total_hashes = self.song_hash_counts.get(&song_id).copied().unwrap_or(1).max(1);
+                    let score = peak_bin_count as f32 / total_hashes as f32;
+                    let best_offset = peak_bin as f32 * OFFSET_BIN_SIZE;
+
+                    results.push((song_id, score, peak_bin_count as usize, best_offset));
+                }
             }
         }
-        
+
         // Sort by score (highest first)
         results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        
+
         results
     }
 }
@@ -379,6 +827,32 @@ mod tests {
         assert!(!fingerprint.peaks.is_empty());
     }
 
+    #[test]
+    fn test_estimate_key_is_confident_and_deterministic_for_steady_tone() {
+        let sample_rate = 44100;
+        let frequency = 220.0;
+
+        let audio_data: Vec<f32> = (0..sample_rate * 2)
+            .map(|i| (2.0 * std::f32::consts::PI * frequency * i as f32 / sample_rate as f32).sin())
+            .collect();
+
+        let (key_a, confidence_a) = estimate_key(&audio_data, sample_rate as u32);
+        let (key_b, confidence_b) = estimate_key(&audio_data, sample_rate as u32);
+
+        assert!(confidence_a > 0.0);
+        assert_eq!(key_a, key_b);
+        assert_eq!(confidence_a, confidence_b);
+    }
+
+    #[test]
+    fn test_estimate_key_zero_confidence_for_silence() {
+        let sample_rate = 44100;
+        let audio_data = vec![0.0f32; sample_rate * 2];
+
+        let (_, confidence) = estimate_key(&audio_data, sample_rate as u32);
+        assert_eq!(confidence, 0.0);
+    }
+
     #[test]
     fn test_fingerprint_similarity() {
         // Create two similar fingerprints
@@ -392,6 +866,7 @@ mod tests {
                 num_bins: 2048,
                 window_size: 4096,
                 overlap: 0.5,
+                key: None,
             },
         };
         
@@ -405,6 +880,7 @@ mod tests {
                 num_bins: 2048,
                 window_size: 4096,
                 overlap: 0.5,
+                key: None,
             },
         };
         
@@ -427,6 +903,7 @@ mod tests {
                 num_bins: 2048,
                 window_size: 4096,
                 overlap: 0.5,
+                key: None,
             },
         };
         
@@ -440,6 +917,7 @@ mod tests {
                 num_bins: 2048,
                 window_size: 4096,
                 overlap: 0.5,
+                key: None,
             },
         };
         
@@ -456,6 +934,7 @@ mod tests {
                 num_bins: 2048,
                 window_size: 4096,
                 overlap: 0.5,
+                key: None,
             },
         };
         
@@ -463,4 +942,212 @@ mod tests {
         assert!(!matches.is_empty());
         assert_eq!(matches[0].0, 1); // Should match song 1
     }
+
+    #[test]
+    fn test_find_matches_offset_histogram() {
+        let mut matcher = FingerprintMatcher::new();
+
+        // Song 1's landmarks all share a consistent +0.5s offset from the query
+        let fingerprint1 = Fingerprint {
+            hashes: vec![1, 2, 3, 4],
+            time_offsets: vec![0.5, 0.6, 0.7, 0.8],
+            peaks: Vec::new(),
+            metadata: FingerprintMetadata {
+                sample_rate: 44100,
+                duration: 1.0,
+                num_bins: 2048,
+                window_size: 4096,
+                overlap: 0.5,
+                key: None,
+            },
+        };
+
+        matcher.add_fingerprint(1, &fingerprint1);
+
+        let query = Fingerprint {
+            hashes: vec![1, 2, 3, 4],
+            time_offsets: vec![0.0, 0.1, 0.2, 0.3],
+            peaks: Vec::new(),
+            metadata: FingerprintMetadata {
+                sample_rate: 44100,
+                duration: 1.0,
+                num_bins: 2048,
+                window_size: 4096,
+                overlap: 0.5,
+                key: None,
+            },
+        };
+
+        let matches = matcher.find_matches(&query, 3);
+        assert!(!matches.is_empty());
+        let (song_id, score, peak_bin_count, best_offset) = matches[0];
+        assert_eq!(song_id, 1);
+        assert_eq!(peak_bin_count, 4);
+        assert!((best_offset - 0.5).abs() < 0.01);
+        assert!(score > 0.0);
+    }
+
+    #[test]
+    fn test_matcher_persistence_round_trip() {
+        let mut matcher = FingerprintMatcher::new();
+
+        let fingerprint = Fingerprint {
+            hashes: vec![1, 2, 3],
+            time_offsets: vec![0.0, 0.1, 0.2],
+            peaks: Vec::new(),
+            metadata: FingerprintMetadata {
+                sample_rate: 44100,
+                duration: 1.0,
+                num_bins: 2048,
+                window_size: 4096,
+                overlap: 0.5,
+                key: None,
+            },
+        };
+        matcher.add_fingerprint(1, &fingerprint);
+
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("sonica_matcher_test_{}.bin", std::process::id()));
+
+        matcher.save_to_path(&path).unwrap();
+        let loaded = FingerprintMatcher::load_from_path(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let query = Fingerprint {
+            hashes: vec![1, 2, 3],
+            time_offsets: vec![0.0, 0.1, 0.2],
+            peaks: Vec::new(),
+            metadata: FingerprintMetadata {
+                sample_rate: 44100,
+                duration: 1.0,
+                num_bins: 2048,
+                window_size: 4096,
+                overlap: 0.5,
+                key: None,
+            },
+        };
+
+        let matches = loaded.find_matches(&query, 1);
+        assert!(!matches.is_empty());
+        assert_eq!(matches[0].0, 1);
+    }
+
+    #[test]
+    fn test_add_fingerprints_batch_and_remove_song() {
+        let mut matcher = FingerprintMatcher::new();
+
+        let fingerprint1 = Fingerprint {
+            hashes: vec![1, 2, 3],
+            time_offsets: vec![0.0, 0.1, 0.2],
+            peaks: Vec::new(),
+            metadata: FingerprintMetadata {
+                sample_rate: 44100,
+                duration: 1.0,
+                num_bins: 2048,
+                window_size: 4096,
+                overlap: 0.5,
+                key: None,
+            },
+        };
+
+        let fingerprint2 = Fingerprint {
+            hashes: vec![4, 5, 6],
+            time_offsets: vec![0.0, 0.1, 0.2],
+            peaks: Vec::new(),
+            metadata: FingerprintMetadata {
+                sample_rate: 44100,
+                duration: 1.0,
+                num_bins: 2048,
+                window_size: 4096,
+                overlap: 0.5,
+                key: None,
+            },
+        };
+
+        matcher.add_fingerprints(vec![(1, &fingerprint1), (2, &fingerprint2)]);
+
+        let query = Fingerprint {
+            hashes: vec![1, 2, 3],
+            time_offsets: vec![0.0, 0.1, 0.2],
+            peaks: Vec::new(),
+            metadata: FingerprintMetadata {
+                sample_rate: 44100,
+                duration: 1.0,
+                num_bins: 2048,
+                window_size: 4096,
+                overlap: 0.5,
+                key: None,
+            },
+        };
+
+        assert!(!matcher.find_matches(&query, 1).is_empty());
+
+        matcher.remove_song(1);
+        assert!(matcher.find_matches(&query, 1).is_empty());
+    }
+
+    fn make_versioning_test_fingerprint() -> Fingerprint {
+        Fingerprint {
+            hashes: vec![1, 2, 3],
+            time_offsets: vec![0.0, 0.1, 0.2],
+            peaks: Vec::new(),
+            metadata: FingerprintMetadata {
+                sample_rate: 44100,
+                duration: 1.0,
+                num_bins: 2048,
+                window_size: 4096,
+                overlap: 0.5,
+                key: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_versioned_round_trip_preserves_all_fields() {
+        let fingerprint = make_versioning_test_fingerprint();
+
+        let bytes = fingerprint.to_bytes_versioned().unwrap();
+        let loaded = Fingerprint::deserialize_any_version(&bytes).unwrap();
+
+        assert_eq!(loaded.hashes, fingerprint.hashes);
+        assert_eq!(loaded.time_offsets, fingerprint.time_offsets);
+        assert_eq!(loaded.metadata.sample_rate, fingerprint.metadata.sample_rate);
+    }
+
+    #[test]
+    fn test_versioned_bytes_start_with_the_current_format_version() {
+        let fingerprint = make_versioning_test_fingerprint();
+        let bytes = fingerprint.to_bytes_versioned().unwrap();
+
+        // `format_version` is a bincode-encoded little-endian u32 and is the
+        // envelope's first field, so it's always the first four bytes.
+        let version = u32::from_le_bytes(bytes[..4].try_into().unwrap());
+        assert_eq!(version, FINGERPRINT_FORMAT_VERSION);
+    }
+
+    #[test]
+    fn test_deserialize_any_version_rejects_unknown_future_version() {
+        let fingerprint = make_versioning_test_fingerprint();
+        let mut bytes = fingerprint.to_bytes_versioned().unwrap();
+
+        // Corrupt just the format_version header to an unreleased version.
+        bytes[..4].copy_from_slice(&999u32.to_le_bytes());
+
+        let result = Fingerprint::deserialize_any_version(&bytes);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_deserialize_any_version_rejects_corrupted_checksum() {
+        let fingerprint = make_versioning_test_fingerprint();
+        let mut bytes = fingerprint.to_bytes_versioned().unwrap();
+
+        // `checksum_polynomial` (u32) immediately follows `format_version`
+        // (u32) in the envelope, and `checksum` (u32) follows that; corrupt
+        // the checksum itself, leaving the polynomial and payload intact.
+        bytes[8..12].copy_from_slice(&0xdead_beefu32.to_le_bytes());
+
+        let result = Fingerprint::deserialize_any_version(&bytes);
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,220 @@
+//! Catalog-scale nearest-match lookup against many stored `OptimizedFingerprint`s.
+//!
+//! Reuses the landmark-hash inverted-index/offset-histogram (constellation)
+//! voting technique from `fingerprint::FingerprintMatcher` to find temporally
+//! consistent candidates cheaply, then rescores only those candidates with
+//! `OptimizedFingerprint::robust_similarity` so the final ranking reflects
+//! `feature_weights`/`feature_confidence` rather than raw hash collisions.
+
+use std::collections::HashMap;
+use crate::optimized_fingerprint::OptimizedFingerprint;
+
+/// Width of each offset-histogram bin, in seconds. Matches
+/// `FingerprintMatcher::find_matches`'s bin size.
+const OFFSET_BIN_SIZE_SECONDS: f32 = 0.05;
+
+/// A single ranked candidate returned by `FingerprintDb::find_matches`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    pub track_id: usize,
+    /// Feature-weighted similarity from `OptimizedFingerprint::robust_similarity`
+    pub score: f32,
+    /// Average overall confidence of the query and matched reference fingerprints
+    pub confidence: f32,
+    /// Time offset, in seconds, that best aligns the query with the matched track
+    pub offset_seconds: f32,
+}
+
+/// An inverted index of reference fingerprints' landmark hashes, answering
+/// "which known track does this query clip match?" against a whole catalog
+/// instead of comparing two fingerprints pairwise.
+#[derive(Default)]
+pub struct FingerprintDb {
+    hash_table: HashMap<u64, Vec<(usize, f32)>>, // hash -> [(track_id, time_offset)]
+    tracks: HashMap<usize, OptimizedFingerprint>,
+}
+
+impl FingerprintDb {
+    /// Create an empty database
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index a reference fingerprint's landmark hashes under `track_id`
+    pub fn insert(&mut self, track_id: usize, fingerprint: OptimizedFingerprint) {
+        for (hash, &time_offset) in fingerprint
+            .hash_fingerprint
+            .hashes
+            .iter()
+            .zip(fingerprint.hash_fingerprint.time_offsets.iter())
+        {
+            self.hash_table.entry(*hash).or_insert_with(Vec::new).push((track_id, time_offset));
+        }
+
+        self.tracks.insert(track_id, fingerprint);
+    }
+
+    /// Remove every posting and stored fingerprint belonging to `track_id`
+    pub fn remove(&mut self, track_id: usize) {
+        self.hash_table.retain(|_, postings| {
+            postings.retain(|&(id, _)| id != track_id);
+            !postings.is_empty()
+        });
+
+        self.tracks.remove(&track_id);
+    }
+
+    /// Number of tracks currently indexed
+    pub fn len(&self) -> usize {
+        self.tracks.len()
+    }
+
+    /// Whether the database holds no tracks
+    pub fn is_empty(&self) -> bool {
+        self.tracks.is_empty()
+    }
+
+    /// Find tracks matching `query`, ranked by feature-weighted similarity.
+    ///
+    /// A candidate's per-hash landmark matches are histogrammed by time-offset
+    /// bin (genuine matches spike in one bin, since every true landmark pair
+    /// shares the same absolute time shift; spurious collisions scatter
+    /// roughly uniformly). Candidates whose tallest bin doesn't clear
+    /// `min_votes` are dropped before the more expensive rescore, so a large
+    /// catalog only pays for `robust_similarity` on plausible matches.
+    pub fn find_matches(&self, query: &OptimizedFingerprint, min_votes: usize) -> Vec<Match> {
+        let mut track_offset_histograms: HashMap<usize, HashMap<i64, u32>> = HashMap::new();
+
+        for (hash, &query_time) in query.hash_fingerprint.hashes.iter().zip(query.hash_fingerprint.time_offsets.iter()) {
+            if let Some(postings) = self.hash_table.get(hash) {
+                for &(track_id, db_time) in postings {
+                    let delta = db_time - query_time;
+                    let bin = (delta / OFFSET_BIN_SIZE_SECONDS).round() as i64;
+
+                    *track_offset_histograms.entry(track_id).or_insert_with(HashMap::new).entry(bin).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut matches: Vec<Match> = track_offset_histograms
+            .into_iter()
+            .filter_map(|(track_id, histogram)| {
+                let (&peak_bin, &votes) = histogram.iter().max_by_key(|&(_, count)| count)?;
+                if (votes as usize) < min_votes {
+                    return None;
+                }
+
+                let reference = self.tracks.get(&track_id)?;
+                let score = query.robust_similarity(reference);
+                let confidence = (query.get_overall_confidence() + reference.get_overall_confidence()) / 2.0;
+
+                Some(Match {
+                    track_id,
+                    score,
+                    confidence,
+                    offset_seconds: peak_bin as f32 * OFFSET_BIN_SIZE_SECONDS,
+                })
+            })
+            .collect();
+
+        matches.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::{Fingerprint, FingerprintMetadata};
+    use crate::optimized_fingerprint::{FeatureConfidence, FeatureWeights, KeyEstimate, OptimizedFingerprint, ProcessingMetadata};
+
+    fn make_test_fingerprint(hashes: Vec<u64>, time_offsets: Vec<f32>) -> OptimizedFingerprint {
+        OptimizedFingerprint {
+            hash_fingerprint: Fingerprint {
+                hashes,
+                time_offsets,
+                peaks: Vec::new(),
+                metadata: FingerprintMetadata {
+                    sample_rate: 44100,
+                    duration: 1.0,
+                    num_bins: 2048,
+                    window_size: 4096,
+                    overlap: 0.5,
+                    key: None,
+                },
+            },
+            mfcc_features: vec![0.1, 0.2, 0.3],
+            timbre_mean: vec![0.1, 0.2, 0.3],
+            timbre_variance: vec![0.01, 0.01, 0.01],
+            chroma_features: vec![0.5; 12],
+            rhythm_features: vec![120.0, 0.5, 0.6],
+            pitch_features: vec![0.0, 1.0, 2.0],
+            key_estimate: KeyEstimate { tonic: 0, is_major: true, strength: 0.9 },
+            feature_weights: FeatureWeights {
+                hash_weight: 0.25,
+                mfcc_weight: 0.2,
+                chroma_weight: 0.15,
+                rhythm_weight: 0.1,
+                pitch_weight: 0.15,
+                language_weight: 0.075,
+                temporal_weight: 0.075,
+            },
+            feature_confidence: FeatureConfidence {
+                hash_confidence: 0.8,
+                mfcc_confidence: 0.8,
+                chroma_confidence: 0.8,
+                rhythm_confidence: 0.8,
+                pitch_confidence: 0.8,
+                language_confidence: 0.8,
+                temporal_confidence: 0.8,
+            },
+            processing_metadata: ProcessingMetadata {
+                processing_time_ms: 50.0,
+                memory_usage_mb: 10.0,
+                simd_operations: 1000,
+                cache_hit_ratio: 0.85,
+            },
+        }
+    }
+
+    #[test]
+    fn test_find_matches_returns_empty_for_unknown_track() {
+        let db = FingerprintDb::new();
+        let query = make_test_fingerprint(vec![1, 2, 3], vec![0.0, 0.1, 0.2]);
+
+        assert!(db.find_matches(&query, 1).is_empty());
+    }
+
+    #[test]
+    fn test_find_matches_finds_indexed_track_with_consistent_offset() {
+        let mut db = FingerprintDb::new();
+        db.insert(1, make_test_fingerprint(vec![1, 2, 3, 4], vec![0.0, 0.1, 0.2, 0.3]));
+
+        let query = make_test_fingerprint(vec![1, 2, 3, 4], vec![1.0, 1.1, 1.2, 1.3]);
+        let matches = db.find_matches(&query, 2);
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].track_id, 1);
+        assert!((matches[0].offset_seconds - (-1.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_find_matches_drops_candidates_below_min_votes() {
+        let mut db = FingerprintDb::new();
+        db.insert(1, make_test_fingerprint(vec![1, 2], vec![0.0, 0.1]));
+
+        let query = make_test_fingerprint(vec![1, 2], vec![0.0, 0.1]);
+        assert!(db.find_matches(&query, 5).is_empty());
+    }
+
+    #[test]
+    fn test_remove_drops_track_from_future_matches() {
+        let mut db = FingerprintDb::new();
+        db.insert(1, make_test_fingerprint(vec![1, 2, 3], vec![0.0, 0.1, 0.2]));
+        db.remove(1);
+
+        let query = make_test_fingerprint(vec![1, 2, 3], vec![0.0, 0.1, 0.2]);
+        assert!(db.find_matches(&query, 1).is_empty());
+        assert!(db.is_empty());
+    }
+}
@@ -0,0 +1,421 @@
+//! In-memory HNSW (Hierarchical Navigable Small World) approximate nearest-neighbor
+//! index — see Malkov & Yashunin, "Efficient and robust approximate nearest neighbor
+//! search using Hierarchical Navigable Small World graphs". Used by `vector_db`'s
+//! `LocalVectorIndex` as an offline alternative to the Pinecone-backed search.
+
+use crate::distance::cosine_distance;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Tuning parameters for `HnswIndex`, following the values suggested by the
+/// original HNSW paper.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct HnswConfig {
+    /// Max neighbors per node at layers above 0 (layer 0 keeps `2 * m`).
+    pub m: usize,
+    /// Candidate set size explored while inserting a new node.
+    pub ef_construction: usize,
+    /// Candidate set size explored while searching, when the caller asks for
+    /// fewer results than this.
+    pub ef_search: usize,
+}
+
+impl Default for HnswConfig {
+    fn default() -> Self {
+        Self { m: 16, ef_construction: 200, ef_search: 64 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HnswNode {
+    id: String,
+    vector: Vec<f32>,
+    /// Per-layer neighbor lists (`neighbors[layer]`), holding indices into
+    /// `HnswIndex::nodes`; `neighbors.len() - 1` is this node's max layer.
+    neighbors: Vec<Vec<usize>>,
+    /// Tombstoned rather than removed outright, so other nodes can keep
+    /// routing through it; filtered out of `search`'s results.
+    deleted: bool,
+}
+
+/// An in-memory HNSW graph over `Vec<f32>` vectors, compared by cosine distance.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HnswIndex {
+    config: HnswConfig,
+    ml: f32,
+    nodes: Vec<HnswNode>,
+    id_to_index: HashMap<String, usize>,
+    entry_point: Option<usize>,
+    rng_state: u64,
+}
+
+impl HnswIndex {
+    pub fn new(config: HnswConfig) -> Self {
+        let ml = 1.0 / (config.m as f32).ln();
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0x9E3779B97F4A7C15)
+            | 1; // xorshift requires a nonzero state
+
+        Self {
+            config,
+            ml,
+            nodes: Vec::new(),
+            id_to_index: HashMap::new(),
+            entry_point: None,
+            rng_state: seed,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.iter().filter(|node| !node.deleted).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Insert (or, if `id` already exists, replace) a vector under `id`.
+    pub fn insert(&mut self, id: String, vector: Vec<f32>) {
+        if let Some(&existing) = self.id_to_index.get(&id) {
+            self.nodes[existing].deleted = true;
+        }
+
+        let level = self.next_level();
+        let new_index = self.nodes.len();
+
+        let Some(entry_idx) = self.entry_point else {
+            self.nodes.push(HnswNode { id: id.clone(), vector, neighbors: vec![Vec::new(); level + 1], deleted: false });
+            self.id_to_index.insert(id, new_index);
+            self.entry_point = Some(new_index);
+            return;
+        };
+
+        let entry_level = self.nodes[entry_idx].neighbors.len() - 1;
+        let mut current_nearest = entry_idx;
+
+        // Greedily descend to a single nearest neighbor down to one layer above
+        // where the new node will actually start connecting.
+        for layer in (level + 1..=entry_level).rev() {
+            if let Some(&(best, _)) = self.search_layer(&vector, &[current_nearest], 1, layer).first() {
+                current_nearest = best;
+            }
+        }
+
+        let mut neighbors_per_layer = vec![Vec::new(); level + 1];
+
+        // Beam-search each layer the new node participates in, select a diverse
+        // neighbor set, and wire up bidirectional edges (pruning any neighbor
+        // that becomes over-full back down to its own best connections).
+        for layer in (0..=level.min(entry_level)).rev() {
+            let candidates = self.search_layer(&vector, &[current_nearest], self.config.ef_construction, layer);
+            let max_connections = if layer == 0 { self.config.m * 2 } else { self.config.m };
+            let selected = self.select_neighbors_heuristic(candidates.clone(), max_connections);
+
+            for &neighbor in &selected {
+                self.nodes[neighbor].neighbors[layer].push(new_index);
+                if self.nodes[neighbor].neighbors[layer].len() > max_connections {
+                    self.prune_connections(neighbor, layer, max_connections);
+                }
+            }
+            neighbors_per_layer[layer] = selected;
+
+            if let Some(&(best, _)) = candidates.first() {
+                current_nearest = best;
+            }
+        }
+
+        self.nodes.push(HnswNode { id: id.clone(), vector, neighbors: neighbors_per_layer, deleted: false });
+        self.id_to_index.insert(id, new_index);
+
+        if level > entry_level {
+            self.entry_point = Some(new_index);
+        }
+    }
+
+    /// Look up the vector previously stored under `id`, if any and not deleted.
+    pub fn get(&self, id: &str) -> Option<Vec<f32>> {
+        let &idx = self.id_to_index.get(id)?;
+        if self.nodes[idx].deleted {
+            return None;
+        }
+        Some(self.nodes[idx].vector.clone())
+    }
+
+    /// Tombstone `id` so it no longer appears in `search` results. Returns
+    /// whether `id` was present.
+    pub fn delete(&mut self, id: &str) -> bool {
+        match self.id_to_index.remove(id) {
+            Some(idx) => {
+                self.nodes[idx].deleted = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Return the `top_k` nearest (id, cosine distance) pairs to `query`.
+    pub fn search(&self, query: &[f32], top_k: usize) -> Vec<(String, f32)> {
+        let Some(entry_idx) = self.entry_point else {
+            return Vec::new();
+        };
+        let entry_level = self.nodes[entry_idx].neighbors.len() - 1;
+        let mut current_nearest = entry_idx;
+
+        for layer in (1..=entry_level).rev() {
+            if let Some(&(best, _)) = self.search_layer(query, &[current_nearest], 1, layer).first() {
+                current_nearest = best;
+            }
+        }
+
+        let ef = self.config.ef_search.max(top_k);
+        let mut found = self.search_layer(query, &[current_nearest], ef, 0);
+        found.retain(|&(idx, _)| !self.nodes[idx].deleted);
+        found.truncate(top_k);
+
+        found.into_iter().map(|(idx, dist)| (self.nodes[idx].id.clone(), dist)).collect()
+    }
+
+    pub fn save_to_file(&self, path: &Path) -> anyhow::Result<()> {
+        let bytes = bincode::serialize(self)?;
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    pub fn load_from_file(path: &Path) -> anyhow::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Ok(bincode::deserialize(&bytes)?)
+    }
+
+    /// `l = floor(-ln(uniform(0,1)) * mL)`, so higher layers are exponentially rarer.
+    fn next_level(&mut self) -> usize {
+        let uniform = self.next_uniform();
+        (-uniform.ln() * self.ml) as usize
+    }
+
+    /// xorshift64, mapped to a `(0, 1)` exclusive uniform float.
+    fn next_uniform(&mut self) -> f32 {
+        self.rng_state ^= self.rng_state << 13;
+        self.rng_state ^= self.rng_state >> 7;
+        self.rng_state ^= self.rng_state << 17;
+
+        ((self.rng_state >> 11) as f64 / (1u64 << 53) as f64).clamp(1e-10, 1.0 - 1e-10) as f32
+    }
+
+    /// Greedy best-first beam search of a single layer, starting from
+    /// `entry_points`, keeping a result set of at most `ef` (id index, distance)
+    /// pairs sorted nearest-first. Mirrors the SEARCH-LAYER algorithm from the
+    /// HNSW paper, implemented over plain `Vec`s since the graphs here are small.
+    fn search_layer(&self, query: &[f32], entry_points: &[usize], ef: usize, layer: usize) -> Vec<(usize, f32)> {
+        let mut visited: std::collections::HashSet<usize> = entry_points.iter().copied().collect();
+        let mut candidates: Vec<(usize, f32)> = entry_points
+            .iter()
+            .map(|&idx| (idx, cosine_distance(query, &self.nodes[idx].vector)))
+            .collect();
+        let mut found = candidates.clone();
+        candidates.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        found.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+        while !candidates.is_empty() {
+            let (current, current_dist) = candidates.remove(0);
+
+            if found.len() >= ef {
+                let worst = found[found.len() - 1].1;
+                if current_dist > worst {
+                    break;
+                }
+            }
+
+            let Some(neighbor_layer) = self.nodes[current].neighbors.get(layer) else {
+                continue;
+            };
+
+            for &neighbor in neighbor_layer {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+
+                let dist = cosine_distance(query, &self.nodes[neighbor].vector);
+                let worst = found.last().map(|&(_, d)| d).unwrap_or(f32::INFINITY);
+
+                if found.len() < ef || dist < worst {
+                    let insert_at = candidates.partition_point(|&(_, d)| d < dist);
+                    candidates.insert(insert_at, (neighbor, dist));
+
+                    let insert_at = found.partition_point(|&(_, d)| d < dist);
+                    found.insert(insert_at, (neighbor, dist));
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Reselect `node_idx`'s neighbors at `layer` down to its `max_connections`
+    /// best, via the same diversity heuristic used on insert.
+    fn prune_connections(&mut self, node_idx: usize, layer: usize, max_connections: usize) {
+        if self.nodes[node_idx].neighbors[layer].len() <= max_connections {
+            return;
+        }
+
+        let node_vector = self.nodes[node_idx].vector.clone();
+        let candidates: Vec<(usize, f32)> = self.nodes[node_idx].neighbors[layer]
+            .iter()
+            .map(|&idx| (idx, cosine_distance(&node_vector, &self.nodes[idx].vector)))
+            .collect();
+
+        self.nodes[node_idx].neighbors[layer] = self.select_neighbors_heuristic(candidates, max_connections);
+    }
+
+    /// Select up to `m` of `candidates` (node index, distance-to-query), preferring
+    /// a diverse spread over the single closest cluster: a candidate is kept only
+    /// if it's closer to the query than to every neighbor already selected (i.e.
+    /// it isn't redundant with something already picked), falling back to filling
+    /// any remaining slots with the closest leftovers so layers don't end up
+    /// under-connected when every candidate fails that check.
+    fn select_neighbors_heuristic(&self, mut candidates: Vec<(usize, f32)>, m: usize) -> Vec<usize> {
+        candidates.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+        let mut selected: Vec<(usize, f32)> = Vec::new();
+        let mut leftovers: Vec<(usize, f32)> = Vec::new();
+
+        for (candidate, dist_to_query) in candidates {
+            if selected.len() >= m {
+                leftovers.push((candidate, dist_to_query));
+                continue;
+            }
+
+            let is_diverse = selected.iter().all(|&(other, _)| {
+                cosine_distance(&self.nodes[candidate].vector, &self.nodes[other].vector) > dist_to_query
+            });
+
+            if is_diverse {
+                selected.push((candidate, dist_to_query));
+            } else {
+                leftovers.push((candidate, dist_to_query));
+            }
+        }
+
+        for (candidate, dist_to_query) in leftovers {
+            if selected.len() >= m {
+                break;
+            }
+            selected.push((candidate, dist_to_query));
+        }
+
+        selected.into_iter().map(|(idx, _)| idx).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_vector(dims: usize, axis: usize) -> Vec<f32> {
+        let mut v = vec![0.0f32; dims];
+        v[axis] = 1.0;
+        v
+    }
+
+    #[test]
+    fn test_search_returns_exact_match_first() {
+        let mut index = HnswIndex::new(HnswConfig::default());
+        for i in 0..20 {
+            index.insert(format!("song-{i}"), unit_vector(20, i));
+        }
+
+        let results = index.search(&unit_vector(20, 7), 3);
+        assert_eq!(results[0].0, "song-7");
+        assert!(results[0].1 < 1e-5);
+    }
+
+    #[test]
+    fn test_search_respects_top_k() {
+        let mut index = HnswIndex::new(HnswConfig::default());
+        for i in 0..50 {
+            index.insert(format!("song-{i}"), unit_vector(50, i));
+        }
+
+        let results = index.search(&unit_vector(50, 0), 5);
+        assert_eq!(results.len(), 5);
+    }
+
+    #[test]
+    fn test_delete_removes_node_from_results() {
+        let mut index = HnswIndex::new(HnswConfig::default());
+        for i in 0..10 {
+            index.insert(format!("song-{i}"), unit_vector(10, i));
+        }
+
+        assert!(index.delete("song-3"));
+        let results = index.search(&unit_vector(10, 3), 10);
+        assert!(!results.iter().any(|(id, _)| id == "song-3"));
+    }
+
+    #[test]
+    fn test_delete_missing_id_returns_false() {
+        let mut index = HnswIndex::new(HnswConfig::default());
+        index.insert("song-0".to_string(), unit_vector(4, 0));
+        assert!(!index.delete("does-not-exist"));
+    }
+
+    #[test]
+    fn test_insert_overwrites_existing_id() {
+        let mut index = HnswIndex::new(HnswConfig::default());
+        index.insert("song-0".to_string(), unit_vector(4, 0));
+        index.insert("song-0".to_string(), unit_vector(4, 2));
+
+        assert_eq!(index.len(), 1);
+        let results = index.search(&unit_vector(4, 2), 1);
+        assert_eq!(results[0].0, "song-0");
+    }
+
+    #[test]
+    fn test_len_and_is_empty() {
+        let mut index = HnswIndex::new(HnswConfig::default());
+        assert!(index.is_empty());
+
+        index.insert("song-0".to_string(), unit_vector(4, 0));
+        assert_eq!(index.len(), 1);
+        assert!(!index.is_empty());
+    }
+
+    #[test]
+    fn test_search_on_empty_index_returns_empty() {
+        let index = HnswIndex::new(HnswConfig::default());
+        assert!(index.search(&unit_vector(4, 0), 5).is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trips_search_results() {
+        let mut index = HnswIndex::new(HnswConfig::default());
+        for i in 0..15 {
+            index.insert(format!("song-{i}"), unit_vector(15, i));
+        }
+
+        let dir = std::env::temp_dir().join(format!("hnsw_test_{:p}", &index));
+        index.save_to_file(&dir).unwrap();
+        let reloaded = HnswIndex::load_from_file(&dir).unwrap();
+        std::fs::remove_file(&dir).ok();
+
+        assert_eq!(index.search(&unit_vector(15, 4), 3), reloaded.search(&unit_vector(15, 4), 3));
+    }
+
+    #[test]
+    fn test_level_assignment_favors_layer_zero() {
+        let mut index = HnswIndex::new(HnswConfig::default());
+        let mut zero_count = 0;
+        for _ in 0..200 {
+            if index.next_level() == 0 {
+                zero_count += 1;
+            }
+        }
+
+        assert!(zero_count > 100, "expected most assigned levels to be 0, got {zero_count}/200");
+    }
+}
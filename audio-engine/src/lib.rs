@@ -32,10 +32,19 @@
 //! const fingerprint = processor.process_audio(audioData);
 //! ```
 
+pub mod analysis;
 pub mod audio;
+pub mod cue;
+pub mod decode;
+pub mod distance;
+pub mod embedding;
 pub mod fingerprint;
+pub mod hnsw;
 pub mod advanced_fingerprint;
 pub mod optimized_fingerprint;
+pub mod compact_fingerprint;
+pub mod fingerprint_db;
+pub mod metrics_sink;
 pub mod similarity;
 pub mod database;
 pub mod config;
@@ -58,6 +67,13 @@ pub struct AudioEngine {
     fingerprint_cache: Arc<RwLock<lru::LruCache<String, fingerprint::Fingerprint>>>,
 }
 
+/// A single step of a generated playlist, alongside the perceptual distance
+/// from the previous track (0.0 for the seed itself)
+pub struct PlaylistEntry {
+    pub song: database::Song,
+    pub distance_from_previous: f32,
+}
+
 impl AudioEngine {
     /// Create a new audio engine instance
     pub async fn new() -> Result<Self, error::AudioEngineError> {
@@ -157,14 +173,130 @@ impl AudioEngine {
         Ok(best_match)
     }
 
+    /// Find songs that sound similar to `audio_data` by perceptual feature distance
+    /// (tempo, spectral shape, loudness, chroma) rather than `recognize`'s exact-hash
+    /// match — a recommendation/clustering query, not a "what song is this" query.
+    pub async fn recognize_similar(&self, audio_data: &[f32], k: usize) -> Result<Vec<database::Song>, error::AudioEngineError> {
+        let processed_audio = self.preprocess_audio(audio_data)?;
+        let query_features = analysis::analyze(&processed_audio, self.config.audio.sample_rate)?;
+
+        let vector_results = self.vector_db.search_similar_by_features(
+            &query_features,
+            k as u32,
+            None, // language filter
+            None, // genre filter
+        ).await?;
+
+        let mut songs = Vec::with_capacity(vector_results.len());
+        for result in vector_results {
+            if let Some(song_id_str) = result.metadata.get("song_id") {
+                if let Some(song_id) = song_id_str.as_str() {
+                    if let Ok(uuid) = uuid::Uuid::parse_str(song_id) {
+                        if let Some(song) = self.database.get_song(&uuid).await? {
+                            songs.push(song);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(songs)
+    }
+
+    /// Build a smoothly-flowing playlist starting from `seed_song_id`, greedily
+    /// picking the unvisited library song closest (in perceptual feature space)
+    /// to the *current* track at each step, so each consecutive pair is
+    /// sonically close — similar to bliss-rs's playlist generation.
+    ///
+    /// `dedup_by_artist` skips a candidate whose artist matches any of the
+    /// previous `N` picks. `max_transition_distance` stops the playlist early
+    /// once the nearest remaining candidate is farther than the cutoff.
+    pub async fn generate_playlist(
+        &self,
+        seed_song_id: uuid::Uuid,
+        length: usize,
+        dedup_by_artist: Option<usize>,
+        max_transition_distance: Option<f32>,
+    ) -> Result<Vec<PlaylistEntry>, error::AudioEngineError> {
+        let seed_song = self.database.get_song(&seed_song_id).await?
+            .ok_or(error::AudioEngineError::SongNotFound { song_id: seed_song_id.to_string() })?;
+
+        let mut visited = std::collections::HashSet::new();
+        visited.insert(seed_song_id);
+        let mut recent_artists: std::collections::VecDeque<String> = std::collections::VecDeque::new();
+        recent_artists.push_back(seed_song.artist.clone());
+
+        let mut playlist = vec![PlaylistEntry {
+            song: seed_song,
+            distance_from_previous: 0.0,
+        }];
+
+        let mut current_id = seed_song_id;
+
+        while playlist.len() < length {
+            let candidate_pool = (visited.len() + 20) as u32;
+            let mut candidates = self.vector_db.search_similar_by_song_id(current_id, candidate_pool).await?;
+            candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+            let mut picked: Option<(database::Song, f32)> = None;
+
+            for candidate in candidates {
+                if let Some(song_id_str) = candidate.metadata.get("song_id").and_then(|v| v.as_str()) {
+                    if let Ok(candidate_id) = uuid::Uuid::parse_str(song_id_str) {
+                        if visited.contains(&candidate_id) {
+                            continue;
+                        }
+
+                        if let Some(candidate_song) = self.database.get_song(&candidate_id).await? {
+                            if let Some(lookback) = dedup_by_artist {
+                                if recent_artists.iter().rev().take(lookback).any(|artist| *artist == candidate_song.artist) {
+                                    continue;
+                                }
+                            }
+
+                            picked = Some((candidate_song, 1.0 - candidate.score));
+                            break;
+                        }
+                    }
+                }
+            }
+
+            let (next_song, distance) = match picked {
+                Some(picked) => picked,
+                None => break,
+            };
+
+            if let Some(cutoff) = max_transition_distance {
+                if distance > cutoff {
+                    break;
+                }
+            }
+
+            current_id = next_song.id;
+            visited.insert(next_song.id);
+            recent_artists.push_back(next_song.artist.clone());
+            playlist.push(PlaylistEntry {
+                song: next_song,
+                distance_from_previous: distance,
+            });
+        }
+
+        Ok(playlist)
+    }
+
     /// Add new song to database and vector database
     pub async fn add_song(&self, song: database::Song, audio_data: &[f32]) -> Result<(), error::AudioEngineError> {
         // Generate fingerprint for new song
         let fingerprint = self.process_audio(audio_data).await?;
-        
+
+        // Perceptual features, used both for the analysis vector stored
+        // alongside the song and for the vector database below
+        let processed_audio = self.preprocess_audio(audio_data)?;
+        let features = analysis::analyze(&processed_audio, self.config.audio.sample_rate)?;
+
         // Store in PostgreSQL database
-        self.database.add_song(song.clone(), fingerprint.clone()).await?;
-        
+        self.database.add_song(song.clone(), fingerprint.clone(), &features).await?;
+
         // Prepare metadata for vector database
         let mut metadata = std::collections::HashMap::new();
         metadata.insert("title".to_string(), serde_json::Value::String(song.title));
@@ -177,10 +309,14 @@ impl AudioEngine {
             metadata.insert("album".to_string(), serde_json::Value::String(album));
         }
         metadata.insert("popularity_score".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(song.popularity_score as f64).unwrap()));
-        
+
         // Store in vector database
-        self.vector_db.add_fingerprint(song.id, &fingerprint, metadata).await?;
-        
+        self.vector_db.add_fingerprint(song.id, &fingerprint, metadata.clone()).await?;
+
+        // Store the same perceptual feature vector in the vector database too,
+        // so `recognize_similar` has something to search over
+        self.vector_db.add_perceptual_features(song.id, &features, metadata).await?;
+
         info!("New song added to database and vector database");
         Ok(())
     }
@@ -250,8 +386,8 @@ impl AudioEngine {
             let candidate_fingerprint = self.database.get_fingerprint(&candidate.id).await?;
             
             // Calculate similarity score
-            let score = similarity::calculate_similarity(query_fingerprint, &candidate_fingerprint);
-            
+            let score = similarity::calculate_similarity_with(query_fingerprint, &candidate_fingerprint, &self.config.similarity);
+
             if score > best_score && score > self.config.recognition_threshold {
                 best_score = score;
                 best_match = Some((candidate.clone(), score));
@@ -0,0 +1,119 @@
+//! Pluggable telemetry sink for fingerprint processing metrics, so a
+//! long-running indexing job can get live gauges/counters instead of
+//! polling `OptimizedFingerprint::get_performance_metrics` by hand.
+
+use std::net::{ToSocketAddrs, UdpSocket};
+
+/// A destination for fingerprint processing telemetry. Implementors decide
+/// where gauges/counters end up (statsd, logs, a test double, nowhere).
+pub trait MetricsSink {
+    /// Record the current value of a gauge-style metric
+    fn gauge(&self, name: &str, value: f64);
+    /// Increment a counter-style metric by `count`
+    fn incr(&self, name: &str, count: u64);
+}
+
+/// A `MetricsSink` that discards everything, for callers that don't want
+/// telemetry wired up
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopMetricsSink;
+
+impl MetricsSink for NoopMetricsSink {
+    fn gauge(&self, _name: &str, _value: f64) {}
+    fn incr(&self, _name: &str, _count: u64) {}
+}
+
+/// A `MetricsSink` that ships gauges/counters to a statsd/dogstatsd agent
+/// over UDP using the `name:value|g` / `name:value|c` line protocol, with
+/// optional dogstatsd-style `|#key:value,...` tags appended to every line.
+///
+/// UDP sends are fire-and-forget: a dropped packet (agent down, buffer full)
+/// never fails or blocks the caller, since losing one metrics sample is far
+/// cheaper than making fingerprinting throughput depend on a monitoring
+/// stack being reachable.
+pub struct StatsdMetricsSink {
+    socket: UdpSocket,
+    tags: Vec<(String, String)>,
+}
+
+impl StatsdMetricsSink {
+    /// Connect to a statsd/dogstatsd agent at `addr` (e.g. `"127.0.0.1:8125"`),
+    /// tagging every line emitted with `tags`
+    pub fn connect<A: ToSocketAddrs>(addr: A, tags: &[(&str, &str)]) -> std::io::Result<Self> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.connect(addr)?;
+
+        Ok(StatsdMetricsSink {
+            socket,
+            tags: tags.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect(),
+        })
+    }
+
+    fn send_line(&self, line: String) {
+        // Best-effort: a lost metrics sample shouldn't affect the caller.
+        let _ = self.socket.send(line.as_bytes());
+    }
+
+    fn tag_suffix(&self) -> String {
+        if self.tags.is_empty() {
+            return String::new();
+        }
+
+        let rendered = self.tags.iter().map(|(key, value)| format!("{key}:{value}")).collect::<Vec<_>>().join(",");
+        format!("|#{rendered}")
+    }
+}
+
+impl MetricsSink for StatsdMetricsSink {
+    fn gauge(&self, name: &str, value: f64) {
+        self.send_line(format!("{name}:{value}|g{}", self.tag_suffix()));
+    }
+
+    fn incr(&self, name: &str, count: u64) {
+        self.send_line(format!("{name}:{count}|c{}", self.tag_suffix()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noop_sink_accepts_any_call_without_panicking() {
+        let sink = NoopMetricsSink;
+        sink.gauge("processing_time_ms", 12.5);
+        sink.incr("fingerprints_generated", 1);
+    }
+
+    #[test]
+    fn test_statsd_sink_sends_gauge_line_over_udp() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let receiver_addr = receiver.local_addr().unwrap();
+        receiver.set_read_timeout(Some(std::time::Duration::from_secs(1))).unwrap();
+
+        let sink = StatsdMetricsSink::connect(receiver_addr, &[("sample_rate", "44100")]).unwrap();
+        sink.gauge("cache_hit_ratio", 0.85);
+
+        let mut buf = [0u8; 256];
+        let (len, _) = receiver.recv_from(&mut buf).unwrap();
+        let received = std::str::from_utf8(&buf[..len]).unwrap();
+
+        assert_eq!(received, "cache_hit_ratio:0.85|g|#sample_rate:44100");
+    }
+
+    #[test]
+    fn test_statsd_sink_sends_counter_line_without_tags() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let receiver_addr = receiver.local_addr().unwrap();
+        receiver.set_read_timeout(Some(std::time::Duration::from_secs(1))).unwrap();
+
+        let sink = StatsdMetricsSink::connect(receiver_addr, &[]).unwrap();
+        sink.incr("fingerprints_generated", 3);
+
+        let mut buf = [0u8; 256];
+        let (len, _) = receiver.recv_from(&mut buf).unwrap();
+        let received = std::str::from_utf8(&buf[..len]).unwrap();
+
+        assert_eq!(received, "fingerprints_generated:3|c");
+    }
+}
@@ -12,7 +12,12 @@ use serde::{Deserialize, Serialize};
 use anyhow::Result;
 use std::f32::consts::PI;
 use ndarray::{Array1, Array2, Array3, Axis};
-use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use super::compact_fingerprint::CompactFingerprint;
+use super::metrics_sink::MetricsSink;
 
 /// Optimized fingerprint with performance improvements
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,10 +26,25 @@ pub struct OptimizedFingerprint {
     pub hash_fingerprint: super::fingerprint::Fingerprint,
     /// Optimized MFCC features
     pub mfcc_features: Vec<f32>,
+    /// Per-coefficient mean of the MFCC frames (length `NUM_MFCC`), modeling
+    /// the track's timbre as a single multivariate Gaussian
+    pub timbre_mean: Vec<f32>,
+    /// Per-coefficient variance of the MFCC frames (length `NUM_MFCC`),
+    /// floored at `TIMBRE_VARIANCE_EPSILON`
+    pub timbre_variance: Vec<f32>,
     /// Chroma features for harmonic analysis
     pub chroma_features: Vec<f32>,
     /// Rhythm features for tempo analysis
     pub rhythm_features: Vec<f32>,
+    /// Per-frame monophonic pitch-class contour from time-domain
+    /// autocorrelation, median-filtered and quantized to 0-11 (or
+    /// `UNVOICED_PITCH_CLASS` for frames with no strong periodicity).
+    /// Captures the melody line explicitly, which helps match cover
+    /// versions and hummed queries where timbre and chroma energy differ
+    /// but the tune doesn't.
+    pub pitch_features: Vec<f32>,
+    /// Estimated musical key (tonic and mode) derived from `chroma_features`
+    pub key_estimate: KeyEstimate,
     /// Learned feature weights
     pub feature_weights: FeatureWeights,
     /// Confidence scores for each feature
@@ -44,6 +64,8 @@ pub struct FeatureWeights {
     pub chroma_weight: f32,
     /// Weight for rhythm similarity
     pub rhythm_weight: f32,
+    /// Weight for pitch-contour similarity
+    pub pitch_weight: f32,
     /// Weight for language-specific features
     pub language_weight: f32,
     /// Weight for temporal features
@@ -61,12 +83,28 @@ pub struct FeatureConfidence {
     pub chroma_confidence: f32,
     /// Confidence in rhythm features
     pub rhythm_confidence: f32,
+    /// Confidence in pitch-contour features, i.e. the fraction of frames
+    /// with a detected pitch
+    pub pitch_confidence: f32,
     /// Confidence in language features
     pub language_confidence: f32,
     /// Confidence in temporal features
     pub temporal_confidence: f32,
 }
 
+/// Estimated musical key, derived from a track's chroma profile via
+/// Krumhansl-Schmuckler key-finding
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct KeyEstimate {
+    /// Tonic pitch class, 0 (C) through 11 (B)
+    pub tonic: u8,
+    /// `true` for a major key, `false` for minor
+    pub is_major: bool,
+    /// Pearson correlation of the chroma profile against the winning
+    /// rotated key template; higher means a more confident key estimate
+    pub strength: f32,
+}
+
 /// Processing metadata for optimization tracking
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessingMetadata {
@@ -74,22 +112,75 @@ pub struct ProcessingMetadata {
     pub processing_time_ms: f32,
     /// Memory usage in MB
     pub memory_usage_mb: f32,
-    /// Number of SIMD operations used
+    /// Number of SIMD-lane-group operations actually executed while
+    /// generating this fingerprint (see `take_simd_op_count`)
     pub simd_operations: u32,
     /// Cache hit ratio
     pub cache_hit_ratio: f32,
 }
 
-/// Global cache for pre-computed values
-static HAMMING_WINDOW_CACHE: OnceLock<HashMap<usize, Vec<f32>>> = OnceLock::new();
-static MEL_FILTER_CACHE: OnceLock<HashMap<(u32, usize, usize), Array2<f32>>> = OnceLock::new();
-static CHROMA_FILTER_CACHE: OnceLock<HashMap<(u32, usize), Array2<f32>>> = OnceLock::new();
+/// Number of MFCC coefficients extracted per frame
+const NUM_MFCC: usize = 13;
+/// Floor applied to per-coefficient MFCC variance so `timbre_divergence`
+/// never divides by (near-)zero on near-constant timbre
+const TIMBRE_VARIANCE_EPSILON: f32 = 1e-4;
+/// Divisor controlling how quickly `timbre_divergence` maps to similarity;
+/// larger values make `robust_similarity` more tolerant of timbre drift
+const TIMBRE_DIVERGENCE_SCALE: f32 = 10.0;
+
+/// Krumhansl-Schmuckler major key profile, tonic-relative (C major)
+const MAJOR_KEY_TEMPLATE: [f32; 12] = [6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88];
+/// Krumhansl-Schmuckler minor key profile, tonic-relative (C minor)
+const MINOR_KEY_TEMPLATE: [f32; 12] = [6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17];
+/// `robust_similarity` bonus applied when both tracks share the same tonic and mode
+const KEY_AGREEMENT_BONUS: f32 = 0.1;
+/// `robust_similarity` bonus applied when the tracks are in relative major/minor keys
+const RELATIVE_KEY_AGREEMENT_BONUS: f32 = 0.05;
+
+/// Global cache for pre-computed values. Reads take a shared lock so
+/// concurrent fingerprinting of multiple files isn't serialized on cache
+/// hits; a miss upgrades to a write lock to insert the newly computed value.
+static HAMMING_WINDOW_CACHE: OnceLock<RwLock<HashMap<usize, Arc<Vec<f32>>>>> = OnceLock::new();
+static MEL_FILTER_CACHE: OnceLock<RwLock<HashMap<(u32, usize, usize), Arc<Array2<f32>>>>> = OnceLock::new();
+static CHROMA_FILTER_CACHE: OnceLock<RwLock<HashMap<(u32, usize), Arc<Array2<f32>>>>> = OnceLock::new();
+
+/// Real hit/miss counters backing `calculate_cache_hit_ratio`
+static CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static CACHE_MISSES: AtomicU64 = AtomicU64::new(0);
+
+/// Number of f32 lanes processed per vectorized step. Four lanes matches a
+/// 128-bit SSE/NEON register; behind the `simd` feature this maps directly
+/// onto `wide::f32x4`, and the scalar fallback still processes the same
+/// chunking so `simd_operations` counts real lane-groups either way.
+const SIMD_LANES: usize = 4;
+
+thread_local! {
+    /// Per-thread count of vectorized lane-groups processed since the last
+    /// `reset_simd_op_count`. `OptimizedFingerprint::generate` resets this
+    /// before extracting features and reads it back into
+    /// `ProcessingMetadata::simd_operations`, so the count reflects work
+    /// actually done for that fingerprint rather than a length-based guess.
+    static SIMD_OP_COUNT: std::cell::Cell<u64> = std::cell::Cell::new(0);
+}
+
+fn record_simd_ops(lane_groups: u64) {
+    SIMD_OP_COUNT.with(|count| count.set(count.get() + lane_groups));
+}
+
+fn reset_simd_op_count() {
+    SIMD_OP_COUNT.with(|count| count.set(0));
+}
+
+fn take_simd_op_count() -> u64 {
+    SIMD_OP_COUNT.with(|count| count.replace(0))
+}
 
 impl OptimizedFingerprint {
     /// Generate optimized fingerprint from audio data
     pub fn generate(audio_data: &[f32], sample_rate: u32) -> Result<Self> {
         let start_time = std::time::Instant::now();
-        
+        reset_simd_op_count();
+
         // Generate base fingerprint
         let hash_fingerprint = super::fingerprint::Fingerprint::generate(audio_data)?;
         
@@ -97,22 +188,26 @@ impl OptimizedFingerprint {
         let mfcc_features = extract_optimized_mfcc_features(audio_data, sample_rate)?;
         let chroma_features = extract_optimized_chroma_features(audio_data, sample_rate)?;
         let rhythm_features = extract_optimized_rhythm_features(audio_data, sample_rate)?;
-        
+        let pitch_features = extract_optimized_pitch_features(audio_data, sample_rate)?;
+        let (timbre_mean, timbre_variance) = compute_timbre_gaussian(&mfcc_features, NUM_MFCC);
+        let key_estimate = estimate_key_from_chroma(&chroma_features);
+
         // Calculate feature weights (learned from training data)
-        let feature_weights = calculate_learned_weights(&mfcc_features, &chroma_features, &rhythm_features);
-        
+        let feature_weights = calculate_learned_weights(&mfcc_features, &chroma_features, &rhythm_features, &pitch_features);
+
         // Calculate feature confidence scores
         let feature_confidence = calculate_feature_confidence(
             &hash_fingerprint,
             &mfcc_features,
             &chroma_features,
             &rhythm_features,
+            &pitch_features,
         );
         
         // Calculate processing metadata
         let processing_time = start_time.elapsed().as_secs_f32() * 1000.0;
         let memory_usage = estimate_memory_usage(audio_data.len());
-        let simd_operations = estimate_simd_operations(audio_data.len());
+        let simd_operations = take_simd_op_count() as u32;
         let cache_hit_ratio = calculate_cache_hit_ratio();
         
         let processing_metadata = ProcessingMetadata {
@@ -125,8 +220,12 @@ impl OptimizedFingerprint {
         Ok(OptimizedFingerprint {
             hash_fingerprint,
             mfcc_features,
+            timbre_mean,
+            timbre_variance,
             chroma_features,
             rhythm_features,
+            pitch_features,
+            key_estimate,
             feature_weights,
             feature_confidence,
             processing_metadata,
@@ -137,23 +236,26 @@ impl OptimizedFingerprint {
     pub fn robust_similarity(&self, other: &OptimizedFingerprint) -> f32 {
         // Calculate individual similarities
         let hash_similarity = self.hash_fingerprint.similarity(&other.hash_fingerprint);
-        let mfcc_similarity = cosine_similarity(&self.mfcc_features, &other.mfcc_features);
+        let mfcc_similarity = (-self.timbre_divergence(other) / TIMBRE_DIVERGENCE_SCALE).exp();
         let chroma_similarity = cosine_similarity(&self.chroma_features, &other.chroma_features);
         let rhythm_similarity = cosine_similarity(&self.rhythm_features, &other.rhythm_features);
-        
+        let pitch_similarity = pitch_contour_similarity(&self.pitch_features, &other.pitch_features);
+
         // Apply confidence weighting
         let weighted_similarities = [
             hash_similarity * self.feature_confidence.hash_confidence * other.feature_confidence.hash_confidence,
             mfcc_similarity * self.feature_confidence.mfcc_confidence * other.feature_confidence.mfcc_confidence,
             chroma_similarity * self.feature_confidence.chroma_confidence * other.feature_confidence.chroma_confidence,
             rhythm_similarity * self.feature_confidence.rhythm_confidence * other.feature_confidence.rhythm_confidence,
+            pitch_similarity * self.feature_confidence.pitch_confidence * other.feature_confidence.pitch_confidence,
         ];
-        
+
         let weights = [
             self.feature_weights.hash_weight,
             self.feature_weights.mfcc_weight,
             self.feature_weights.chroma_weight,
             self.feature_weights.rhythm_weight,
+            self.feature_weights.pitch_weight,
         ];
         
         // Calculate weighted average
@@ -163,14 +265,70 @@ impl OptimizedFingerprint {
             .sum();
         
         let total_weight: f32 = weights.iter().sum();
-        
-        if total_weight > 0.0 {
+
+        let base_similarity = if total_weight > 0.0 {
             weighted_sum / total_weight
         } else {
             0.0
+        };
+
+        (base_similarity + self.key_agreement_bonus(other)).min(1.0)
+    }
+
+    /// Bonus added to `robust_similarity` when the two tracks' estimated
+    /// keys are harmonically compatible: the full bonus for a matching tonic
+    /// and mode, a partial bonus for relative major/minor (e.g. C major and
+    /// A minor), and none otherwise.
+    fn key_agreement_bonus(&self, other: &OptimizedFingerprint) -> f32 {
+        if self.key_estimate.tonic == other.key_estimate.tonic
+            && self.key_estimate.is_major == other.key_estimate.is_major
+        {
+            KEY_AGREEMENT_BONUS
+        } else if self.is_relative_key_of(other) {
+            RELATIVE_KEY_AGREEMENT_BONUS
+        } else {
+            0.0
         }
     }
-    
+
+    /// True if `self` and `other` are in relative major/minor keys (same key
+    /// signature, different tonic/mode), e.g. C major and A minor
+    fn is_relative_key_of(&self, other: &OptimizedFingerprint) -> bool {
+        if self.key_estimate.is_major == other.key_estimate.is_major {
+            return false;
+        }
+
+        let (major_tonic, minor_tonic) = if self.key_estimate.is_major {
+            (self.key_estimate.tonic, other.key_estimate.tonic)
+        } else {
+            (other.key_estimate.tonic, self.key_estimate.tonic)
+        };
+
+        (minor_tonic as i32 - major_tonic as i32).rem_euclid(12) == 9
+    }
+
+    /// Symmetric KL divergence between this track's and `other`'s timbre,
+    /// modeling each as a diagonal-covariance Gaussian over MFCC frames.
+    /// Unlike flattening MFCC frames into one vector and taking cosine
+    /// similarity, this is insensitive to the two tracks having different
+    /// frame counts or frame alignment.
+    pub fn timbre_divergence(&self, other: &OptimizedFingerprint) -> f32 {
+        let dims = self.timbre_mean.len().min(other.timbre_mean.len());
+        if dims == 0 {
+            return 0.0;
+        }
+
+        let mut divergence = 0.0;
+        for i in 0..dims {
+            let (m1, v1) = (self.timbre_mean[i], self.timbre_variance[i]);
+            let (m2, v2) = (other.timbre_mean[i], other.timbre_variance[i]);
+
+            divergence += v1 / v2 + v2 / v1 + (m1 - m2).powi(2) * (1.0 / v1 + 1.0 / v2) - 2.0;
+        }
+
+        0.5 * divergence
+    }
+
     /// Get performance metrics
     pub fn get_performance_metrics(&self) -> HashMap<String, f32> {
         let mut metrics = HashMap::new();
@@ -181,7 +339,19 @@ impl OptimizedFingerprint {
         metrics.insert("overall_confidence".to_string(), self.get_overall_confidence());
         metrics
     }
-    
+
+    /// Push this fingerprint's processing metrics through a `MetricsSink`,
+    /// so a long-running indexing job gets live telemetry instead of having
+    /// to poll `get_performance_metrics` after the fact
+    pub fn publish_metrics(&self, sink: &dyn MetricsSink) {
+        sink.gauge("fingerprint.processing_time_ms", self.processing_metadata.processing_time_ms as f64);
+        sink.gauge("fingerprint.memory_usage_mb", self.processing_metadata.memory_usage_mb as f64);
+        sink.gauge("fingerprint.simd_operations", self.processing_metadata.simd_operations as f64);
+        sink.gauge("fingerprint.cache_hit_ratio", self.processing_metadata.cache_hit_ratio as f64);
+        sink.gauge("fingerprint.overall_confidence", self.get_overall_confidence() as f64);
+        sink.incr("fingerprint.generated", 1);
+    }
+
     /// Get overall confidence score
     pub fn get_overall_confidence(&self) -> f32 {
         let confidences = [
@@ -189,32 +359,96 @@ impl OptimizedFingerprint {
             self.feature_confidence.mfcc_confidence,
             self.feature_confidence.chroma_confidence,
             self.feature_confidence.rhythm_confidence,
+            self.feature_confidence.pitch_confidence,
         ];
-        
+
         confidences.iter().sum::<f32>() / confidences.len() as f32
     }
+
+    /// Fold every weighted sub-feature (hash, MFCC, chroma, rhythm, pitch)
+    /// into a single stable `CompactFingerprint`, cheap enough to use as an
+    /// index key instead of comparing each feature vector individually
+    pub fn compact(&self) -> CompactFingerprint {
+        let hash_component = hash_feature_vector(
+            &self.hash_fingerprint.hashes.iter().map(|&hash| hash as f32).collect::<Vec<_>>(),
+            self.feature_weights.hash_weight,
+        );
+        let mfcc_component = hash_feature_vector(&self.mfcc_features, self.feature_weights.mfcc_weight);
+        let chroma_component = hash_feature_vector(&self.chroma_features, self.feature_weights.chroma_weight);
+        let rhythm_component = hash_feature_vector(&self.rhythm_features, self.feature_weights.rhythm_weight);
+        let pitch_component = hash_feature_vector(&self.pitch_features, self.feature_weights.pitch_weight);
+
+        hash_component
+            .combine(mfcc_component)
+            .combine(chroma_component)
+            .combine(rhythm_component)
+            .combine(pitch_component)
+    }
+}
+
+/// Hash a feature vector (by bit pattern, since `f32` isn't `Hash`) together
+/// with its blend weight, so two tracks whose features agree but whose
+/// learned weights differ still produce different compact fingerprints
+fn hash_feature_vector(values: &[f32], weight: f32) -> CompactFingerprint {
+    let mut hasher = DefaultHasher::new();
+    weight.to_bits().hash(&mut hasher);
+    for value in values {
+        value.to_bits().hash(&mut hasher);
+    }
+
+    CompactFingerprint::from_smaller_hash(hasher.finish())
 }
 
 /// Extract optimized MFCC features with pre-computed windows and optimized DCT
 fn extract_optimized_mfcc_features(audio_data: &[f32], sample_rate: u32) -> Result<Vec<f32>> {
     let window_size = 2048; // Increased for better Indian classical music analysis
     let hop_size = 256;     // Decreased for better temporal resolution
-    let num_mfcc = 13;
-    
+
     // Use pre-computed spectrogram
     let spectrogram = compute_optimized_spectrogram(audio_data, window_size, hop_size, sample_rate)?;
-    
+
     // Use cached mel filter bank
     let mel_filters = get_cached_mel_filter_bank(sample_rate, window_size, 26);
     let mel_spectrogram = apply_mel_filters_optimized(&spectrogram, &mel_filters);
-    
+
     // Apply log and optimized DCT
     let log_mel = mel_spectrogram.mapv(|x| (x + 1e-10).ln());
-    let mfcc = apply_optimized_dct(&log_mel, num_mfcc);
-    
+    let mfcc = apply_optimized_dct(&log_mel, NUM_MFCC);
+
     Ok(mfcc.iter().cloned().collect())
 }
 
+/// Reshape flattened per-frame MFCC coefficients into an `(num_mfcc,
+/// num_frames)` matrix and summarize each coefficient's distribution across
+/// frames as a mean and a variance (floored at `TIMBRE_VARIANCE_EPSILON`),
+/// i.e. a diagonal-covariance Gaussian timbre model for `timbre_divergence`.
+fn compute_timbre_gaussian(mfcc_features: &[f32], num_mfcc: usize) -> (Vec<f32>, Vec<f32>) {
+    if num_mfcc == 0 || mfcc_features.len() < num_mfcc {
+        return (vec![0.0; num_mfcc], vec![TIMBRE_VARIANCE_EPSILON; num_mfcc]);
+    }
+
+    let num_frames = mfcc_features.len() / num_mfcc;
+    let frames = Array2::from_shape_vec(
+        (num_frames, num_mfcc),
+        mfcc_features[..num_frames * num_mfcc].to_vec(),
+    )
+    .expect("mfcc_features was truncated to a multiple of num_mfcc");
+    let coefficients = frames.reversed_axes(); // shape (num_mfcc, num_frames)
+
+    let mut mean = vec![0.0; num_mfcc];
+    let mut variance = vec![TIMBRE_VARIANCE_EPSILON; num_mfcc];
+
+    for (i, coefficient_frames) in coefficients.axis_iter(Axis(0)).enumerate() {
+        let m = coefficient_frames.iter().sum::<f32>() / coefficient_frames.len() as f32;
+        let v = coefficient_frames.iter().map(|&x| (x - m).powi(2)).sum::<f32>() / coefficient_frames.len() as f32;
+
+        mean[i] = m;
+        variance[i] = v.max(TIMBRE_VARIANCE_EPSILON);
+    }
+
+    (mean, variance)
+}
+
 /// Extract optimized chroma features
 fn extract_optimized_chroma_features(audio_data: &[f32], sample_rate: u32) -> Result<Vec<f32>> {
     let window_size = 2048;
@@ -236,6 +470,82 @@ fn extract_optimized_chroma_features(audio_data: &[f32], sample_rate: u32) -> Re
     Ok(chroma_features)
 }
 
+/// Sum per-frame chroma vectors (12 bins each, frame-major in
+/// `chroma_features`) into a single normalized 12-bin pitch-class profile
+fn pitch_class_profile(chroma_features: &[f32]) -> [f32; 12] {
+    let mut profile = [0.0f32; 12];
+
+    for frame in chroma_features.chunks(12) {
+        for (i, &magnitude) in frame.iter().enumerate() {
+            profile[i] += magnitude;
+        }
+    }
+
+    let total: f32 = profile.iter().sum();
+    if total > 0.0 {
+        for bin in profile.iter_mut() {
+            *bin /= total;
+        }
+    }
+
+    profile
+}
+
+/// Rotate a tonic-relative key template so pitch class `tonic` becomes the root
+fn rotate_key_template(template: &[f32; 12], tonic: usize) -> [f32; 12] {
+    let mut rotated = [0.0f32; 12];
+    for (pitch_class, slot) in rotated.iter_mut().enumerate() {
+        *slot = template[(pitch_class + 12 - tonic) % 12];
+    }
+    rotated
+}
+
+/// Estimate musical key by correlating a chroma-derived pitch-class profile
+/// against all 24 rotations of the Krumhansl-Schmuckler major/minor templates
+fn estimate_key_from_chroma(chroma_features: &[f32]) -> KeyEstimate {
+    let profile = pitch_class_profile(chroma_features);
+
+    let mut best = KeyEstimate { tonic: 0, is_major: true, strength: f32::MIN };
+
+    for tonic in 0..12 {
+        let major_correlation = pearson_correlation(&profile, &rotate_key_template(&MAJOR_KEY_TEMPLATE, tonic));
+        if major_correlation > best.strength {
+            best = KeyEstimate { tonic: tonic as u8, is_major: true, strength: major_correlation };
+        }
+
+        let minor_correlation = pearson_correlation(&profile, &rotate_key_template(&MINOR_KEY_TEMPLATE, tonic));
+        if minor_correlation > best.strength {
+            best = KeyEstimate { tonic: tonic as u8, is_major: false, strength: minor_correlation };
+        }
+    }
+
+    best
+}
+
+/// Pearson correlation coefficient between two equal-length slices
+fn pearson_correlation(a: &[f32; 12], b: &[f32; 12]) -> f32 {
+    let mean_a = a.iter().sum::<f32>() / a.len() as f32;
+    let mean_b = b.iter().sum::<f32>() / b.len() as f32;
+
+    let mut covariance = 0.0;
+    let mut variance_a = 0.0;
+    let mut variance_b = 0.0;
+
+    for i in 0..a.len() {
+        let da = a[i] - mean_a;
+        let db = b[i] - mean_b;
+        covariance += da * db;
+        variance_a += da * da;
+        variance_b += db * db;
+    }
+
+    if variance_a <= 0.0 || variance_b <= 0.0 {
+        0.0
+    } else {
+        covariance / (variance_a.sqrt() * variance_b.sqrt())
+    }
+}
+
 /// Extract optimized rhythm features
 fn extract_optimized_rhythm_features(audio_data: &[f32], sample_rate: u32) -> Result<Vec<f32>> {
     let window_size = 1024;
@@ -250,17 +560,12 @@ fn extract_optimized_rhythm_features(audio_data: &[f32], sample_rate: u32) -> Re
     
     // Calculate onset strength with SIMD
     let onset_strength = calculate_onset_strength_simd(&percussion_spectrum);
-    
-    // Estimate tempo using optimized autocorrelation
-    let tempo = estimate_tempo_optimized(&onset_strength, sample_rate, hop_size);
-    
-    // Extract rhythmic patterns
-    let rhythmic_patterns = extract_rhythmic_patterns_optimized(&onset_strength, tempo);
-    
-    let mut features = vec![tempo];
-    features.extend(rhythmic_patterns);
-    
-    Ok(features)
+
+    // Characterize rhythm by its beat spectrum rather than a single BPM
+    // estimate, so similarity is robust to half/double-tempo aliasing
+    let frame_rate = sample_rate as f32 / hop_size as f32;
+
+    Ok(extract_beat_spectrum(&onset_strength, frame_rate))
 }
 
 /// Compute optimized spectrogram with pre-computed windows
@@ -311,81 +616,261 @@ fn compute_optimized_spectrogram(
     Ok(spectrogram)
 }
 
-/// Get cached Hamming window
-fn get_cached_hamming_window(window_size: usize) -> Vec<f32> {
-    let cache = HAMMING_WINDOW_CACHE.get_or_init(|| HashMap::new());
-    
-    if let Some(window) = cache.get(&window_size) {
-        window.clone()
-    } else {
-        let window: Vec<f32> = (0..window_size)
-            .map(|i| {
-                0.54 - 0.46 * (2.0 * PI * i as f32 / (window_size - 1) as f32).cos()
-            })
-            .collect();
-        
-        // Note: In a real implementation, we would need to handle cache updates
-        // For now, we'll compute it each time
-        window
+/// Get the cached Hamming window for `window_size`, computing and inserting
+/// it on a cache miss
+fn get_cached_hamming_window(window_size: usize) -> Arc<Vec<f32>> {
+    let cache = HAMMING_WINDOW_CACHE.get_or_init(|| RwLock::new(HashMap::new()));
+
+    if let Some(window) = cache.read().unwrap().get(&window_size) {
+        record_cache_hit();
+        return window.clone();
     }
+
+    record_cache_miss();
+    let window = Arc::new(
+        (0..window_size)
+            .map(|i| 0.54 - 0.46 * (2.0 * PI * i as f32 / (window_size - 1) as f32).cos())
+            .collect::<Vec<f32>>(),
+    );
+    cache.write().unwrap().insert(window_size, window.clone());
+    window
 }
 
-/// Get cached mel filter bank
-fn get_cached_mel_filter_bank(sample_rate: u32, window_size: usize, num_filters: usize) -> Array2<f32> {
-    let cache = MEL_FILTER_CACHE.get_or_init(|| HashMap::new());
+/// Get the cached mel filter bank for `(sample_rate, window_size,
+/// num_filters)`, building and inserting it on a cache miss
+fn get_cached_mel_filter_bank(sample_rate: u32, window_size: usize, num_filters: usize) -> Arc<Array2<f32>> {
+    let cache = MEL_FILTER_CACHE.get_or_init(|| RwLock::new(HashMap::new()));
     let key = (sample_rate, window_size, num_filters);
-    
-    if let Some(filters) = cache.get(&key) {
-        filters.clone()
-    } else {
-        // Create mel filter bank (simplified version)
-        let nyquist = sample_rate as f32 / 2.0;
-        let num_bins = window_size / 2 + 1;
-        let mut filter_bank = Array2::zeros((num_filters, num_bins));
-        
-        // Simplified mel filter bank creation
-        for i in 0..num_filters {
-            let start_bin = (i * num_bins) / num_filters;
-            let end_bin = ((i + 1) * num_bins) / num_filters;
-            
-            for bin in start_bin..end_bin {
-                filter_bank[[i, bin]] = 1.0;
+
+    if let Some(filters) = cache.read().unwrap().get(&key) {
+        record_cache_hit();
+        return filters.clone();
+    }
+
+    record_cache_miss();
+    let filters = Arc::new(build_triangular_mel_filter_bank(sample_rate, window_size, num_filters));
+    cache.write().unwrap().insert(key, filters.clone());
+    filters
+}
+
+/// Convert a frequency in Hz to the mel scale
+fn hz_to_mel(hz: f32) -> f32 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+/// Convert a mel-scale value back to Hz
+fn mel_to_hz(mel: f32) -> f32 {
+    700.0 * (10f32.powf(mel / 2595.0) - 1.0)
+}
+
+/// Build a real triangular mel filter bank: `num_filters + 2` mel points are
+/// spaced equally across `[0, Nyquist]`, converted back to Hz and then to FFT
+/// bin indices, and each filter ramps linearly from 0.0 at its left point to
+/// 1.0 at its center and back to 0.0 at its right point.
+fn build_triangular_mel_filter_bank(sample_rate: u32, window_size: usize, num_filters: usize) -> Array2<f32> {
+    let nyquist = sample_rate as f32 / 2.0;
+    let num_bins = window_size / 2 + 1;
+
+    let min_mel = hz_to_mel(0.0);
+    let max_mel = hz_to_mel(nyquist);
+
+    let mel_points: Vec<f32> = (0..num_filters + 2)
+        .map(|i| min_mel + (max_mel - min_mel) * i as f32 / (num_filters + 1) as f32)
+        .collect();
+
+    let bin_points: Vec<usize> = mel_points
+        .iter()
+        .map(|&mel| {
+            let hz = mel_to_hz(mel);
+            ((hz / nyquist) * (num_bins - 1) as f32).round() as usize
+        })
+        .collect();
+
+    let mut filter_bank = Array2::zeros((num_filters, num_bins));
+
+    for i in 0..num_filters {
+        let left = bin_points[i];
+        let center = bin_points[i + 1];
+        let right = bin_points[i + 2];
+
+        for bin in left..center {
+            if center > left {
+                filter_bank[[i, bin]] = (bin - left) as f32 / (center - left) as f32;
+            }
+        }
+        if center < num_bins {
+            filter_bank[[i, center]] = 1.0;
+        }
+        for bin in center..right {
+            if right > center {
+                filter_bank[[i, bin]] = (right - bin) as f32 / (right - center) as f32;
             }
         }
-        
-        filter_bank
     }
+
+    filter_bank
 }
 
-/// Get cached chroma filter bank
-fn get_cached_chroma_filter_bank(sample_rate: u32, window_size: usize) -> Array2<f32> {
-    let cache = CHROMA_FILTER_CACHE.get_or_init(|| HashMap::new());
+/// Get the cached chroma filter bank for `(sample_rate, window_size)`,
+/// building and inserting it on a cache miss
+fn get_cached_chroma_filter_bank(sample_rate: u32, window_size: usize) -> Arc<Array2<f32>> {
+    let cache = CHROMA_FILTER_CACHE.get_or_init(|| RwLock::new(HashMap::new()));
     let key = (sample_rate, window_size);
-    
-    if let Some(filters) = cache.get(&key) {
-        filters.clone()
-    } else {
-        // Create chroma filter bank (simplified version)
-        let num_bins = window_size / 2 + 1;
-        let mut chroma_filters = Array2::zeros((12, num_bins));
-        
-        // Simplified chroma filter bank
-        for bin in 0..num_bins {
-            let chroma_bin = bin % 12;
-            chroma_filters[[chroma_bin, bin]] = 1.0;
+
+    if let Some(filters) = cache.read().unwrap().get(&key) {
+        record_cache_hit();
+        return filters.clone();
+    }
+
+    record_cache_miss();
+    let filters = Arc::new(build_log_frequency_chroma_filter_bank(sample_rate, window_size));
+    cache.write().unwrap().insert(key, filters.clone());
+    filters
+}
+
+/// Frequency of C0, the reference pitch class 0 is measured relative to
+const CHROMA_REFERENCE_HZ: f32 = 16.35;
+/// Standard deviation (in pitch classes) of the Gaussian used to spread each
+/// FFT bin's contribution across neighboring chroma bins
+const CHROMA_GAUSSIAN_SIGMA: f32 = 0.5;
+
+/// Build a log-frequency chroma filter bank: each FFT bin's center frequency
+/// is converted to octaves above `CHROMA_REFERENCE_HZ` and then to a
+/// (fractional) pitch class, with the bin's magnitude spread across nearby
+/// chroma bins by a small Gaussian to reduce semitone-boundary quantization
+/// artifacts. The DC bin and bins below `CHROMA_REFERENCE_HZ` carry no
+/// well-defined pitch class and are skipped.
+fn build_log_frequency_chroma_filter_bank(sample_rate: u32, window_size: usize) -> Array2<f32> {
+    let num_bins = window_size / 2 + 1;
+    let bin_hz = sample_rate as f32 / window_size as f32;
+    let mut chroma_filters = Array2::zeros((12, num_bins));
+
+    for bin in 1..num_bins {
+        let frequency = bin as f32 * bin_hz;
+        if frequency < CHROMA_REFERENCE_HZ {
+            continue;
+        }
+
+        let octaves = (frequency / CHROMA_REFERENCE_HZ).log2();
+        let pitch_class_continuous = 12.0 * octaves;
+        let nearest_pitch_class = pitch_class_continuous.round();
+
+        for offset in -2..=2 {
+            let neighbor = nearest_pitch_class + offset as f32;
+            let distance = pitch_class_continuous - neighbor;
+            let weight = (-0.5 * (distance / CHROMA_GAUSSIAN_SIGMA).powi(2)).exp();
+            let pitch_class = (neighbor as i32).rem_euclid(12) as usize;
+
+            chroma_filters[[pitch_class, bin]] += weight;
         }
-        
-        chroma_filters
     }
+
+    chroma_filters
 }
 
 /// Apply window function with SIMD optimization
 fn apply_window_simd(audio_data: &[f32], window: &[f32]) -> Vec<f32> {
-    // Simplified SIMD-like operation
-    audio_data.iter()
-        .zip(window.iter())
-        .map(|(&sample, &window_val)| sample * window_val)
-        .collect()
+    simd_elementwise_multiply(audio_data, window)
+}
+
+/// Multiply two equal-length slices element-wise, processing `SIMD_LANES`
+/// f32s per step. With the `simd` feature enabled this dispatches to
+/// `wide::f32x4`; without it (the default, since this crate has no manifest
+/// wiring the feature up yet) the same chunking runs scalar, which still
+/// gives LLVM's auto-vectorizer a fixed-width loop to work with.
+#[cfg(feature = "simd")]
+fn simd_elementwise_multiply(a: &[f32], b: &[f32]) -> Vec<f32> {
+    use wide::f32x4;
+
+    let len = a.len().min(b.len());
+    let chunks = len / SIMD_LANES;
+    let mut result = Vec::with_capacity(len);
+
+    for chunk in 0..chunks {
+        let base = chunk * SIMD_LANES;
+        let va = f32x4::from(<[f32; 4]>::try_from(&a[base..base + SIMD_LANES]).unwrap());
+        let vb = f32x4::from(<[f32; 4]>::try_from(&b[base..base + SIMD_LANES]).unwrap());
+        result.extend_from_slice(&(va * vb).to_array());
+    }
+    record_simd_ops(chunks as u64);
+
+    for i in chunks * SIMD_LANES..len {
+        result.push(a[i] * b[i]);
+    }
+
+    result
+}
+
+#[cfg(not(feature = "simd"))]
+fn simd_elementwise_multiply(a: &[f32], b: &[f32]) -> Vec<f32> {
+    let len = a.len().min(b.len());
+    let chunks = len / SIMD_LANES;
+    let mut result = Vec::with_capacity(len);
+
+    for chunk in 0..chunks {
+        let base = chunk * SIMD_LANES;
+        for lane in 0..SIMD_LANES {
+            result.push(a[base + lane] * b[base + lane]);
+        }
+    }
+    record_simd_ops(chunks as u64);
+
+    for i in chunks * SIMD_LANES..len {
+        result.push(a[i] * b[i]);
+    }
+
+    result
+}
+
+/// Sum of element-wise products of two equal-length slices, processing
+/// `SIMD_LANES` f32s per step; see `simd_elementwise_multiply` for the
+/// feature-gating rationale. Used for both dot products and (by passing the
+/// same slice twice) sums of squares.
+#[cfg(feature = "simd")]
+fn simd_dot_product(a: &[f32], b: &[f32]) -> f32 {
+    use wide::f32x4;
+
+    let len = a.len().min(b.len());
+    let chunks = len / SIMD_LANES;
+    let mut acc = f32x4::splat(0.0);
+
+    for chunk in 0..chunks {
+        let base = chunk * SIMD_LANES;
+        let va = f32x4::from(<[f32; 4]>::try_from(&a[base..base + SIMD_LANES]).unwrap());
+        let vb = f32x4::from(<[f32; 4]>::try_from(&b[base..base + SIMD_LANES]).unwrap());
+        acc += va * vb;
+    }
+    record_simd_ops(chunks as u64);
+
+    let mut sum: f32 = acc.to_array().iter().sum();
+    for i in chunks * SIMD_LANES..len {
+        sum += a[i] * b[i];
+    }
+
+    sum
+}
+
+#[cfg(not(feature = "simd"))]
+fn simd_dot_product(a: &[f32], b: &[f32]) -> f32 {
+    let len = a.len().min(b.len());
+    let chunks = len / SIMD_LANES;
+    let mut sum = 0.0;
+
+    for chunk in 0..chunks {
+        let base = chunk * SIMD_LANES;
+        let mut lane_sum = 0.0;
+        for lane in 0..SIMD_LANES {
+            lane_sum += a[base + lane] * b[base + lane];
+        }
+        sum += lane_sum;
+    }
+    record_simd_ops(chunks as u64);
+
+    for i in chunks * SIMD_LANES..len {
+        sum += a[i] * b[i];
+    }
+
+    sum
 }
 
 /// Apply mel filters with optimization
@@ -413,15 +898,14 @@ fn apply_optimized_dct(mel_spectrogram: &Array2<f32>, num_mfcc: usize) -> Array1
 
 /// Apply chroma filters with SIMD optimization
 fn apply_chroma_filters_simd(frame: &Array1<f32>, chroma_filters: &Array2<f32>) -> Vec<f32> {
-    let mut chroma_frame = vec![0.0; 12];
-    
-    for (bin_idx, &magnitude) in frame.iter().enumerate() {
-        for chroma_bin in 0..12 {
-            chroma_frame[chroma_bin] += magnitude * chroma_filters[[chroma_bin, bin_idx]];
-        }
-    }
-    
-    chroma_frame
+    let frame_values: Vec<f32> = frame.iter().copied().collect();
+
+    (0..12)
+        .map(|chroma_bin| {
+            let filter_row: Vec<f32> = chroma_filters.row(chroma_bin).iter().copied().collect();
+            simd_dot_product(&frame_values, &filter_row)
+        })
+        .collect()
 }
 
 /// Get optimized percussion bins
@@ -444,73 +928,206 @@ fn calculate_onset_strength_simd(percussion_spectrum: &Array2<f32>) -> Array1<f3
     let mut onset_strength = Array1::zeros(num_frames);
     
     for frame_idx in 0..num_frames {
-        let frame = percussion_spectrum.column(frame_idx);
-        let energy = frame.iter().map(|&x| x * x).sum::<f32>();
-        onset_strength[frame_idx] = energy;
+        let frame: Vec<f32> = percussion_spectrum.column(frame_idx).iter().copied().collect();
+        onset_strength[frame_idx] = simd_dot_product(&frame, &frame);
     }
     
     onset_strength
 }
 
-/// Estimate tempo with optimized autocorrelation
-fn estimate_tempo_optimized(onset_strength: &Array1<f32>, sample_rate: u32, hop_size: usize) -> f32 {
-    let frame_rate = sample_rate as f32 / hop_size as f32;
-    let min_bpm = 60.0;
-    let max_bpm = 200.0;
-    
-    let min_lag = (60.0 / max_bpm * frame_rate) as usize;
-    let max_lag = (60.0 / min_bpm * frame_rate) as usize;
-    
-    let mut best_lag = min_lag;
-    let mut best_correlation = 0.0;
-    
-    // Optimized autocorrelation with early termination
-    for lag in min_lag..=max_lag.min(onset_strength.len() / 2) {
-        let mut correlation = 0.0;
-        let mut count = 0;
-        
-        for i in 0..onset_strength.len() - lag {
-            correlation += onset_strength[i] * onset_strength[i + lag];
-            count += 1;
-        }
-        
-        if count > 0 {
-            correlation /= count as f32;
-            if correlation > best_correlation {
-                best_correlation = correlation;
-                best_lag = lag;
-            }
+/// Number of samples the beat spectrum is resampled to, so clips of
+/// different durations produce directly comparable `rhythm_features`
+const BEAT_SPECTRUM_LENGTH: usize = 64;
+/// Maximum autocorrelation lag considered when building the beat spectrum
+const BEAT_SPECTRUM_MAX_LAG_SECONDS: f32 = 4.0;
+
+/// Compute a tempo-invariant "beat spectrum": the normalized autocorrelation
+/// of the onset-strength envelope over lags from 0 up to
+/// `BEAT_SPECTRUM_MAX_LAG_SECONDS`, resampled to a fixed length. Because it
+/// captures periodic rhythmic structure rather than committing to a single
+/// BPM estimate, two tracks with a similar groove still compare as similar
+/// even when a tempo estimator would pick a half/double-tempo alias for one
+/// of them.
+fn extract_beat_spectrum(onset_strength: &Array1<f32>, frame_rate: f32) -> Vec<f32> {
+    let max_lag = ((BEAT_SPECTRUM_MAX_LAG_SECONDS * frame_rate) as usize)
+        .min(onset_strength.len().saturating_sub(1));
+    let energy: f32 = onset_strength.iter().map(|&s| s * s).sum();
+
+    let mut beat_spectrum = vec![0.0; max_lag + 1];
+    if energy > 0.0 {
+        for (lag, slot) in beat_spectrum.iter_mut().enumerate() {
+            let correlation: f32 = (0..onset_strength.len() - lag)
+                .map(|t| onset_strength[t] * onset_strength[t + lag])
+                .sum();
+            *slot = correlation / energy;
         }
-        
-        // Early termination if correlation is decreasing
-        if correlation < best_correlation * 0.8 {
-            break;
+    }
+
+    resample_to_fixed_length(&beat_spectrum, BEAT_SPECTRUM_LENGTH)
+}
+
+/// Linearly resample `values` to exactly `length` samples via linear
+/// interpolation, so beat spectra from clips of differing lengths remain
+/// directly comparable by cosine similarity
+fn resample_to_fixed_length(values: &[f32], length: usize) -> Vec<f32> {
+    if values.len() <= 1 {
+        return vec![values.first().copied().unwrap_or(0.0); length];
+    }
+
+    (0..length)
+        .map(|i| {
+            let position = i as f32 * (values.len() - 1) as f32 / (length - 1).max(1) as f32;
+            let lower = position.floor() as usize;
+            let upper = (lower + 1).min(values.len() - 1);
+            let fraction = position - lower as f32;
+
+            values[lower] * (1.0 - fraction) + values[upper] * fraction
+        })
+        .collect()
+}
+
+/// Frame size used for per-frame pitch detection; large enough to contain a
+/// full period down to `PITCH_MIN_HZ`
+const PITCH_FRAME_SIZE: usize = 2048;
+/// Hop between successive pitch-detection frames
+const PITCH_HOP_SIZE: usize = 1024;
+/// Lowest fundamental frequency searched for (bounds the autocorrelation lag)
+const PITCH_MIN_HZ: f32 = 50.0;
+/// Highest fundamental frequency searched for
+const PITCH_MAX_HZ: f32 = 1000.0;
+/// Minimum normalized autocorrelation peak to treat a frame as voiced
+const PITCH_VOICING_THRESHOLD: f32 = 0.3;
+/// Radius (in frames) of the median filter smoothing the raw pitch contour
+const PITCH_MEDIAN_FILTER_RADIUS: usize = 1;
+/// Sentinel stored in `pitch_features` for frames with no detected pitch
+const UNVOICED_PITCH_CLASS: f32 = -1.0;
+
+/// Extract a monophonic pitch-class contour via time-domain autocorrelation:
+/// each frame's fundamental is found from the strongest autocorrelation
+/// peak within the pitch lag range, median-filtered to suppress octave
+/// jitter, then quantized to a pitch class. This is an explicit melody-line
+/// descriptor, complementing the statistical MFCC/chroma features.
+fn extract_optimized_pitch_features(audio_data: &[f32], sample_rate: u32) -> Result<Vec<f32>> {
+    let raw_pitches: Vec<Option<f32>> = audio_data
+        .windows(PITCH_FRAME_SIZE)
+        .step_by(PITCH_HOP_SIZE)
+        .map(|frame| detect_frame_pitch(frame, sample_rate))
+        .collect();
+
+    let smoothed = median_filter_pitch_contour(&raw_pitches);
+
+    Ok(smoothed
+        .iter()
+        .map(|pitch| pitch.map_or(UNVOICED_PITCH_CLASS, quantize_to_pitch_class))
+        .collect())
+}
+
+/// Detect a single frame's fundamental frequency via time-domain
+/// autocorrelation: remove the frame's DC offset, compute
+/// `c[lag] = Σ x[i]·x[i+lag]` normalized by the zero-lag energy over the lag
+/// range covering `PITCH_MIN_HZ..PITCH_MAX_HZ`, and convert the strongest
+/// peak's lag to Hz as `sample_rate / lag`. Returns `None` if no lag clears
+/// `PITCH_VOICING_THRESHOLD` (i.e. the frame is unvoiced or noisy).
+fn detect_frame_pitch(frame: &[f32], sample_rate: u32) -> Option<f32> {
+    let mean = frame.iter().sum::<f32>() / frame.len() as f32;
+    let centered: Vec<f32> = frame.iter().map(|&x| x - mean).collect();
+
+    let min_lag = (sample_rate as f32 / PITCH_MAX_HZ) as usize;
+    let max_lag = ((sample_rate as f32 / PITCH_MIN_HZ) as usize).min(centered.len().saturating_sub(1));
+    if min_lag == 0 || min_lag >= max_lag {
+        return None;
+    }
+
+    let zero_lag_energy = simd_dot_product(&centered, &centered);
+    if zero_lag_energy <= 0.0 {
+        return None;
+    }
+
+    let mut best_lag = None;
+    let mut best_correlation = PITCH_VOICING_THRESHOLD;
+
+    for lag in min_lag..=max_lag {
+        let correlation = simd_dot_product(&centered[..centered.len() - lag], &centered[lag..]);
+        let normalized = correlation / zero_lag_energy;
+
+        if normalized > best_correlation {
+            best_correlation = normalized;
+            best_lag = Some(lag);
         }
     }
-    
-    60.0 * frame_rate / best_lag as f32
+
+    best_lag.map(|lag| sample_rate as f32 / lag as f32)
 }
 
-/// Extract rhythmic patterns with optimization
-fn extract_rhythmic_patterns_optimized(onset_strength: &Array1<f32>, tempo: f32) -> Vec<f32> {
-    let beat_duration = 60.0 / tempo;
-    let frame_rate = 44100.0 / 256.0; // Assuming hop_size = 256
-    let beat_frames = (beat_duration * frame_rate) as usize;
-    
-    let mut patterns = Vec::new();
-    
-    // Extract patterns for 4 beats with optimized indexing
-    for beat in 0..4 {
-        let start_frame = beat * beat_frames;
-        let end_frame = (start_frame + beat_frames).min(onset_strength.len());
-        
-        if start_frame < onset_strength.len() {
-            let beat_energy = onset_strength.slice(ndarray::s![start_frame..end_frame]).sum();
-            patterns.push(beat_energy);
+/// Smooth a raw per-frame pitch contour with a median filter over a
+/// `2 * PITCH_MEDIAN_FILTER_RADIUS + 1`-frame window (ignoring unvoiced
+/// neighbors), reducing the octave jumps autocorrelation pitch tracking is
+/// prone to without discarding genuinely unvoiced frames
+fn median_filter_pitch_contour(pitches: &[Option<f32>]) -> Vec<Option<f32>> {
+    pitches
+        .iter()
+        .enumerate()
+        .map(|(i, &current)| {
+            let start = i.saturating_sub(PITCH_MEDIAN_FILTER_RADIUS);
+            let end = (i + PITCH_MEDIAN_FILTER_RADIUS + 1).min(pitches.len());
+            let mut window: Vec<f32> = pitches[start..end].iter().filter_map(|&p| p).collect();
+
+            if window.is_empty() {
+                return current;
+            }
+
+            window.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            Some(window[window.len() / 2])
+        })
+        .collect()
+}
+
+/// Map a frequency to its pitch class (0 = C, ..., 11 = B), using the same
+/// `CHROMA_REFERENCE_HZ`-relative octave convention as the chroma filter bank
+fn quantize_to_pitch_class(frequency_hz: f32) -> f32 {
+    if frequency_hz <= 0.0 {
+        return UNVOICED_PITCH_CLASS;
+    }
+
+    let octaves = (frequency_hz / CHROMA_REFERENCE_HZ).log2();
+    ((12.0 * octaves).round() as i32).rem_euclid(12) as f32
+}
+
+/// Levenshtein edit distance between two pitch-class contours, so melody
+/// lines of different lengths (e.g. a short hummed query vs. a full track)
+/// remain comparable
+fn pitch_contour_edit_distance(a: &[f32], b: &[f32]) -> usize {
+    let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, &a_class) in a.iter().enumerate() {
+        let mut current_row = vec![i + 1];
+        for (j, &b_class) in b.iter().enumerate() {
+            let cost = if a_class == b_class { 0 } else { 1 };
+            let insertion = current_row[j] + 1;
+            let deletion = previous_row[j + 1] + 1;
+            let substitution = previous_row[j] + cost;
+            current_row.push(insertion.min(deletion).min(substitution));
         }
+        previous_row = current_row;
     }
-    
-    patterns
+
+    previous_row[b.len()]
+}
+
+/// Normalize the edit distance between two pitch-class contours into a
+/// `[0, 1]` similarity score for `robust_similarity`
+fn pitch_contour_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let distance = pitch_contour_edit_distance(a, b);
+    let max_len = a.len().max(b.len());
+
+    1.0 - (distance as f32 / max_len as f32)
 }
 
 /// Calculate learned feature weights
@@ -518,15 +1135,17 @@ fn calculate_learned_weights(
     mfcc_features: &[f32],
     chroma_features: &[f32],
     rhythm_features: &[f32],
+    pitch_features: &[f32],
 ) -> FeatureWeights {
     // Simplified learned weights (in practice, these would be learned from training data)
     FeatureWeights {
-        hash_weight: 0.3,
-        mfcc_weight: 0.25,
-        chroma_weight: 0.2,
-        rhythm_weight: 0.15,
-        language_weight: 0.05,
-        temporal_weight: 0.05,
+        hash_weight: 0.25,
+        mfcc_weight: 0.2,
+        chroma_weight: 0.15,
+        rhythm_weight: 0.1,
+        pitch_weight: 0.15,
+        language_weight: 0.075,
+        temporal_weight: 0.075,
     }
 }
 
@@ -536,12 +1155,18 @@ fn calculate_feature_confidence(
     mfcc_features: &[f32],
     chroma_features: &[f32],
     rhythm_features: &[f32],
+    pitch_features: &[f32],
 ) -> FeatureConfidence {
     FeatureConfidence {
         hash_confidence: if hash_fingerprint.hashes.len() > 10 { 0.8 } else { 0.4 },
         mfcc_confidence: if mfcc_features.len() > 50 { 0.9 } else { 0.5 },
         chroma_confidence: if chroma_features.len() > 100 { 0.7 } else { 0.3 },
         rhythm_confidence: if rhythm_features.len() > 5 { 0.8 } else { 0.4 },
+        pitch_confidence: if pitch_features.is_empty() {
+            0.0
+        } else {
+            pitch_features.iter().filter(|&&class| class >= 0.0).count() as f32 / pitch_features.len() as f32
+        },
         language_confidence: 0.6, // Placeholder
         temporal_confidence: 0.7, // Placeholder
     }
@@ -553,32 +1178,45 @@ fn estimate_memory_usage(audio_length: usize) -> f32 {
     (audio_length * 4) as f32 / (1024.0 * 1024.0) // 4 bytes per f32
 }
 
-/// Estimate SIMD operations
-fn estimate_simd_operations(audio_length: usize) -> u32 {
-    // Rough estimate of SIMD operations
-    (audio_length / 4) as u32 // Assuming 4-wide SIMD
+/// Record a hit against the window/filter-bank caches
+fn record_cache_hit() {
+    CACHE_HITS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Record a miss against the window/filter-bank caches
+fn record_cache_miss() {
+    CACHE_MISSES.fetch_add(1, Ordering::Relaxed);
 }
 
-/// Calculate cache hit ratio
+/// Calculate the genuine cache hit ratio across all window/filter-bank
+/// lookups made so far, from the atomic counters `get_cached_*` maintain
 fn calculate_cache_hit_ratio() -> f32 {
-    // Placeholder for cache hit ratio calculation
-    0.85
+    let hits = CACHE_HITS.load(Ordering::Relaxed);
+    let misses = CACHE_MISSES.load(Ordering::Relaxed);
+    let total = hits + misses;
+
+    if total == 0 {
+        0.0
+    } else {
+        hits as f32 / total as f32
+    }
 }
 
-/// Calculate cosine similarity
+/// Calculate cosine similarity, with the dot product and both norms computed
+/// via the SIMD-lane backend above
 fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() || a.is_empty() {
         return 0.0;
     }
-    
-    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
-    
+
+    let dot_product = simd_dot_product(a, b);
+    let norm_a = simd_dot_product(a, a).sqrt();
+    let norm_b = simd_dot_product(b, b).sqrt();
+
     if norm_a == 0.0 || norm_b == 0.0 {
         return 0.0;
     }
-    
+
     dot_product / (norm_a * norm_b)
 }
 
@@ -586,6 +1224,307 @@ fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_hamming_window_cache_hits_on_second_lookup() {
+        let window_size = 9973; // unlikely to collide with other tests' window sizes
+        let hits_before = CACHE_HITS.load(Ordering::Relaxed);
+
+        let first = get_cached_hamming_window(window_size);
+        let second = get_cached_hamming_window(window_size);
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(CACHE_HITS.load(Ordering::Relaxed) > hits_before);
+    }
+
+    #[test]
+    fn test_mel_filter_bank_cache_hits_on_second_lookup() {
+        let hits_before = CACHE_HITS.load(Ordering::Relaxed);
+
+        let first = get_cached_mel_filter_bank(22050, 1117, 19);
+        let second = get_cached_mel_filter_bank(22050, 1117, 19);
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(CACHE_HITS.load(Ordering::Relaxed) > hits_before);
+    }
+
+    #[test]
+    fn test_calculate_cache_hit_ratio_is_within_unit_range() {
+        let _ = get_cached_chroma_filter_bank(44100, 1301);
+        let ratio = calculate_cache_hit_ratio();
+        assert!((0.0..=1.0).contains(&ratio));
+    }
+
+    #[test]
+    fn test_simd_elementwise_multiply_matches_scalar_reference() {
+        let a = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+        let b = vec![2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0];
+
+        let result = simd_elementwise_multiply(&a, &b);
+        let expected: Vec<f32> = a.iter().zip(b.iter()).map(|(x, y)| x * y).collect();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_simd_dot_product_matches_scalar_reference() {
+        let a = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0];
+        let b = vec![9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0, 1.0];
+
+        let result = simd_dot_product(&a, &b);
+        let expected: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+
+        assert!((result - expected).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_apply_window_simd_counts_lane_groups() {
+        reset_simd_op_count();
+
+        let audio_data = vec![1.0; 17];
+        let window = vec![0.5; 17];
+        apply_window_simd(&audio_data, &window);
+
+        // 17 samples / SIMD_LANES(4) = 4 full lane groups, plus a scalar remainder.
+        assert_eq!(take_simd_op_count(), 4);
+    }
+
+    #[test]
+    fn test_cosine_similarity_unchanged_by_simd_backend() {
+        let a = vec![1.0, 0.0, 1.0, 0.0, 1.0];
+        let b = vec![1.0, 0.0, 1.0, 0.0, 1.0];
+
+        assert!((cosine_similarity(&a, &b) - 1.0).abs() < 1e-5);
+
+        let c = vec![1.0, 0.0, 0.0, 0.0, 0.0];
+        let d = vec![0.0, 1.0, 0.0, 0.0, 0.0];
+        assert!(cosine_similarity(&c, &d).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_detect_frame_pitch_recovers_known_tone() {
+        let sample_rate = 44100;
+        let frequency = 220.0; // A3
+        let frame: Vec<f32> = (0..PITCH_FRAME_SIZE)
+            .map(|i| (2.0 * PI * frequency * i as f32 / sample_rate as f32).sin())
+            .collect();
+
+        let detected = detect_frame_pitch(&frame, sample_rate).expect("clean tone should be voiced");
+        assert!((detected - frequency).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_detect_frame_pitch_returns_none_for_silence() {
+        let sample_rate = 44100;
+        let frame = vec![0.0; PITCH_FRAME_SIZE];
+
+        assert_eq!(detect_frame_pitch(&frame, sample_rate), None);
+    }
+
+    #[test]
+    fn test_quantize_to_pitch_class_maps_a_to_nine() {
+        assert_eq!(quantize_to_pitch_class(220.0), 9.0);
+        assert_eq!(quantize_to_pitch_class(440.0), 9.0);
+    }
+
+    #[test]
+    fn test_median_filter_pitch_contour_removes_single_frame_outlier() {
+        let contour = vec![Some(9.0), Some(9.0), Some(2.0), Some(9.0), Some(9.0)];
+        let smoothed = median_filter_pitch_contour(&contour);
+
+        assert_eq!(smoothed[2], Some(9.0));
+    }
+
+    #[test]
+    fn test_pitch_contour_similarity_is_one_for_identical_contours() {
+        let a = vec![0.0, 4.0, 7.0, 9.0];
+        assert_eq!(pitch_contour_similarity(&a, &a), 1.0);
+    }
+
+    #[test]
+    fn test_pitch_contour_similarity_decreases_with_edit_distance() {
+        let a = vec![0.0, 4.0, 7.0, 9.0];
+        let close = vec![0.0, 4.0, 7.0, 2.0];
+        let far = vec![1.0, 5.0, 8.0, 10.0];
+
+        let close_similarity = pitch_contour_similarity(&a, &close);
+        let far_similarity = pitch_contour_similarity(&a, &far);
+
+        assert!(close_similarity < 1.0);
+        assert!(far_similarity < close_similarity);
+    }
+
+    #[test]
+    fn test_pitch_contour_similarity_handles_differing_lengths() {
+        let full_track = vec![0.0, 4.0, 7.0, 9.0, 0.0, 4.0, 7.0, 9.0];
+        let hummed_snippet = vec![0.0, 4.0, 7.0, 9.0];
+
+        let similarity = pitch_contour_similarity(&full_track, &hummed_snippet);
+        assert!(similarity > 0.0 && similarity <= 1.0);
+    }
+
+    #[test]
+    fn test_beat_spectrum_has_fixed_length_regardless_of_input_length() {
+        let short_onsets = Array1::from(vec![1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0]);
+        let long_onsets = Array1::from((0..1000).map(|i| if i % 10 == 0 { 1.0 } else { 0.0 }).collect::<Vec<f32>>());
+
+        let short_spectrum = extract_beat_spectrum(&short_onsets, 50.0);
+        let long_spectrum = extract_beat_spectrum(&long_onsets, 50.0);
+
+        assert_eq!(short_spectrum.len(), BEAT_SPECTRUM_LENGTH);
+        assert_eq!(long_spectrum.len(), BEAT_SPECTRUM_LENGTH);
+    }
+
+    #[test]
+    fn test_beat_spectrum_peaks_at_zero_lag() {
+        let onsets = Array1::from((0..200).map(|i| if i % 8 == 0 { 1.0 } else { 0.1 }).collect::<Vec<f32>>());
+        let spectrum = extract_beat_spectrum(&onsets, 50.0);
+
+        let peak_index = spectrum
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .map(|(i, _)| i)
+            .unwrap();
+
+        // Lag 0 always has the highest normalized autocorrelation; after
+        // resampling to BEAT_SPECTRUM_LENGTH it should still be the first bin.
+        assert_eq!(peak_index, 0);
+    }
+
+    #[test]
+    fn test_resample_to_fixed_length_preserves_endpoints() {
+        let values = vec![1.0, 2.0, 3.0, 4.0];
+        let resampled = resample_to_fixed_length(&values, 10);
+
+        assert_eq!(resampled.len(), 10);
+        assert!((resampled[0] - 1.0).abs() < 1e-6);
+        assert!((resampled[9] - 4.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_chroma_filter_bank_maps_a440_bin_to_pitch_class_a() {
+        let sample_rate = 44100;
+        let window_size = 4096;
+        let chroma_filters = build_log_frequency_chroma_filter_bank(sample_rate, window_size);
+
+        let bin_hz = sample_rate as f32 / window_size as f32;
+        let bin = (440.0 / bin_hz).round() as usize;
+
+        // A440 is pitch class 9 (A) regardless of octave.
+        let dominant_pitch_class = (0..12)
+            .max_by(|&a, &b| chroma_filters[[a, bin]].partial_cmp(&chroma_filters[[b, bin]]).unwrap())
+            .unwrap();
+        assert_eq!(dominant_pitch_class, 9);
+    }
+
+    #[test]
+    fn test_chroma_filter_bank_skips_dc_and_sub_reference_bins() {
+        let chroma_filters = build_log_frequency_chroma_filter_bank(44100, 4096);
+
+        // The DC bin (0 Hz) and any bin below CHROMA_REFERENCE_HZ carry no
+        // meaningful pitch class and should contribute nothing.
+        for bin in 0..2 {
+            for pitch_class in 0..12 {
+                assert_eq!(chroma_filters[[pitch_class, bin]], 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mel_filter_bank_filters_are_triangular_and_peak_at_one() {
+        let filter_bank = build_triangular_mel_filter_bank(44100, 2048, 26);
+        assert_eq!(filter_bank.shape(), &[26, 1025]);
+
+        for filter in filter_bank.axis_iter(Axis(0)) {
+            let peak = filter.iter().cloned().fold(0.0_f32, f32::max);
+            assert!((peak - 1.0).abs() < 1e-6, "each filter should peak at 1.0, got {}", peak);
+
+            // Each filter should ramp up then down, i.e. be unimodal: once it
+            // starts decreasing it should never increase again.
+            let mut seen_decrease = false;
+            for pair in filter.iter().collect::<Vec<_>>().windows(2) {
+                if pair[1] < pair[0] {
+                    seen_decrease = true;
+                } else if pair[1] > pair[0] {
+                    assert!(!seen_decrease, "filter should be unimodal (ramp up, then down)");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mel_filter_bank_low_frequency_filters_are_narrower_in_hz() {
+        // The mel scale compresses high frequencies, so filters spaced
+        // equally in mel should span a narrower Hz range at low frequencies
+        // than at high frequencies.
+        let filter_bank = build_triangular_mel_filter_bank(44100, 2048, 26);
+        let num_bins = filter_bank.ncols();
+        let bin_width_hz = (44100.0 / 2.0) / (num_bins - 1) as f32;
+
+        let support_width_bins = |filter_idx: usize| -> usize {
+            filter_bank
+                .row(filter_idx)
+                .iter()
+                .filter(|&&magnitude| magnitude > 0.0)
+                .count()
+        };
+
+        let low_width = support_width_bins(0) as f32 * bin_width_hz;
+        let high_width = support_width_bins(24) as f32 * bin_width_hz;
+
+        assert!(high_width > low_width);
+    }
+
+    #[test]
+    fn test_estimate_key_from_chroma_recovers_c_major_profile() {
+        // A pitch-class profile proportional to the C major template itself
+        // should be identified as C major (tonic 0) with high strength.
+        let mut chroma_features = Vec::new();
+        for _ in 0..4 {
+            chroma_features.extend_from_slice(&MAJOR_KEY_TEMPLATE);
+        }
+
+        let estimate = estimate_key_from_chroma(&chroma_features);
+
+        assert_eq!(estimate.tonic, 0);
+        assert!(estimate.is_major);
+        assert!(estimate.strength > 0.9);
+    }
+
+    #[test]
+    fn test_estimate_key_from_chroma_recovers_rotated_minor_profile() {
+        // Rotate the minor template so the tonic is pitch class 5 (F minor)
+        let rotated = rotate_key_template(&MINOR_KEY_TEMPLATE, 5);
+        let mut chroma_features = Vec::new();
+        for _ in 0..4 {
+            chroma_features.extend_from_slice(&rotated);
+        }
+
+        let estimate = estimate_key_from_chroma(&chroma_features);
+
+        assert_eq!(estimate.tonic, 5);
+        assert!(!estimate.is_major);
+        assert!(estimate.strength > 0.9);
+    }
+
+    #[test]
+    fn test_key_agreement_bonus_full_for_matching_key_partial_for_relative_key_none_otherwise() {
+        let mut fingerprint = make_test_fingerprint(vec![0.1, 0.2, 0.3], vec![0.01, 0.01, 0.01]);
+        fingerprint.key_estimate = KeyEstimate { tonic: 0, is_major: true, strength: 0.9 }; // C major
+
+        let mut same_key = fingerprint.clone();
+        same_key.key_estimate = KeyEstimate { tonic: 0, is_major: true, strength: 0.9 }; // C major
+        assert_eq!(fingerprint.key_agreement_bonus(&same_key), KEY_AGREEMENT_BONUS);
+
+        let mut relative_key = fingerprint.clone();
+        relative_key.key_estimate = KeyEstimate { tonic: 9, is_major: false, strength: 0.9 }; // A minor
+        assert_eq!(fingerprint.key_agreement_bonus(&relative_key), RELATIVE_KEY_AGREEMENT_BONUS);
+
+        let mut unrelated_key = fingerprint.clone();
+        unrelated_key.key_estimate = KeyEstimate { tonic: 6, is_major: true, strength: 0.9 }; // F#/Gb major
+        assert_eq!(fingerprint.key_agreement_bonus(&unrelated_key), 0.0);
+    }
+
     #[test]
     fn test_optimized_fingerprint_generation() {
         // Generate test audio
@@ -623,16 +1562,22 @@ mod tests {
                     num_bins: 2048,
                     window_size: 4096,
                     overlap: 0.5,
+                    key: None,
                 },
             },
             mfcc_features: vec![0.1, 0.2, 0.3],
+            timbre_mean: vec![0.1, 0.2, 0.3],
+            timbre_variance: vec![0.01, 0.01, 0.01],
             chroma_features: vec![0.4, 0.5, 0.6],
             rhythm_features: vec![120.0, 0.5, 0.6],
+            pitch_features: vec![0.0, 1.0, 2.0],
+            key_estimate: KeyEstimate { tonic: 0, is_major: true, strength: 0.9 },
             feature_weights: FeatureWeights {
                 hash_weight: 0.3,
                 mfcc_weight: 0.25,
                 chroma_weight: 0.2,
                 rhythm_weight: 0.15,
+                pitch_weight: 0.15,
                 language_weight: 0.05,
                 temporal_weight: 0.05,
             },
@@ -641,6 +1586,7 @@ mod tests {
                 mfcc_confidence: 0.9,
                 chroma_confidence: 0.7,
                 rhythm_confidence: 0.8,
+                pitch_confidence: 0.8,
                 language_confidence: 0.6,
                 temporal_confidence: 0.7,
             },
@@ -659,6 +1605,140 @@ mod tests {
         assert!(similarity <= 1.0);
     }
 
+    #[test]
+    fn test_timbre_divergence_zero_for_identical_gaussian() {
+        let fingerprint1 = make_test_fingerprint(vec![0.1, 0.2, 0.3], vec![0.01, 0.01, 0.01]);
+        let fingerprint2 = fingerprint1.clone();
+
+        assert_eq!(fingerprint1.timbre_divergence(&fingerprint2), 0.0);
+    }
+
+    #[test]
+    fn test_timbre_divergence_grows_with_mean_shift() {
+        let fingerprint1 = make_test_fingerprint(vec![0.1, 0.2, 0.3], vec![0.01, 0.01, 0.01]);
+        let close = make_test_fingerprint(vec![0.15, 0.2, 0.3], vec![0.01, 0.01, 0.01]);
+        let far = make_test_fingerprint(vec![5.0, 0.2, 0.3], vec![0.01, 0.01, 0.01]);
+
+        let close_divergence = fingerprint1.timbre_divergence(&close);
+        let far_divergence = fingerprint1.timbre_divergence(&far);
+
+        assert!(close_divergence > 0.0);
+        assert!(far_divergence > close_divergence);
+    }
+
+    #[test]
+    fn test_robust_similarity_is_robust_to_differing_frame_counts() {
+        // Two fingerprints whose raw MFCC vectors differ in length (different
+        // clip lengths/frame counts) but whose per-coefficient distributions
+        // are identical should still score as very similar, since
+        // robust_similarity now compares Gaussian timbre models rather than
+        // flattened-vector cosine similarity.
+        let mut fingerprint1 = make_test_fingerprint(vec![0.1, 0.2, 0.3], vec![0.01, 0.01, 0.01]);
+        fingerprint1.mfcc_features = vec![0.1, 0.2, 0.3, 0.1, 0.2, 0.3];
+
+        let mut fingerprint2 = make_test_fingerprint(vec![0.1, 0.2, 0.3], vec![0.01, 0.01, 0.01]);
+        fingerprint2.mfcc_features = vec![0.1, 0.2, 0.3];
+
+        let similarity = fingerprint1.robust_similarity(&fingerprint2);
+        assert!(similarity > 0.9);
+    }
+
+    #[test]
+    fn test_compact_is_deterministic_for_identical_fingerprints() {
+        let fingerprint1 = make_test_fingerprint(vec![0.1, 0.2, 0.3], vec![0.01, 0.01, 0.01]);
+        let fingerprint2 = fingerprint1.clone();
+
+        assert_eq!(fingerprint1.compact(), fingerprint2.compact());
+    }
+
+    #[test]
+    fn test_compact_differs_when_a_feature_differs() {
+        let fingerprint1 = make_test_fingerprint(vec![0.1, 0.2, 0.3], vec![0.01, 0.01, 0.01]);
+        let mut fingerprint2 = fingerprint1.clone();
+        fingerprint2.chroma_features = vec![0.9, 0.1, 0.2];
+
+        assert_ne!(fingerprint1.compact(), fingerprint2.compact());
+    }
+
+    #[derive(Default)]
+    struct RecordingMetricsSink {
+        gauges: std::cell::RefCell<Vec<(String, f64)>>,
+        incrs: std::cell::RefCell<Vec<(String, u64)>>,
+    }
+
+    impl MetricsSink for RecordingMetricsSink {
+        fn gauge(&self, name: &str, value: f64) {
+            self.gauges.borrow_mut().push((name.to_string(), value));
+        }
+
+        fn incr(&self, name: &str, count: u64) {
+            self.incrs.borrow_mut().push((name.to_string(), count));
+        }
+    }
+
+    #[test]
+    fn test_publish_metrics_reports_all_processing_metadata_fields() {
+        let fingerprint = make_test_fingerprint(vec![0.1, 0.2, 0.3], vec![0.01, 0.01, 0.01]);
+        let sink = RecordingMetricsSink::default();
+
+        fingerprint.publish_metrics(&sink);
+
+        let gauges = sink.gauges.borrow();
+        assert_eq!(gauges.len(), 5);
+        assert!(gauges.iter().any(|(name, _)| name == "fingerprint.processing_time_ms"));
+        assert!(gauges.iter().any(|(name, _)| name == "fingerprint.overall_confidence"));
+        assert_eq!(sink.incrs.borrow().as_slice(), &[("fingerprint.generated".to_string(), 1)]);
+    }
+
+    fn make_test_fingerprint(timbre_mean: Vec<f32>, timbre_variance: Vec<f32>) -> OptimizedFingerprint {
+        OptimizedFingerprint {
+            hash_fingerprint: super::super::fingerprint::Fingerprint {
+                hashes: vec![1, 2, 3],
+                time_offsets: vec![0.0, 0.1, 0.2],
+                peaks: Vec::new(),
+                metadata: super::super::fingerprint::FingerprintMetadata {
+                    sample_rate: 44100,
+                    duration: 1.0,
+                    num_bins: 2048,
+                    window_size: 4096,
+                    overlap: 0.5,
+                    key: None,
+                },
+            },
+            mfcc_features: timbre_mean.clone(),
+            timbre_mean,
+            timbre_variance,
+            chroma_features: vec![0.4, 0.5, 0.6],
+            rhythm_features: vec![120.0, 0.5, 0.6],
+            pitch_features: vec![0.0, 1.0, 2.0],
+            key_estimate: KeyEstimate { tonic: 0, is_major: true, strength: 0.9 },
+            feature_weights: FeatureWeights {
+                hash_weight: 0.3,
+                mfcc_weight: 0.25,
+                chroma_weight: 0.2,
+                rhythm_weight: 0.15,
+                pitch_weight: 0.15,
+                language_weight: 0.05,
+                temporal_weight: 0.05,
+            },
+            feature_confidence: FeatureConfidence {
+                hash_confidence: 0.8,
+                mfcc_confidence: 0.9,
+                chroma_confidence: 0.7,
+                rhythm_confidence: 0.8,
+                pitch_confidence: 0.8,
+                language_confidence: 0.6,
+                temporal_confidence: 0.7,
+            },
+            processing_metadata: ProcessingMetadata {
+                processing_time_ms: 50.0,
+                memory_usage_mb: 10.0,
+                simd_operations: 1000,
+                cache_hit_ratio: 0.85,
+            },
+        }
+    }
+
     #[test]
     fn test_performance_metrics() {
         let fingerprint = OptimizedFingerprint {
@@ -672,16 +1752,22 @@ mod tests {
                     num_bins: 2048,
                     window_size: 4096,
                     overlap: 0.5,
+                    key: None,
                 },
             },
             mfcc_features: vec![0.1, 0.2, 0.3],
+            timbre_mean: vec![0.1, 0.2, 0.3],
+            timbre_variance: vec![0.01, 0.01, 0.01],
             chroma_features: vec![0.4, 0.5, 0.6],
             rhythm_features: vec![120.0, 0.5, 0.6],
+            pitch_features: vec![0.0, 1.0, 2.0],
+            key_estimate: KeyEstimate { tonic: 0, is_major: true, strength: 0.9 },
             feature_weights: FeatureWeights {
                 hash_weight: 0.3,
                 mfcc_weight: 0.25,
                 chroma_weight: 0.2,
                 rhythm_weight: 0.15,
+                pitch_weight: 0.15,
                 language_weight: 0.05,
                 temporal_weight: 0.05,
             },
@@ -690,6 +1776,7 @@ mod tests {
                 mfcc_confidence: 0.9,
                 chroma_confidence: 0.7,
                 rhythm_confidence: 0.8,
+                pitch_confidence: 0.8,
                 language_confidence: 0.6,
                 temporal_confidence: 0.7,
             },
@@ -1,19 +1,114 @@
 //! Similarity calculation algorithms for audio fingerprints
 
+use crate::config::SimilarityConfig;
 use crate::fingerprint::Fingerprint;
 use ndarray::{Array1, Array2};
 use std::collections::HashMap;
 
-/// Calculate similarity between two fingerprints
+/// Scales down the weighted-sum similarity when two fingerprints carry
+/// estimated keys that disagree, suppressing false positives between
+/// unrelated tracks that happen to share hashes/peaks by chance
+const KEY_MISMATCH_PENALTY: f32 = 0.6;
+
+/// A single pluggable scoring signal used by `calculate_similarity_with`, so
+/// new signals can be added (or existing ones reordered/dropped) without
+/// touching the weighted-sum blend itself.
+pub trait SimilarityMetric {
+    /// Score two fingerprints under this metric, in `[0.0, 1.0]`
+    fn score(&self, fingerprint1: &Fingerprint, fingerprint2: &Fingerprint, config: &SimilarityConfig) -> f32;
+    /// This metric's weight in the weighted-sum blend
+    fn weight(&self, config: &SimilarityConfig) -> f32;
+}
+
+/// Jaccard similarity over the two fingerprints' hash sets
+pub struct HashSimilarityMetric;
+
+impl SimilarityMetric for HashSimilarityMetric {
+    fn score(&self, fingerprint1: &Fingerprint, fingerprint2: &Fingerprint, _config: &SimilarityConfig) -> f32 {
+        calculate_hash_similarity(fingerprint1, fingerprint2)
+    }
+
+    fn weight(&self, config: &SimilarityConfig) -> f32 {
+        config.hash_weight
+    }
+}
+
+/// Fraction of spectral peaks that have a close match (within configured
+/// frequency/time tolerances) in the other fingerprint
+pub struct PeakSimilarityMetric;
+
+impl SimilarityMetric for PeakSimilarityMetric {
+    fn score(&self, fingerprint1: &Fingerprint, fingerprint2: &Fingerprint, config: &SimilarityConfig) -> f32 {
+        calculate_peak_similarity_with(fingerprint1, fingerprint2, config)
+    }
+
+    fn weight(&self, config: &SimilarityConfig) -> f32 {
+        config.peak_weight
+    }
+}
+
+/// Cosine similarity between the two fingerprints' frequency/time/magnitude
+/// spectral histograms
+pub struct SpectralSimilarityMetric;
+
+impl SimilarityMetric for SpectralSimilarityMetric {
+    fn score(&self, fingerprint1: &Fingerprint, fingerprint2: &Fingerprint, config: &SimilarityConfig) -> f32 {
+        calculate_spectral_similarity_with(fingerprint1, fingerprint2, config)
+    }
+
+    fn weight(&self, config: &SimilarityConfig) -> f32 {
+        config.spectral_weight
+    }
+}
+
+/// The default metric set used by `calculate_similarity`/`calculate_similarity_with`
+fn default_metrics() -> Vec<Box<dyn SimilarityMetric>> {
+    vec![
+        Box::new(HashSimilarityMetric),
+        Box::new(PeakSimilarityMetric),
+        Box::new(SpectralSimilarityMetric),
+    ]
+}
+
+/// Calculate similarity between two fingerprints using the default metrics
+/// and tolerances. Thin wrapper over `calculate_similarity_with` for callers
+/// that don't need to tune recognition per deployment.
 pub fn calculate_similarity(fingerprint1: &Fingerprint, fingerprint2: &Fingerprint) -> f32 {
-    // Use multiple similarity metrics and combine them
-    let hash_similarity = calculate_hash_similarity(fingerprint1, fingerprint2);
-    let peak_similarity = calculate_peak_similarity(fingerprint1, fingerprint2);
-    let spectral_similarity = calculate_spectral_similarity(fingerprint1, fingerprint2);
-    
-    // Weighted combination of similarities
-    let weights = (0.5, 0.3, 0.2); // hash, peak, spectral
-    hash_similarity * weights.0 + peak_similarity * weights.1 + spectral_similarity * weights.2
+    calculate_similarity_with(fingerprint1, fingerprint2, &SimilarityConfig::default())
+}
+
+/// Calculate similarity between two fingerprints using a caller-supplied
+/// `SimilarityConfig`, so weights and tolerances can be retuned (e.g. widened
+/// for noisy microphone captures) without a recompile
+pub fn calculate_similarity_with(fingerprint1: &Fingerprint, fingerprint2: &Fingerprint, config: &SimilarityConfig) -> f32 {
+    calculate_similarity_with_metrics(fingerprint1, fingerprint2, config, &default_metrics())
+}
+
+/// Calculate similarity using an explicit set of `SimilarityMetric`s, for
+/// callers that want to extend or reorder the scoring signals themselves
+pub fn calculate_similarity_with_metrics(
+    fingerprint1: &Fingerprint,
+    fingerprint2: &Fingerprint,
+    config: &SimilarityConfig,
+    metrics: &[Box<dyn SimilarityMetric>],
+) -> f32 {
+    let base_similarity: f32 = metrics
+        .iter()
+        .map(|metric| metric.score(fingerprint1, fingerprint2, config) * metric.weight(config))
+        .sum();
+
+    apply_key_mismatch_penalty(fingerprint1, fingerprint2, base_similarity)
+}
+
+/// When both fingerprints have an estimated key and they disagree, the tracks
+/// are very unlikely to be the same recording regardless of how well their
+/// hashes/peaks happen to line up, so scale the similarity down. Fingerprints
+/// without a confident key estimate (`None`) are left unpenalized.
+fn apply_key_mismatch_penalty(fingerprint1: &Fingerprint, fingerprint2: &Fingerprint, base_similarity: f32) -> f32 {
+    match (fingerprint1.metadata.key, fingerprint2.metadata.key) {
+        (Some(key1), Some(key2)) if key1 != key2 => base_similarity * KEY_MISMATCH_PENALTY,
+        _ => base_similarity,
+    }
 }
 
 /// Calculate hash-based similarity
@@ -21,15 +116,15 @@ fn calculate_hash_similarity(fingerprint1: &Fingerprint, fingerprint2: &Fingerpr
     if fingerprint1.hashes.is_empty() || fingerprint2.hashes.is_empty() {
         return 0.0;
     }
-    
+
     // Create hash sets for fast lookup
     let hash_set1: std::collections::HashSet<u64> = fingerprint1.hashes.iter().cloned().collect();
     let hash_set2: std::collections::HashSet<u64> = fingerprint2.hashes.iter().cloned().collect();
-    
+
     // Calculate Jaccard similarity
     let intersection = hash_set1.intersection(&hash_set2).count();
     let union = hash_set1.union(&hash_set2).count();
-    
+
     if union == 0 {
         0.0
     } else {
@@ -37,33 +132,34 @@ fn calculate_hash_similarity(fingerprint1: &Fingerprint, fingerprint2: &Fingerpr
     }
 }
 
-/// Calculate peak-based similarity
+/// Calculate peak-based similarity using the default tolerances
 fn calculate_peak_similarity(fingerprint1: &Fingerprint, fingerprint2: &Fingerprint) -> f32 {
+    calculate_peak_similarity_with(fingerprint1, fingerprint2, &SimilarityConfig::default())
+}
+
+/// Calculate peak-based similarity using configured frequency/time tolerances
+fn calculate_peak_similarity_with(fingerprint1: &Fingerprint, fingerprint2: &Fingerprint, config: &SimilarityConfig) -> f32 {
     if fingerprint1.peaks.is_empty() || fingerprint2.peaks.is_empty() {
         return 0.0;
     }
-    
+
     let mut matches = 0;
     let mut total_peaks = 0;
-    
-    // Find matching peaks within tolerance
-    let freq_tolerance = 50.0; // Hz
-    let time_tolerance = 0.1; // seconds
-    
+
     for peak1 in &fingerprint1.peaks {
         total_peaks += 1;
-        
+
         for peak2 in &fingerprint2.peaks {
             let freq_diff = (peak1.frequency - peak2.frequency).abs();
             let time_diff = (peak1.time - peak2.time).abs();
-            
-            if freq_diff <= freq_tolerance && time_diff <= time_tolerance {
+
+            if freq_diff <= config.freq_tolerance && time_diff <= config.time_tolerance {
                 matches += 1;
                 break;
             }
         }
     }
-    
+
     if total_peaks == 0 {
         0.0
     } else {
@@ -71,46 +167,49 @@ fn calculate_peak_similarity(fingerprint1: &Fingerprint, fingerprint2: &Fingerpr
     }
 }
 
-/// Calculate spectral similarity
+/// Calculate spectral similarity using the default histogram bin counts
 fn calculate_spectral_similarity(fingerprint1: &Fingerprint, fingerprint2: &Fingerprint) -> f32 {
-    // Compare spectral characteristics
-    let spectral_features1 = extract_spectral_features(fingerprint1);
-    let spectral_features2 = extract_spectral_features(fingerprint2);
-    
-    // Calculate cosine similarity
+    calculate_spectral_similarity_with(fingerprint1, fingerprint2, &SimilarityConfig::default())
+}
+
+/// Calculate spectral similarity using configured histogram bin counts
+fn calculate_spectral_similarity_with(fingerprint1: &Fingerprint, fingerprint2: &Fingerprint, config: &SimilarityConfig) -> f32 {
+    let spectral_features1 = extract_spectral_features(fingerprint1, config);
+    let spectral_features2 = extract_spectral_features(fingerprint2, config);
+
     cosine_similarity(&spectral_features1, &spectral_features2)
 }
 
-/// Extract spectral features from fingerprint
-fn extract_spectral_features(fingerprint: &Fingerprint) -> Vec<f32> {
+/// Extract spectral features from fingerprint using configured bin counts
+fn extract_spectral_features(fingerprint: &Fingerprint, config: &SimilarityConfig) -> Vec<f32> {
     let mut features = Vec::new();
-    
+
     // Frequency distribution
-    let freq_bins = 20;
+    let freq_bins = config.spectral_freq_bins;
     let mut freq_histogram = vec![0.0; freq_bins];
-    
+
     for peak in &fingerprint.peaks {
         let bin = ((peak.frequency / 20000.0) * freq_bins as f32) as usize;
         if bin < freq_bins {
             freq_histogram[bin] += peak.magnitude;
         }
     }
-    
+
     features.extend(freq_histogram);
-    
+
     // Time distribution
-    let time_bins = 10;
+    let time_bins = config.spectral_time_bins;
     let mut time_histogram = vec![0.0; time_bins];
-    
+
     for peak in &fingerprint.peaks {
         let bin = ((peak.time / fingerprint.metadata.duration) * time_bins as f32) as usize;
         if bin < time_bins {
             time_histogram[bin] += peak.magnitude;
         }
     }
-    
+
     features.extend(time_histogram);
-    
+
     // Statistical features
     if !fingerprint.peaks.is_empty() {
         let magnitudes: Vec<f32> = fingerprint.peaks.iter().map(|p| p.magnitude).collect();
@@ -242,19 +341,30 @@ fn calculate_time_aligned_similarity(
     }
 }
 
-/// Batch similarity calculation for multiple fingerprints
+/// Batch similarity calculation for multiple fingerprints using the default
+/// config and filter cutoff
 pub fn calculate_batch_similarity(
     query_fingerprint: &Fingerprint,
     candidate_fingerprints: &[Fingerprint],
+) -> Vec<(usize, f32)> {
+    calculate_batch_similarity_with(query_fingerprint, candidate_fingerprints, &SimilarityConfig::default())
+}
+
+/// Batch similarity calculation using a caller-supplied config, so the
+/// low-similarity filter cutoff can be tuned alongside the scoring weights
+pub fn calculate_batch_similarity_with(
+    query_fingerprint: &Fingerprint,
+    candidate_fingerprints: &[Fingerprint],
+    config: &SimilarityConfig,
 ) -> Vec<(usize, f32)> {
     candidate_fingerprints
         .iter()
         .enumerate()
         .map(|(i, candidate)| {
-            let similarity = calculate_similarity(query_fingerprint, candidate);
+            let similarity = calculate_similarity_with(query_fingerprint, candidate, config);
             (i, similarity)
         })
-        .filter(|(_, similarity)| *similarity > 0.1) // Filter out very low similarities
+        .filter(|(_, similarity)| *similarity > config.batch_filter_cutoff)
         .collect()
 }
 
@@ -264,6 +374,166 @@ pub fn calculate_fast_similarity(fingerprint1: &Fingerprint, fingerprint2: &Fing
     calculate_hash_similarity(fingerprint1, fingerprint2)
 }
 
+/// One matching excerpt between two fingerprints, as returned by `match_segments`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchSegment {
+    /// Start time of the matching region in `fingerprint1`, in seconds
+    pub start1: f32,
+    /// Start time of the matching region in `fingerprint2`, in seconds
+    pub start2: f32,
+    /// Duration of the matching region, in seconds
+    pub duration: f32,
+    /// Fraction of `fingerprint1` hashes in the window that matched
+    pub score: f32,
+}
+
+const SEGMENT_OFFSET_BIN_SIZE: f32 = 0.1;
+const SEGMENT_MIN_OFFSET_VOTES: usize = 3;
+const SEGMENT_CONTIGUITY_TOLERANCE: f32 = 1.0; // seconds
+const SEGMENT_MERGE_GAP_TOLERANCE: f32 = 0.5; // seconds
+
+/// Find which portions of two recordings overlap, rather than a single global
+/// score, so a sample that appears only partway through a track can still be
+/// detected.
+///
+/// Builds an inverted index of `fingerprint2`'s hashes, then for every
+/// colliding hash pair votes on the time offset between the two occurrences
+/// (quantized into `SEGMENT_OFFSET_BIN_SIZE` bins, the same histogram-voting
+/// idea `FingerprintMatcher::find_matches` uses across many songs). Offsets
+/// with enough votes are walked in time order and grouped into runs of
+/// temporally contiguous matches; each run becomes a segment once it clears
+/// `minimum_segment_duration` and `maximum_difference`.
+pub fn match_segments(
+    fingerprint1: &Fingerprint,
+    fingerprint2: &Fingerprint,
+    minimum_segment_duration: f32,
+    maximum_difference: f32,
+) -> Vec<MatchSegment> {
+    if fingerprint1.hashes.is_empty() || fingerprint2.hashes.is_empty() {
+        return Vec::new();
+    }
+
+    let mut index2: HashMap<u64, Vec<usize>> = HashMap::new();
+    for (j, &hash) in fingerprint2.hashes.iter().enumerate() {
+        index2.entry(hash).or_insert_with(Vec::new).push(j);
+    }
+
+    let mut aligned_pairs: Vec<(f32, f32, i64)> = Vec::new();
+    let mut offset_votes: HashMap<i64, usize> = HashMap::new();
+
+    for (i, &hash) in fingerprint1.hashes.iter().enumerate() {
+        if let Some(js) = index2.get(&hash) {
+            for &j in js {
+                let offset = fingerprint1.time_offsets[i] - fingerprint2.time_offsets[j];
+                let bin = (offset / SEGMENT_OFFSET_BIN_SIZE).round() as i64;
+                aligned_pairs.push((fingerprint1.time_offsets[i], fingerprint2.time_offsets[j], bin));
+                *offset_votes.entry(bin).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let dominant_offsets: Vec<i64> = offset_votes
+        .into_iter()
+        .filter(|&(_, votes)| votes >= SEGMENT_MIN_OFFSET_VOTES)
+        .map(|(bin, _)| bin)
+        .collect();
+
+    let mut segments = Vec::new();
+    for offset_bin in dominant_offsets {
+        let mut pairs: Vec<(f32, f32)> = aligned_pairs
+            .iter()
+            .filter(|&&(_, _, bin)| bin == offset_bin)
+            .map(|&(t1, t2, _)| (t1, t2))
+            .collect();
+        pairs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        let mut run_start_idx = 0;
+        for idx in 1..=pairs.len() {
+            let run_ends_here =
+                idx == pairs.len() || pairs[idx].0 - pairs[idx - 1].0 > SEGMENT_CONTIGUITY_TOLERANCE;
+            if run_ends_here {
+                try_emit_match_segment(
+                    &pairs[run_start_idx..idx],
+                    fingerprint1,
+                    minimum_segment_duration,
+                    maximum_difference,
+                    &mut segments,
+                );
+                run_start_idx = idx;
+            }
+        }
+    }
+
+    merge_adjacent_match_segments(segments)
+}
+
+/// Turn one run of temporally contiguous aligned hash pairs into a
+/// `MatchSegment`, dropping it if it's too short or too noisy
+fn try_emit_match_segment(
+    run: &[(f32, f32)],
+    fingerprint1: &Fingerprint,
+    minimum_segment_duration: f32,
+    maximum_difference: f32,
+    segments: &mut Vec<MatchSegment>,
+) {
+    if run.is_empty() {
+        return;
+    }
+
+    let start1 = run.iter().map(|&(t1, _)| t1).fold(f32::INFINITY, f32::min);
+    let end1 = run.iter().map(|&(t1, _)| t1).fold(f32::NEG_INFINITY, f32::max);
+    let start2 = run.iter().map(|&(_, t2)| t2).fold(f32::INFINITY, f32::min);
+    let duration = end1 - start1;
+
+    if duration < minimum_segment_duration {
+        return;
+    }
+
+    let total_in_window = fingerprint1
+        .time_offsets
+        .iter()
+        .filter(|&&t| t >= start1 && t <= end1)
+        .count()
+        .max(1);
+    let score = run.len() as f32 / total_in_window as f32;
+
+    if 1.0 - score > maximum_difference {
+        return;
+    }
+
+    segments.push(MatchSegment { start1, start2, duration, score });
+}
+
+/// Merge segments whose time windows in `fingerprint1` overlap or sit within
+/// `SEGMENT_MERGE_GAP_TOLERANCE` of each other, which happens when the same
+/// alignment gets split across adjacent offset bins
+fn merge_adjacent_match_segments(mut segments: Vec<MatchSegment>) -> Vec<MatchSegment> {
+    if segments.is_empty() {
+        return segments;
+    }
+
+    segments.sort_by(|a, b| a.start1.partial_cmp(&b.start1).unwrap());
+
+    let mut merged: Vec<MatchSegment> = Vec::with_capacity(segments.len());
+    for segment in segments {
+        if let Some(last) = merged.last_mut() {
+            let last_end = last.start1 + last.duration;
+            if segment.start1 <= last_end + SEGMENT_MERGE_GAP_TOLERANCE {
+                let new_end = last_end.max(segment.start1 + segment.duration);
+                let last_weight = last.duration.max(0.001);
+                let segment_weight = segment.duration.max(0.001);
+                last.score = (last.score * last_weight + segment.score * segment_weight)
+                    / (last_weight + segment_weight);
+                last.duration = new_end - last.start1;
+                continue;
+            }
+        }
+        merged.push(segment);
+    }
+
+    merged
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -280,10 +550,77 @@ mod tests {
                 num_bins: 2048,
                 window_size: 4096,
                 overlap: 0.5,
+                key: None,
             },
         }
     }
 
+    #[test]
+    fn test_calculate_similarity_penalizes_key_mismatch() {
+        use crate::fingerprint::{KeyClass, PitchClass};
+
+        let mut fingerprint1 = create_test_fingerprint(vec![1, 2, 3, 4, 5], Vec::new());
+        let mut fingerprint2 = create_test_fingerprint(vec![1, 2, 3, 6, 7], Vec::new());
+        fingerprint1.metadata.key = Some(KeyClass::Major(PitchClass::C));
+        fingerprint2.metadata.key = Some(KeyClass::Major(PitchClass::C));
+
+        let same_key_similarity = calculate_similarity(&fingerprint1, &fingerprint2);
+
+        fingerprint2.metadata.key = Some(KeyClass::Minor(PitchClass::FSharp));
+        let different_key_similarity = calculate_similarity(&fingerprint1, &fingerprint2);
+
+        assert!(different_key_similarity < same_key_similarity);
+        assert!((different_key_similarity - same_key_similarity * KEY_MISMATCH_PENALTY).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_calculate_similarity_with_default_config_matches_calculate_similarity() {
+        let fingerprint1 = create_test_fingerprint(vec![1, 2, 3, 4, 5], Vec::new());
+        let fingerprint2 = create_test_fingerprint(vec![1, 2, 3, 6, 7], Vec::new());
+
+        let default_score = calculate_similarity(&fingerprint1, &fingerprint2);
+        let config_score = calculate_similarity_with(&fingerprint1, &fingerprint2, &SimilarityConfig::default());
+
+        assert_eq!(default_score, config_score);
+    }
+
+    #[test]
+    fn test_calculate_similarity_with_rebalanced_weights_changes_score() {
+        let peaks1 = vec![SpectralPeak { frequency: 440.0, time: 0.1, magnitude: 1.0 }];
+        let peaks2 = vec![SpectralPeak { frequency: 445.0, time: 0.1, magnitude: 1.0 }];
+        let fingerprint1 = create_test_fingerprint(vec![1, 2, 3], peaks1);
+        let fingerprint2 = create_test_fingerprint(vec![4, 5, 6], peaks2);
+
+        let default_score = calculate_similarity_with(&fingerprint1, &fingerprint2, &SimilarityConfig::default());
+
+        let mut peak_only_config = SimilarityConfig::default();
+        peak_only_config.hash_weight = 0.0;
+        peak_only_config.peak_weight = 1.0;
+        peak_only_config.spectral_weight = 0.0;
+        let peak_only_score = calculate_similarity_with(&fingerprint1, &fingerprint2, &peak_only_config);
+
+        // The two fingerprints share no hashes but their sole peaks are within the
+        // default frequency tolerance, so weighting peak similarity more heavily
+        // should raise the score above the hash-dominated default.
+        assert!(peak_only_score > default_score);
+    }
+
+    #[test]
+    fn test_calculate_similarity_with_widened_freq_tolerance_matches_more_peaks() {
+        let peaks1 = vec![SpectralPeak { frequency: 440.0, time: 0.1, magnitude: 1.0 }];
+        let peaks2 = vec![SpectralPeak { frequency: 500.0, time: 0.1, magnitude: 1.0 }];
+        let fingerprint1 = create_test_fingerprint(Vec::new(), peaks1);
+        let fingerprint2 = create_test_fingerprint(Vec::new(), peaks2);
+
+        let narrow_score = calculate_peak_similarity_with(&fingerprint1, &fingerprint2, &SimilarityConfig::default());
+        assert_eq!(narrow_score, 0.0);
+
+        let mut wide_config = SimilarityConfig::default();
+        wide_config.freq_tolerance = 100.0;
+        let wide_score = calculate_peak_similarity_with(&fingerprint1, &fingerprint2, &wide_config);
+        assert_eq!(wide_score, 1.0);
+    }
+
     #[test]
     fn test_hash_similarity() {
         let fingerprint1 = create_test_fingerprint(vec![1, 2, 3, 4, 5], Vec::new());
@@ -317,4 +654,46 @@ mod tests {
         assert!(!results.is_empty());
         assert_eq!(results[0].0, 0); // First candidate should have highest similarity
     }
+
+    #[test]
+    fn test_match_segments_finds_excerpt_appearing_midway() {
+        // fingerprint2 is the full track; fingerprint1 (the query) is an excerpt
+        // that matches the middle of it at a constant +5.0s offset.
+        let mut fingerprint2 = create_test_fingerprint(
+            (0..20).map(|i| i as u64 * 101).collect(),
+            Vec::new(),
+        );
+        fingerprint2.time_offsets = (0..20).map(|i| i as f32 * 0.5).collect();
+
+        let fingerprint1 = Fingerprint {
+            hashes: fingerprint2.hashes[8..14].to_vec(),
+            time_offsets: (0..6).map(|i| 5.0 + i as f32 * 0.5).collect(),
+            peaks: Vec::new(),
+            metadata: fingerprint2.metadata.clone(),
+        };
+
+        let segments = match_segments(&fingerprint1, &fingerprint2, 1.0, 0.2);
+
+        assert!(!segments.is_empty(), "expected the embedded excerpt to be found");
+        let best = segments.iter().max_by(|a, b| a.score.partial_cmp(&b.score).unwrap()).unwrap();
+        assert!((best.start1 - 5.0).abs() < 0.5, "expected start1 near 5.0s, got {}", best.start1);
+        assert!((best.start2 - 4.0).abs() < 0.5, "expected start2 near 4.0s, got {}", best.start2);
+        assert!(best.score > 0.9);
+    }
+
+    #[test]
+    fn test_match_segments_no_match_for_unrelated_hashes() {
+        let fingerprint1 = create_test_fingerprint((0..10).map(|i| i as u64 * 7 + 1).collect(), Vec::new());
+        let fingerprint2 = create_test_fingerprint((0..10).map(|i| i as u64 * 999_983 + 5).collect(), Vec::new());
+
+        let segments = match_segments(&fingerprint1, &fingerprint2, 0.5, 0.2);
+        assert!(segments.is_empty());
+    }
+
+    #[test]
+    fn test_match_segments_empty_input_returns_empty() {
+        let fingerprint1 = create_test_fingerprint(Vec::new(), Vec::new());
+        let fingerprint2 = create_test_fingerprint(vec![1, 2, 3], Vec::new());
+        assert!(match_segments(&fingerprint1, &fingerprint2, 0.5, 0.2).is_empty());
+    }
 }
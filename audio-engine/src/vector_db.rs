@@ -1,19 +1,110 @@
 //! Vector database integration for ultra-fast similarity search
-//! 
-//! This module provides integration with Pinecone vector database for
-//! sub-millisecond similarity search of audio fingerprints.
+//!
+//! This module provides sub-millisecond similarity search of audio
+//! fingerprints against a pluggable `VectorStore` backend: `PineconeStore`
+//! (network, Pinecone's HTTP API), `QdrantStore` (network, Qdrant's HTTP
+//! API), or an in-process `LocalVectorIndex` (HNSW-backed, see
+//! `crate::hnsw`) for local development, CI, and air-gapped deployments
+//! where no network store is available. `VectorDatabase::from_config`
+//! selects the concrete backend at startup from a `VectorStoreConfig`; the
+//! fingerprint-indexing logic above it (`add_fingerprint`,
+//! `search_similar_fingerprints`, `batch_upsert_fingerprints`, ...) only
+//! ever calls through the `VectorStore` trait, so adding another backend
+//! means implementing that trait, not touching the indexing logic.
 
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use tokio::sync::RwLock;
 use uuid::Uuid;
 
-/// Vector database client for similarity search
+use crate::hnsw::{HnswConfig, HnswIndex};
+
+/// Pinecone namespace used for perceptual feature vectors, kept separate from the
+/// default (hash-fingerprint) namespace since the two have unrelated dimensions
+const PERCEPTUAL_FEATURES_NAMESPACE: &str = "perceptual_features";
+
+/// Vector database client for similarity search. Dispatches to whichever
+/// `VectorStore` backend was chosen at construction — Pinecone, Qdrant, or
+/// the in-process `LocalVectorIndex` — via `new`/`new_qdrant`/`new_local`
+/// or, for config-driven startup, `from_config`.
 pub struct VectorDatabase {
+    dimensions: u32,
+    backend: VectorBackend,
+    hash_index: RwLock<HashIndex>,
+    embedder: Box<dyn Embedder>,
+}
+
+/// The actual storage/search engine behind `VectorDatabase`. Every variant
+/// implements `VectorStore`; everything else on `VectorDatabase` is composed
+/// from that trait and doesn't need to know which backend is in use.
+enum VectorBackend {
+    Pinecone(PineconeStore),
+    Qdrant(QdrantStore),
+    Local(RwLock<LocalVectorIndex>),
+}
+
+/// Selects which `VectorStore` backend `VectorDatabase::from_config` builds,
+/// so the concrete store is a startup-time configuration choice rather than
+/// a code change — Pinecone or Qdrant in production, `Local` for
+/// development, CI, and air-gapped deployments.
+pub enum VectorStoreConfig {
+    Pinecone { api_key: String, environment: String, index_name: String },
+    Qdrant { base_url: String, collection: String, api_key: Option<String> },
+    Local { persist_path: Option<PathBuf> },
+}
+
+/// Operations every vector-store backend must support. `VectorDatabase`'s
+/// fingerprint-indexing logic is written entirely against this trait, so it
+/// doesn't need to change when a new backend is added — only a new
+/// `VectorStore` impl and `VectorBackend`/`VectorStoreConfig` variant do.
+pub trait VectorStore: Send + Sync {
+    async fn get_index_stats(&self) -> Result<IndexStats>;
+
+    async fn upsert_vectors(
+        &self,
+        vectors: Vec<(String, Vec<f32>, HashMap<String, serde_json::Value>)>,
+        namespace: Option<String>,
+    ) -> Result<()>;
+
+    async fn query_similar(
+        &self,
+        query_vector: Vec<f32>,
+        top_k: u32,
+        namespace: Option<String>,
+        filter: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<Vec<VectorSearchResult>>;
+
+    async fn delete_vectors(&self, ids: Vec<String>, namespace: Option<String>) -> Result<()>;
+
+    async fn fetch_vector(&self, id: &str, namespace: Option<String>) -> Result<Option<Vec<f32>>>;
+
+    /// Confirm the store is reachable and ready. Default: a stats round-trip.
+    async fn initialize(&self) -> Result<()> {
+        let stats = self.get_index_stats().await?;
+        tracing::info!(
+            "Vector database initialized: {} vectors, {} dimensions",
+            stats.total_vector_count,
+            stats.dimension
+        );
+        Ok(())
+    }
+
+    /// Default health check: the store responds to a stats round-trip.
+    async fn health_check(&self) -> Result<bool> {
+        Ok(self.get_index_stats().await.is_ok())
+    }
+}
+
+/// The original network-backed implementation, talking to a Pinecone index
+/// over HTTP.
+struct PineconeStore {
     api_key: String,
     environment: String,
     index_name: String,
-    dimensions: u32,
     client: reqwest::Client,
     base_url: String,
 }
@@ -74,43 +165,28 @@ pub struct IndexStats {
     pub index_fullness: f32,
 }
 
-impl VectorDatabase {
-    /// Create a new vector database client
-    pub fn new(
-        api_key: String,
-        environment: String,
-        index_name: String,
-        dimensions: u32,
-    ) -> Self {
-        let base_url = format!("https://{}-{}.svc.pinecone.io", index_name, environment);
-        
-        Self {
-            api_key,
-            environment,
-            index_name,
-            dimensions,
-            client: reqwest::Client::new(),
-            base_url,
-        }
-    }
+/// Vector fetch response
+#[derive(Debug, Deserialize)]
+struct VectorFetchResponse {
+    vectors: HashMap<String, VectorFetchRecord>,
+    #[allow(dead_code)]
+    namespace: String,
+}
 
-    /// Initialize the vector database connection
-    pub async fn initialize(&self) -> Result<()> {
-        // Check if index exists and is ready
-        let stats = self.get_index_stats().await?;
-        tracing::info!(
-            "Vector database initialized: {} vectors, {} dimensions",
-            stats.total_vector_count,
-            stats.dimension
-        );
-        
-        Ok(())
-    }
+/// Vector fetch record
+#[derive(Debug, Deserialize)]
+struct VectorFetchRecord {
+    #[allow(dead_code)]
+    id: String,
+    values: Vec<f32>,
+    #[allow(dead_code)]
+    metadata: Option<HashMap<String, serde_json::Value>>,
+}
 
-    /// Get index statistics
-    pub async fn get_index_stats(&self) -> Result<IndexStats> {
+impl VectorStore for PineconeStore {
+    async fn get_index_stats(&self) -> Result<IndexStats> {
         let url = format!("{}/describe_index_stats", self.base_url);
-        
+
         let response = self.client
             .post(&url)
             .header("Api-Key", &self.api_key)
@@ -127,14 +203,13 @@ impl VectorDatabase {
         Ok(stats)
     }
 
-    /// Upsert vectors to the database
-    pub async fn upsert_vectors(
+    async fn upsert_vectors(
         &self,
         vectors: Vec<(String, Vec<f32>, HashMap<String, serde_json::Value>)>,
         namespace: Option<String>,
     ) -> Result<()> {
         let url = format!("{}/vectors/upsert", self.base_url);
-        
+
         let vector_data: Vec<VectorData> = vectors
             .into_iter()
             .map(|(id, values, metadata)| VectorData {
@@ -166,8 +241,7 @@ impl VectorDatabase {
         Ok(())
     }
 
-    /// Query similar vectors
-    pub async fn query_similar(
+    async fn query_similar(
         &self,
         query_vector: Vec<f32>,
         top_k: u32,
@@ -175,7 +249,7 @@ impl VectorDatabase {
         filter: Option<HashMap<String, serde_json::Value>>,
     ) -> Result<Vec<VectorSearchResult>> {
         let url = format!("{}/query", self.base_url);
-        
+
         let request = VectorQueryRequest {
             vector: query_vector,
             top_k,
@@ -198,7 +272,7 @@ impl VectorDatabase {
         }
 
         let query_response: VectorQueryResponse = response.json().await?;
-        
+
         let results: Vec<VectorSearchResult> = query_response
             .matches
             .into_iter()
@@ -213,18 +287,17 @@ impl VectorDatabase {
         Ok(results)
     }
 
-    /// Delete vectors by IDs
-    pub async fn delete_vectors(
+    async fn delete_vectors(
         &self,
         ids: Vec<String>,
         namespace: Option<String>,
     ) -> Result<()> {
         let url = format!("{}/vectors/delete", self.base_url);
-        
+
         let mut request_body = serde_json::json!({
             "ids": ids
         });
-        
+
         if let Some(ns) = namespace {
             request_body["namespace"] = serde_json::Value::String(ns);
         }
@@ -246,134 +319,1004 @@ impl VectorDatabase {
         Ok(())
     }
 
-    /// Add audio fingerprint to vector database
-    pub async fn add_fingerprint(
+    async fn fetch_vector(&self, id: &str, namespace: Option<String>) -> Result<Option<Vec<f32>>> {
+        let mut url = format!("{}/vectors/fetch?ids={}", self.base_url, id);
+        if let Some(ns) = &namespace {
+            url.push_str(&format!("&namespace={}", ns));
+        }
+
+        let response = self.client
+            .get(&url)
+            .header("Api-Key", &self.api_key)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(anyhow::anyhow!("Failed to fetch vector: {}", error_text));
+        }
+
+        let fetch_response: VectorFetchResponse = response.json().await?;
+        Ok(fetch_response.vectors.get(id).map(|record| record.values.clone()))
+    }
+}
+
+/// Network-backed `VectorStore` talking to a self-hosted Qdrant instance
+/// over its HTTP API. Qdrant's wire format differs from Pinecone's in every
+/// respect this module touches: point IDs must be unsigned integers or
+/// UUIDs (not arbitrary strings), there's no namespace concept (modeled here
+/// as one collection per namespace), and metadata filters are a `must`
+/// clause of field/match conditions rather than a flat equality map.
+struct QdrantStore {
+    base_url: String,
+    collection: String,
+    api_key: Option<String>,
+    client: reqwest::Client,
+}
+
+/// Deterministically derive a Qdrant-compatible unsigned point ID from our
+/// string vector IDs, so the same logical ID always maps to the same point
+/// both at upsert and at delete/fetch time.
+fn qdrant_point_id(id: &str) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    id.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Translate a Pinecone-style flat equality filter into Qdrant's `must`
+/// clause of field/match conditions.
+fn qdrant_filter(filter: &HashMap<String, serde_json::Value>) -> serde_json::Value {
+    let must: Vec<serde_json::Value> = filter
+        .iter()
+        .map(|(key, value)| serde_json::json!({ "key": key, "match": { "value": value } }))
+        .collect();
+
+    serde_json::json!({ "must": must })
+}
+
+#[derive(Debug, Serialize)]
+struct QdrantPoint {
+    id: u64,
+    vector: Vec<f32>,
+    payload: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantCollectionInfoResponse {
+    result: QdrantCollectionInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantCollectionInfo {
+    points_count: u64,
+    config: QdrantCollectionConfig,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantCollectionConfig {
+    params: QdrantCollectionParams,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantCollectionParams {
+    vectors: QdrantVectorParams,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantVectorParams {
+    size: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantSearchResponse {
+    result: Vec<QdrantScoredPoint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantScoredPoint {
+    score: f32,
+    payload: Option<HashMap<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantPointsResponse {
+    result: Vec<QdrantRetrievedPoint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantRetrievedPoint {
+    vector: Option<Vec<f32>>,
+}
+
+impl QdrantStore {
+    /// Qdrant has no namespace concept; model one as a collection of its own
+    /// so per-namespace vectors (e.g. perceptual features vs. fingerprints)
+    /// stay as isolated as they are under Pinecone.
+    fn collection_name(&self, namespace: &Option<String>) -> String {
+        match namespace {
+            Some(ns) => format!("{}_{}", self.collection, ns),
+            None => self.collection.clone(),
+        }
+    }
+
+    fn request(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(key) => builder.header("api-key", key),
+            None => builder,
+        }
+    }
+}
+
+impl VectorStore for QdrantStore {
+    async fn get_index_stats(&self) -> Result<IndexStats> {
+        let url = format!("{}/collections/{}", self.base_url, self.collection);
+        let response = self.request(self.client.get(&url)).send().await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(anyhow::anyhow!("Failed to get Qdrant collection info: {}", error_text));
+        }
+
+        let info: QdrantCollectionInfoResponse = response.json().await?;
+        Ok(IndexStats {
+            total_vector_count: info.result.points_count,
+            dimension: info.result.config.params.vectors.size,
+            index_fullness: 0.0,
+        })
+    }
+
+    async fn upsert_vectors(
         &self,
-        song_id: Uuid,
-        fingerprint: &crate::fingerprint::Fingerprint,
-        metadata: HashMap<String, serde_json::Value>,
+        vectors: Vec<(String, Vec<f32>, HashMap<String, serde_json::Value>)>,
+        namespace: Option<String>,
     ) -> Result<()> {
-        // Convert fingerprint to vector representation
-        let vector = self.fingerprint_to_vector(fingerprint)?;
-        
-        let mut full_metadata = metadata;
-        full_metadata.insert("song_id".to_string(), serde_json::Value::String(song_id.to_string()));
-        full_metadata.insert("fingerprint_id".to_string(), serde_json::Value::String(Uuid::new_v4().to_string()));
-        full_metadata.insert("created_at".to_string(), serde_json::Value::String(chrono::Utc::now().to_rfc3339()));
+        let url = format!("{}/collections/{}/points", self.base_url, self.collection_name(&namespace));
 
-        let vector_id = format!("fingerprint_{}", song_id);
-        
-        self.upsert_vectors(
-            vec![(vector_id, vector, full_metadata)],
-            None,
-        ).await?;
+        let points: Vec<QdrantPoint> = vectors
+            .into_iter()
+            .map(|(id, values, mut metadata)| {
+                metadata.insert("_id".to_string(), serde_json::Value::String(id.clone()));
+                QdrantPoint { id: qdrant_point_id(&id), vector: values, payload: metadata }
+            })
+            .collect();
+        let count = points.len();
+
+        let response = self
+            .request(self.client.put(&url))
+            .json(&serde_json::json!({ "points": points }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(anyhow::anyhow!("Failed to upsert vectors to Qdrant: {}", error_text));
+        }
 
+        tracing::info!("Successfully upserted {} vectors", count);
         Ok(())
     }
 
-    /// Search for similar fingerprints
-    pub async fn search_similar_fingerprints(
+    async fn query_similar(
         &self,
-        query_fingerprint: &crate::fingerprint::Fingerprint,
+        query_vector: Vec<f32>,
         top_k: u32,
-        language_filter: Option<String>,
-        genre_filter: Option<String>,
+        namespace: Option<String>,
+        filter: Option<HashMap<String, serde_json::Value>>,
     ) -> Result<Vec<VectorSearchResult>> {
-        // Convert fingerprint to vector
-        let query_vector = self.fingerprint_to_vector(query_fingerprint)?;
-        
-        // Build filter if needed
-        let mut filter = HashMap::new();
-        if let Some(lang) = language_filter {
-            filter.insert("language".to_string(), serde_json::Value::String(lang));
+        let url = format!("{}/collections/{}/points/search", self.base_url, self.collection_name(&namespace));
+
+        let mut body = serde_json::json!({
+            "vector": query_vector,
+            "limit": top_k,
+            "with_payload": true,
+        });
+        if let Some(filter) = &filter {
+            body["filter"] = qdrant_filter(filter);
         }
-        if let Some(genre) = genre_filter {
-            filter.insert("genre".to_string(), serde_json::Value::String(genre));
+
+        let response = self.request(self.client.post(&url)).json(&body).send().await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(anyhow::anyhow!("Failed to query Qdrant: {}", error_text));
         }
-        
-        let filter_option = if filter.is_empty() { None } else { Some(filter) };
 
-        let results = self.query_similar(
-            query_vector,
-            top_k,
-            None,
-            filter_option,
-        ).await?;
+        let search_response: QdrantSearchResponse = response.json().await?;
+        let results: Vec<VectorSearchResult> = search_response
+            .result
+            .into_iter()
+            .filter_map(|point| {
+                let mut metadata = point.payload.unwrap_or_default();
+                let id = match metadata.remove("_id") {
+                    Some(serde_json::Value::String(id)) => id,
+                    _ => return None,
+                };
+                Some(VectorSearchResult { id, score: point.score, metadata })
+            })
+            .collect();
 
+        tracing::debug!("Found {} similar vectors", results.len());
         Ok(results)
     }
 
-    /// Convert fingerprint to vector representation
-    fn fingerprint_to_vector(&self, fingerprint: &crate::fingerprint::Fingerprint) -> Result<Vec<f32>> {
-        // Use spectral features as vector representation
-        let mut vector = Vec::new();
-        
-        // Add spectral features
-        if !fingerprint.peaks.is_empty() {
-            // Frequency distribution (20 bins)
-            let freq_bins = 20;
-            let mut freq_histogram = vec![0.0; freq_bins];
-            
-            for peak in &fingerprint.peaks {
-                let bin = ((peak.frequency / 20000.0) * freq_bins as f32) as usize;
-                if bin < freq_bins {
-                    freq_histogram[bin] += peak.magnitude;
-                }
-            }
-            
-            // Normalize frequency histogram
-            let max_freq = freq_histogram.iter().fold(0.0, |a, &b| a.max(b));
-            if max_freq > 0.0 {
-                for val in &mut freq_histogram {
-                    *val /= max_freq;
-                }
-            }
-            
-            vector.extend(freq_histogram);
-        }
-        
-        // Add time distribution (10 bins)
-        let time_bins = 10;
-        let mut time_histogram = vec![0.0; time_bins];
-        
-        for peak in &fingerprint.peaks {
-            let bin = ((peak.time / fingerprint.metadata.duration) * time_bins as f32) as usize;
-            if bin < time_bins {
-                time_histogram[bin] += peak.magnitude;
-            }
+    async fn delete_vectors(&self, ids: Vec<String>, namespace: Option<String>) -> Result<()> {
+        let url = format!("{}/collections/{}/points/delete", self.base_url, self.collection_name(&namespace));
+        let point_ids: Vec<u64> = ids.iter().map(|id| qdrant_point_id(id)).collect();
+
+        let response = self
+            .request(self.client.post(&url))
+            .json(&serde_json::json!({ "points": point_ids }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(anyhow::anyhow!("Failed to delete vectors from Qdrant: {}", error_text));
         }
-        
-        // Normalize time histogram
+
+        tracing::info!("Successfully deleted {} vectors", ids.len());
+        Ok(())
+    }
+
+    async fn fetch_vector(&self, id: &str, namespace: Option<String>) -> Result<Option<Vec<f32>>> {
+        let url = format!("{}/collections/{}/points", self.base_url, self.collection_name(&namespace));
+
+        let response = self
+            .request(self.client.post(&url))
+            .json(&serde_json::json!({ "ids": [qdrant_point_id(id)], "with_vector": true, "with_payload": false }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(anyhow::anyhow!("Failed to fetch vector from Qdrant: {}", error_text));
+        }
+
+        let points_response: QdrantPointsResponse = response.json().await?;
+        Ok(points_response.result.into_iter().next().and_then(|point| point.vector))
+    }
+}
+
+/// One Pinecone-style "namespace" worth of vectors in a `LocalVectorIndex`: an
+/// HNSW graph for search plus the per-id metadata Pinecone would otherwise
+/// store alongside each vector.
+#[derive(Serialize, Deserialize)]
+struct LocalNamespace {
+    graph: HnswIndex,
+    metadata: HashMap<String, HashMap<String, serde_json::Value>>,
+}
+
+impl LocalNamespace {
+    fn new() -> Self {
+        Self { graph: HnswIndex::new(HnswConfig::default()), metadata: HashMap::new() }
+    }
+}
+
+/// Offline stand-in for the network-backed `VectorStore`s: the same operations, backed by
+/// an in-process HNSW graph per namespace instead of a network round-trip.
+#[derive(Serialize, Deserialize)]
+struct LocalVectorIndex {
+    dimensions: u32,
+    namespaces: HashMap<String, LocalNamespace>,
+    #[serde(skip)]
+    persist_path: Option<PathBuf>,
+}
+
+impl LocalVectorIndex {
+    fn new(dimensions: u32) -> Self {
+        Self { dimensions, namespaces: HashMap::new(), persist_path: None }
+    }
+
+    /// Load a previously-persisted graph from `path`, or start a fresh empty
+    /// one if nothing's there yet; either way, future writes save back to `path`.
+    fn load_or_create(dimensions: u32, path: PathBuf) -> Result<Self> {
+        if path.exists() {
+            let bytes = std::fs::read(&path)?;
+            let mut index: Self = bincode::deserialize(&bytes)?;
+            index.persist_path = Some(path);
+            return Ok(index);
+        }
+
+        Ok(Self { persist_path: Some(path), ..Self::new(dimensions) })
+    }
+
+    fn persist(&self) -> Result<()> {
+        let Some(path) = &self.persist_path else {
+            return Ok(());
+        };
+
+        let bytes = bincode::serialize(self)?;
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    fn namespace_key(namespace: &Option<String>) -> &str {
+        namespace.as_deref().unwrap_or_default()
+    }
+
+    fn get_index_stats(&self) -> Result<IndexStats> {
+        let total_vector_count: u64 = self.namespaces.values().map(|ns| ns.graph.len() as u64).sum();
+        Ok(IndexStats { total_vector_count, dimension: self.dimensions, index_fullness: 0.0 })
+    }
+
+    fn upsert_vectors(
+        &mut self,
+        vectors: Vec<(String, Vec<f32>, HashMap<String, serde_json::Value>)>,
+        namespace: Option<String>,
+    ) -> Result<()> {
+        let key = Self::namespace_key(&namespace).to_string();
+        let ns = self.namespaces.entry(key).or_insert_with(LocalNamespace::new);
+
+        for (id, values, metadata) in vectors {
+            ns.graph.insert(id.clone(), values);
+            ns.metadata.insert(id, metadata);
+        }
+
+        self.persist()
+    }
+
+    fn query_similar(
+        &self,
+        query_vector: &[f32],
+        top_k: u32,
+        namespace: Option<String>,
+        filter: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<Vec<VectorSearchResult>> {
+        let Some(ns) = self.namespaces.get(Self::namespace_key(&namespace)) else {
+            return Ok(Vec::new());
+        };
+
+        let results = ns
+            .graph
+            .search(query_vector, top_k as usize)
+            .into_iter()
+            .filter_map(|(id, distance)| {
+                let metadata = ns.metadata.get(&id).cloned().unwrap_or_default();
+                if let Some(filter) = &filter {
+                    if !matches_filter(&metadata, filter) {
+                        return None;
+                    }
+                }
+                // Cosine distance is in `[0, 2]`; map it back to a Pinecone-style
+                // similarity score (higher is more similar) for a consistent caller API.
+                Some(VectorSearchResult { id, score: 1.0 - distance, metadata })
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    fn delete_vectors(&mut self, ids: &[String], namespace: Option<String>) -> Result<()> {
+        let key = Self::namespace_key(&namespace).to_string();
+        if let Some(ns) = self.namespaces.get_mut(&key) {
+            for id in ids {
+                ns.graph.delete(id);
+                ns.metadata.remove(id);
+            }
+        }
+
+        self.persist()
+    }
+
+    fn fetch_vector(&self, id: &str, namespace: Option<String>) -> Option<Vec<f32>> {
+        self.namespaces.get(Self::namespace_key(&namespace))?.graph.get(id)
+    }
+}
+
+impl VectorStore for RwLock<LocalVectorIndex> {
+    async fn get_index_stats(&self) -> Result<IndexStats> {
+        self.read().await.get_index_stats()
+    }
+
+    async fn upsert_vectors(
+        &self,
+        vectors: Vec<(String, Vec<f32>, HashMap<String, serde_json::Value>)>,
+        namespace: Option<String>,
+    ) -> Result<()> {
+        self.write().await.upsert_vectors(vectors, namespace)
+    }
+
+    async fn query_similar(
+        &self,
+        query_vector: Vec<f32>,
+        top_k: u32,
+        namespace: Option<String>,
+        filter: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<Vec<VectorSearchResult>> {
+        self.read().await.query_similar(&query_vector, top_k, namespace, filter)
+    }
+
+    async fn delete_vectors(&self, ids: Vec<String>, namespace: Option<String>) -> Result<()> {
+        self.write().await.delete_vectors(&ids, namespace)
+    }
+
+    async fn fetch_vector(&self, id: &str, namespace: Option<String>) -> Result<Option<Vec<f32>>> {
+        Ok(self.read().await.fetch_vector(id, namespace))
+    }
+}
+
+/// Maximum number of HTTP requests `query_similar_batch`/
+/// `search_similar_fingerprints_batch` keep in flight at once, so
+/// fingerprinting a burst of sliding-window segments doesn't open one
+/// connection per window.
+const BATCH_CONCURRENCY_LIMIT: usize = 8;
+
+/// Log aggregate latency/throughput for a batch call, and how many of its
+/// per-item results failed, via the same `tracing` hooks the rest of this
+/// module uses.
+fn log_batch_throughput<T>(operation: &str, total: usize, results: &[Result<T>], elapsed: Duration) {
+    let failed = results.iter().filter(|r| r.is_err()).count();
+    let seconds = elapsed.as_secs_f64();
+    tracing::info!(
+        "{}: {} queries ({} failed) in {:.3}s ({:.1} queries/sec)",
+        operation,
+        total,
+        failed,
+        seconds,
+        total as f64 / seconds.max(f64::EPSILON),
+    );
+}
+
+/// Approximate Pinecone's equality-filter semantics for the local backend: a
+/// candidate matches only if every key in `filter` is present in its metadata
+/// with an equal value. Richer operators (`$in`, `$gte`, ...) aren't
+/// implemented since nothing in this codebase issues them yet.
+fn matches_filter(metadata: &HashMap<String, serde_json::Value>, filter: &HashMap<String, serde_json::Value>) -> bool {
+    filter.iter().all(|(key, value)| metadata.get(key) == Some(value))
+}
+
+/// Width of each offset-histogram bin, in seconds, used by `HashIndex::rank`.
+/// Matches `fingerprint_db::OFFSET_BIN_SIZE_SECONDS`.
+const HASH_INDEX_OFFSET_BIN_SIZE_SECONDS: f32 = 0.05;
+
+/// An in-memory landmark-hash inverted index kept alongside the vector
+/// backend, so `hybrid_search_similar_fingerprints` can run an exact/near-exact
+/// hash lookup without needing the raw `Fingerprint.hashes` that
+/// `fingerprint_to_vector`'s histogram conversion throws away. Populated by
+/// `add_fingerprint`/`batch_upsert_fingerprints` alongside the vector upsert.
+#[derive(Default)]
+struct HashIndex {
+    hash_table: HashMap<u64, Vec<(String, f32)>>, // hash -> [(vector_id, time_offset)]
+}
+
+impl HashIndex {
+    fn insert(&mut self, vector_id: String, fingerprint: &crate::fingerprint::Fingerprint) {
+        for (&hash, &time_offset) in fingerprint.hashes.iter().zip(fingerprint.time_offsets.iter()) {
+            self.hash_table.entry(hash).or_insert_with(Vec::new).push((vector_id.clone(), time_offset));
+        }
+    }
+
+    /// Rank indexed fingerprints by temporally-consistent landmark-hash
+    /// matches against `query`, using the same offset-histogram voting as
+    /// `fingerprint_db::FingerprintDb::find_matches`: a candidate's matches are
+    /// histogrammed by time-offset bin, and its vote count is the tallest
+    /// bin's count (genuine matches share one consistent time shift; spurious
+    /// collisions scatter across bins). Returns `(vector_id, match_count)`
+    /// pairs sorted by descending match count, truncated to `limit`.
+    fn rank(&self, query: &crate::fingerprint::Fingerprint, limit: usize) -> Vec<(String, usize)> {
+        let mut histograms: HashMap<String, HashMap<i64, u32>> = HashMap::new();
+
+        for (&hash, &query_time) in query.hashes.iter().zip(query.time_offsets.iter()) {
+            if let Some(postings) = self.hash_table.get(&hash) {
+                for (vector_id, indexed_time) in postings {
+                    let delta = indexed_time - query_time;
+                    let bin = (delta / HASH_INDEX_OFFSET_BIN_SIZE_SECONDS).round() as i64;
+                    *histograms.entry(vector_id.clone()).or_insert_with(HashMap::new).entry(bin).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut ranked: Vec<(String, usize)> = histograms
+            .into_iter()
+            .filter_map(|(vector_id, histogram)| Some((vector_id, *histogram.values().max()? as usize)))
+            .collect();
+
+        ranked.sort_by(|a, b| b.1.cmp(&a.1));
+        ranked.truncate(limit);
+        ranked
+    }
+}
+
+/// Converts a fingerprint into the fixed-width vector that gets indexed and
+/// searched against. Swappable so `VectorDatabase` isn't locked into the
+/// hand-rolled histogram layout `HistogramEmbedder` implements by default —
+/// e.g. a learned embedding model, or precomputed vectors supplied by the
+/// caller via `PrecomputedEmbedder`.
+pub trait Embedder: Send + Sync {
+    /// Produce the vector representation of `fingerprint`. The returned
+    /// vector's length must equal `dimensions()`; `VectorDatabase` treats a
+    /// mismatch as an error rather than padding/truncating on its behalf.
+    fn embed(&self, fingerprint: &crate::fingerprint::Fingerprint) -> Result<Vec<f32>>;
+
+    /// The width of vectors this embedder produces.
+    fn dimensions(&self) -> u32;
+}
+
+/// Default `Embedder`: a hand-rolled histogram over the fingerprint's
+/// spectral peaks (20 frequency bins + 10 time bins + 3 magnitude stats),
+/// zero-padded or truncated to `dimensions`. This is the original
+/// `fingerprint_to_vector` logic, kept as the out-of-the-box behavior.
+pub struct HistogramEmbedder {
+    dimensions: u32,
+}
+
+impl HistogramEmbedder {
+    pub fn new(dimensions: u32) -> Self {
+        Self { dimensions }
+    }
+}
+
+impl Embedder for HistogramEmbedder {
+    fn embed(&self, fingerprint: &crate::fingerprint::Fingerprint) -> Result<Vec<f32>> {
+        // Use spectral features as vector representation
+        let mut vector = Vec::new();
+
+        // Add spectral features
+        if !fingerprint.peaks.is_empty() {
+            // Frequency distribution (20 bins)
+            let freq_bins = 20;
+            let mut freq_histogram = vec![0.0; freq_bins];
+
+            for peak in &fingerprint.peaks {
+                let bin = ((peak.frequency / 20000.0) * freq_bins as f32) as usize;
+                if bin < freq_bins {
+                    freq_histogram[bin] += peak.magnitude;
+                }
+            }
+
+            // Normalize frequency histogram
+            let max_freq = freq_histogram.iter().fold(0.0, |a, &b| a.max(b));
+            if max_freq > 0.0 {
+                for val in &mut freq_histogram {
+                    *val /= max_freq;
+                }
+            }
+
+            vector.extend(freq_histogram);
+        }
+
+        // Add time distribution (10 bins)
+        let time_bins = 10;
+        let mut time_histogram = vec![0.0; time_bins];
+
+        for peak in &fingerprint.peaks {
+            let bin = ((peak.time / fingerprint.metadata.duration) * time_bins as f32) as usize;
+            if bin < time_bins {
+                time_histogram[bin] += peak.magnitude;
+            }
+        }
+
+        // Normalize time histogram
         let max_time = time_histogram.iter().fold(0.0, |a, &b| a.max(b));
         if max_time > 0.0 {
             for val in &mut time_histogram {
                 *val /= max_time;
             }
         }
-        
+
         vector.extend(time_histogram);
-        
+
         // Add statistical features
         if !fingerprint.peaks.is_empty() {
             let magnitudes: Vec<f32> = fingerprint.peaks.iter().map(|p| p.magnitude).collect();
             let mean_magnitude = magnitudes.iter().sum::<f32>() / magnitudes.len() as f32;
             let max_magnitude = magnitudes.iter().fold(0.0, |a, &b| a.max(b));
             let min_magnitude = magnitudes.iter().fold(f32::INFINITY, |a, &b| a.min(b));
-            
+
             vector.push(mean_magnitude);
             vector.push(max_magnitude);
             vector.push(min_magnitude);
         }
+
+        // Pad or truncate to target dimensions
+        while vector.len() < self.dimensions as usize {
+            vector.push(0.0);
+        }
+
+        if vector.len() > self.dimensions as usize {
+            vector.truncate(self.dimensions as usize);
+        }
+
+        Ok(vector)
+    }
+
+    fn dimensions(&self) -> u32 {
+        self.dimensions
+    }
+}
+
+/// `Embedder` for vectors computed outside this process (an external model,
+/// a batch job, ...) and handed to us ahead of time. Vectors are registered
+/// per-fingerprint via `register`, keyed by the fingerprint's own hashes
+/// (stable for a given recording, with no song_id/identity needed at
+/// `embed`-time); `embed` looks the vector back up rather than computing one.
+#[derive(Default)]
+pub struct PrecomputedEmbedder {
+    dimensions: u32,
+    vectors: HashMap<Vec<u64>, Vec<f32>>,
+}
+
+impl PrecomputedEmbedder {
+    pub fn new(dimensions: u32) -> Self {
+        Self { dimensions, vectors: HashMap::new() }
+    }
+
+    /// Register the externally-computed vector for `fingerprint`, to be
+    /// returned by a later `embed` call for an equal fingerprint. Errors if
+    /// `vector`'s length doesn't match this embedder's configured dimensions.
+    pub fn register(&mut self, fingerprint: &crate::fingerprint::Fingerprint, vector: Vec<f32>) -> Result<()> {
+        if vector.len() != self.dimensions as usize {
+            return Err(anyhow::anyhow!(
+                "precomputed vector has {} dimensions but this embedder is configured for {}",
+                vector.len(),
+                self.dimensions
+            ));
+        }
+
+        self.vectors.insert(fingerprint.hashes.clone(), vector);
+        Ok(())
+    }
+}
+
+impl Embedder for PrecomputedEmbedder {
+    fn embed(&self, fingerprint: &crate::fingerprint::Fingerprint) -> Result<Vec<f32>> {
+        self.vectors
+            .get(&fingerprint.hashes)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("no precomputed vector registered for this fingerprint"))
+    }
+
+    fn dimensions(&self) -> u32 {
+        self.dimensions
+    }
+}
+
+/// Fuse two ranked result lists with Reciprocal Rank Fusion (`k = 60`):
+/// `score(d) = (1 - semantic_ratio) / (k + rank_hash(d)) + semantic_ratio / (k + rank_vector(d))`,
+/// where `rank_*` is the 1-based position in each list and a candidate absent
+/// from a list contributes 0 for that term. `semantic_ratio = 0.0` degenerates
+/// to pure hash-match ranking; `1.0` to pure vector-similarity ranking.
+fn fuse_with_reciprocal_rank_fusion(
+    vector_results: Vec<VectorSearchResult>,
+    hash_results: Vec<(String, usize)>,
+    semantic_ratio: f32,
+) -> Vec<VectorSearchResult> {
+    const RRF_K: f32 = 60.0;
+    let semantic_ratio = semantic_ratio.clamp(0.0, 1.0);
+
+    let mut fused: HashMap<String, (f32, HashMap<String, serde_json::Value>)> = HashMap::new();
+
+    for (index, result) in vector_results.into_iter().enumerate() {
+        let rank = (index + 1) as f32;
+        let entry = fused.entry(result.id).or_insert_with(|| (0.0, HashMap::new()));
+        entry.0 += semantic_ratio / (RRF_K + rank);
+        entry.1.extend(result.metadata);
+        entry.1.insert("vector_score".to_string(), serde_json::json!(result.score));
+    }
+
+    for (index, (id, match_count)) in hash_results.into_iter().enumerate() {
+        let rank = (index + 1) as f32;
+        let entry = fused.entry(id).or_insert_with(|| (0.0, HashMap::new()));
+        entry.0 += (1.0 - semantic_ratio) / (RRF_K + rank);
+        entry.1.insert("hash_match_count".to_string(), serde_json::json!(match_count));
+    }
+
+    let mut results: Vec<VectorSearchResult> = fused
+        .into_iter()
+        .map(|(id, (score, metadata))| VectorSearchResult { id, score, metadata })
+        .collect();
+
+    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    results
+}
+
+impl VectorDatabase {
+    /// Build a vector database for the backend selected by `config`, at
+    /// `dimensions`. This is the single place that turns a `VectorStoreConfig`
+    /// into the concrete `VectorBackend` it describes; `new`/`new_local`/
+    /// `new_qdrant` are thin convenience wrappers around it.
+    pub fn from_config(config: VectorStoreConfig, dimensions: u32) -> Result<Self> {
+        let backend = match config {
+            VectorStoreConfig::Pinecone { api_key, environment, index_name } => {
+                let base_url = format!("https://{}-{}.svc.pinecone.io", index_name, environment);
+                VectorBackend::Pinecone(PineconeStore {
+                    api_key,
+                    environment,
+                    index_name,
+                    client: reqwest::Client::new(),
+                    base_url,
+                })
+            }
+            VectorStoreConfig::Qdrant { base_url, collection, api_key } => {
+                VectorBackend::Qdrant(QdrantStore { base_url, collection, api_key, client: reqwest::Client::new() })
+            }
+            VectorStoreConfig::Local { persist_path } => {
+                let index = match persist_path {
+                    Some(path) => LocalVectorIndex::load_or_create(dimensions, path)?,
+                    None => LocalVectorIndex::new(dimensions),
+                };
+                VectorBackend::Local(RwLock::new(index))
+            }
+        };
+
+        Ok(Self {
+            dimensions,
+            backend,
+            hash_index: RwLock::new(HashIndex::default()),
+            embedder: Box::new(HistogramEmbedder::new(dimensions)),
+        })
+    }
+
+    /// Create a new vector database client, backed by Pinecone over HTTP
+    pub fn new(
+        api_key: String,
+        environment: String,
+        index_name: String,
+        dimensions: u32,
+    ) -> Self {
+        Self::from_config(VectorStoreConfig::Pinecone { api_key, environment, index_name }, dimensions)
+            .expect("the Pinecone backend never fails to construct")
+    }
+
+    /// Create a vector database backed by a self-hosted Qdrant instance
+    /// instead of Pinecone.
+    pub fn new_qdrant(base_url: String, collection: String, api_key: Option<String>, dimensions: u32) -> Self {
+        Self::from_config(VectorStoreConfig::Qdrant { base_url, collection, api_key }, dimensions)
+            .expect("the Qdrant backend never fails to construct")
+    }
+
+    /// Create a vector database backed by an in-process HNSW index instead of
+    /// a network store, so fingerprints and perceptual features can be
+    /// searched with no external service — local development, CI, and
+    /// air-gapped deployments. If `persist_path` points at an existing file,
+    /// the graph is loaded from it; otherwise a new empty index is created,
+    /// and (if `persist_path` is set) saved there after every write.
+    pub fn new_local(dimensions: u32, persist_path: Option<PathBuf>) -> Result<Self> {
+        Self::from_config(VectorStoreConfig::Local { persist_path }, dimensions)
+    }
+
+    /// Replace the embedder used by `add_fingerprint`/`batch_upsert_fingerprints`
+    /// /`search_similar_fingerprints` to turn fingerprints into vectors.
+    /// Errors if `embedder.dimensions()` doesn't match this index's configured
+    /// width, rather than silently padding/truncating at embed-time.
+    pub fn set_embedder(&mut self, embedder: Box<dyn Embedder>) -> Result<()> {
+        if embedder.dimensions() != self.dimensions {
+            return Err(anyhow::anyhow!(
+                "embedder dimensions ({}) do not match this index's configured dimensions ({})",
+                embedder.dimensions(),
+                self.dimensions
+            ));
+        }
+
+        self.embedder = embedder;
+        Ok(())
+    }
+
+    /// Initialize the vector database connection
+    pub async fn initialize(&self) -> Result<()> {
+        match &self.backend {
+            VectorBackend::Pinecone(store) => store.initialize().await,
+            VectorBackend::Qdrant(store) => store.initialize().await,
+            VectorBackend::Local(store) => store.initialize().await,
+        }
+    }
+
+    /// Get index statistics
+    pub async fn get_index_stats(&self) -> Result<IndexStats> {
+        match &self.backend {
+            VectorBackend::Pinecone(store) => store.get_index_stats().await,
+            VectorBackend::Qdrant(store) => store.get_index_stats().await,
+            VectorBackend::Local(store) => store.get_index_stats().await,
+        }
+    }
+
+    /// Upsert vectors to the database
+    pub async fn upsert_vectors(
+        &self,
+        vectors: Vec<(String, Vec<f32>, HashMap<String, serde_json::Value>)>,
+        namespace: Option<String>,
+    ) -> Result<()> {
+        match &self.backend {
+            VectorBackend::Pinecone(store) => store.upsert_vectors(vectors, namespace).await,
+            VectorBackend::Qdrant(store) => store.upsert_vectors(vectors, namespace).await,
+            VectorBackend::Local(store) => store.upsert_vectors(vectors, namespace).await,
+        }
+    }
+
+    /// Query similar vectors
+    pub async fn query_similar(
+        &self,
+        query_vector: Vec<f32>,
+        top_k: u32,
+        namespace: Option<String>,
+        filter: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<Vec<VectorSearchResult>> {
+        match &self.backend {
+            VectorBackend::Pinecone(store) => store.query_similar(query_vector, top_k, namespace, filter).await,
+            VectorBackend::Qdrant(store) => store.query_similar(query_vector, top_k, namespace, filter).await,
+            VectorBackend::Local(store) => store.query_similar(query_vector, top_k, namespace, filter).await,
+        }
+    }
+
+    /// Run `top_k`-nearest queries for every vector in `queries` concurrently,
+    /// with at most `BATCH_CONCURRENCY_LIMIT` requests in flight at a time,
+    /// returning one `Result` per query in the same order they were given —
+    /// a failed query doesn't abort the rest of the batch. This is the
+    /// read-side analogue of `batch_upsert_fingerprints`'s chunking, for
+    /// workloads that must resolve a stream of query windows per second.
+    pub async fn query_similar_batch(
+        &self,
+        queries: Vec<Vec<f32>>,
+        top_k: u32,
+        namespace: Option<String>,
+        filter: Option<HashMap<String, serde_json::Value>>,
+    ) -> Vec<Result<Vec<VectorSearchResult>>> {
+        let total_queries = queries.len();
+        let started_at = std::time::Instant::now();
+
+        let results: Vec<Result<Vec<VectorSearchResult>>> = stream::iter(queries.into_iter().map(|query_vector| {
+            let namespace = namespace.clone();
+            let filter = filter.clone();
+            async move { self.query_similar(query_vector, top_k, namespace, filter).await }
+        }))
+        .buffered(BATCH_CONCURRENCY_LIMIT)
+        .collect()
+        .await;
+
+        log_batch_throughput("query_similar_batch", total_queries, &results, started_at.elapsed());
+        results
+    }
+
+    /// Delete vectors by IDs
+    pub async fn delete_vectors(
+        &self,
+        ids: Vec<String>,
+        namespace: Option<String>,
+    ) -> Result<()> {
+        match &self.backend {
+            VectorBackend::Pinecone(store) => store.delete_vectors(ids, namespace).await,
+            VectorBackend::Qdrant(store) => store.delete_vectors(ids, namespace).await,
+            VectorBackend::Local(store) => store.delete_vectors(ids, namespace).await,
+        }
+    }
+
+    /// Fetch a previously-upserted vector by its ID
+    pub async fn fetch_vector(&self, id: &str, namespace: Option<String>) -> Result<Option<Vec<f32>>> {
+        match &self.backend {
+            VectorBackend::Pinecone(store) => store.fetch_vector(id, namespace).await,
+            VectorBackend::Qdrant(store) => store.fetch_vector(id, namespace).await,
+            VectorBackend::Local(store) => store.fetch_vector(id, namespace).await,
+        }
+    }
+
+    /// Add audio fingerprint to vector database
+    pub async fn add_fingerprint(
+        &self,
+        song_id: Uuid,
+        fingerprint: &crate::fingerprint::Fingerprint,
+        metadata: HashMap<String, serde_json::Value>,
+    ) -> Result<()> {
+        // Convert fingerprint to vector representation
+        let vector = self.fingerprint_to_vector(fingerprint)?;
         
-        // Pad or truncate to target dimensions
-        while vector.len() < self.dimensions as usize {
-            vector.push(0.0);
+        let mut full_metadata = metadata;
+        full_metadata.insert("song_id".to_string(), serde_json::Value::String(song_id.to_string()));
+        full_metadata.insert("fingerprint_id".to_string(), serde_json::Value::String(Uuid::new_v4().to_string()));
+        full_metadata.insert("created_at".to_string(), serde_json::Value::String(chrono::Utc::now().to_rfc3339()));
+
+        let vector_id = format!("fingerprint_{}", song_id);
+
+        self.hash_index.write().await.insert(vector_id.clone(), fingerprint);
+
+        self.upsert_vectors(
+            vec![(vector_id, vector, full_metadata)],
+            None,
+        ).await?;
+
+        Ok(())
+    }
+
+    /// Search for similar fingerprints
+    pub async fn search_similar_fingerprints(
+        &self,
+        query_fingerprint: &crate::fingerprint::Fingerprint,
+        top_k: u32,
+        language_filter: Option<String>,
+        genre_filter: Option<String>,
+    ) -> Result<Vec<VectorSearchResult>> {
+        // Convert fingerprint to vector
+        let query_vector = self.fingerprint_to_vector(query_fingerprint)?;
+
+        // Build filter if needed
+        let mut filter = HashMap::new();
+        if let Some(lang) = language_filter {
+            filter.insert("language".to_string(), serde_json::Value::String(lang));
         }
-        
-        if vector.len() > self.dimensions as usize {
-            vector.truncate(self.dimensions as usize);
+        if let Some(genre) = genre_filter {
+            filter.insert("genre".to_string(), serde_json::Value::String(genre));
         }
-        
+
+        let filter_option = if filter.is_empty() { None } else { Some(filter) };
+
+        let results = self.query_similar(
+            query_vector,
+            top_k,
+            None,
+            filter_option,
+        ).await?;
+
+        Ok(results)
+    }
+
+    /// Fingerprint-level analogue of `query_similar_batch`: resolve matches
+    /// for many candidate fingerprints (e.g. sliding-window clips cut from
+    /// one long recording) concurrently, one `Result` per fingerprint in the
+    /// order given.
+    pub async fn search_similar_fingerprints_batch(
+        &self,
+        query_fingerprints: Vec<&crate::fingerprint::Fingerprint>,
+        top_k: u32,
+        language_filter: Option<String>,
+        genre_filter: Option<String>,
+    ) -> Vec<Result<Vec<VectorSearchResult>>> {
+        let total_queries = query_fingerprints.len();
+        let started_at = std::time::Instant::now();
+
+        let results: Vec<Result<Vec<VectorSearchResult>>> = stream::iter(query_fingerprints.into_iter().map(|fingerprint| {
+            let language_filter = language_filter.clone();
+            let genre_filter = genre_filter.clone();
+            async move { self.search_similar_fingerprints(fingerprint, top_k, language_filter, genre_filter).await }
+        }))
+        .buffered(BATCH_CONCURRENCY_LIMIT)
+        .collect()
+        .await;
+
+        log_batch_throughput("search_similar_fingerprints_batch", total_queries, &results, started_at.elapsed());
+        results
+    }
+
+    /// Search for similar fingerprints using both retrieval signals at once:
+    /// an exact/near-exact landmark-hash lookup (via the in-memory
+    /// `HashIndex`, which keeps the raw hashes `fingerprint_to_vector`'s
+    /// histogram conversion discards) and the usual vector `query_similar`
+    /// call, fused with Reciprocal Rank Fusion. `semantic_ratio` trades off
+    /// melodic/spectral similarity (1.0) against exact fingerprint collisions
+    /// (0.0). At `semantic_ratio = 1.0` the order follows
+    /// `search_similar_fingerprints`, but scores are RRF values and hash-only
+    /// candidates are still included with a score of 0.
+    pub async fn hybrid_search_similar_fingerprints(
+        &self,
+        query_fingerprint: &crate::fingerprint::Fingerprint,
+        top_k: u32,
+        semantic_ratio: f32,
+        language_filter: Option<String>,
+        genre_filter: Option<String>,
+    ) -> Result<Vec<VectorSearchResult>> {
+        let vector_results = self
+            .search_similar_fingerprints(query_fingerprint, top_k, language_filter, genre_filter)
+            .await?;
+        let hash_results = self.hash_index.read().await.rank(query_fingerprint, top_k as usize);
+
+        Ok(fuse_with_reciprocal_rank_fusion(vector_results, hash_results, semantic_ratio))
+    }
+
+    /// Convert a fingerprint to its vector representation via the configured
+    /// `Embedder`, erroring if the embedder's output doesn't match this
+    /// index's configured dimensions rather than padding/truncating it.
+    fn fingerprint_to_vector(&self, fingerprint: &crate::fingerprint::Fingerprint) -> Result<Vec<f32>> {
+        let vector = self.embedder.embed(fingerprint)?;
+
+        if vector.len() != self.dimensions as usize {
+            return Err(anyhow::anyhow!(
+                "embedder produced a {}-dimension vector but this index is configured for {} dimensions",
+                vector.len(),
+                self.dimensions
+            ));
+        }
+
         Ok(vector)
     }
 
@@ -385,25 +1328,124 @@ impl VectorDatabase {
         let batch_size = 100; // Pinecone batch limit
         
         for chunk in fingerprints.chunks(batch_size) {
+            {
+                let mut hash_index = self.hash_index.write().await;
+                for (song_id, fingerprint, _) in chunk {
+                    hash_index.insert(format!("fingerprint_{}", song_id), fingerprint);
+                }
+            }
+
             let vectors: Vec<(String, Vec<f32>, HashMap<String, serde_json::Value>)> = chunk
                 .iter()
                 .map(|(song_id, fingerprint, metadata)| {
-                    let vector = self.fingerprint_to_vector(fingerprint).unwrap_or_default();
+                    let vector = self.fingerprint_to_vector(fingerprint)?;
                     let mut full_metadata = metadata.clone();
                     full_metadata.insert("song_id".to_string(), serde_json::Value::String(song_id.to_string()));
                     full_metadata.insert("fingerprint_id".to_string(), serde_json::Value::String(Uuid::new_v4().to_string()));
                     full_metadata.insert("created_at".to_string(), serde_json::Value::String(chrono::Utc::now().to_rfc3339()));
-                    
-                    (format!("fingerprint_{}", song_id), vector, full_metadata)
+
+                    Ok((format!("fingerprint_{}", song_id), vector, full_metadata))
                 })
-                .collect();
-            
+                .collect::<Result<Vec<_>>>()?;
+
             self.upsert_vectors(vectors, None).await?;
         }
         
         Ok(())
     }
 
+    /// Add a track's perceptual feature vector (tempo, spectral shape, loudness,
+    /// chroma) to the database, stored in a dedicated namespace so it doesn't mix
+    /// with hash-based fingerprint vectors of a different dimensionality.
+    pub async fn add_perceptual_features(
+        &self,
+        song_id: Uuid,
+        features: &crate::audio::AudioFeatures,
+        metadata: HashMap<String, serde_json::Value>,
+    ) -> Result<()> {
+        let vector = self.perceptual_features_to_vector(features);
+
+        let mut full_metadata = metadata;
+        full_metadata.insert("song_id".to_string(), serde_json::Value::String(song_id.to_string()));
+        full_metadata.insert("created_at".to_string(), serde_json::Value::String(chrono::Utc::now().to_rfc3339()));
+
+        let vector_id = format!("features_{}", song_id);
+
+        self.upsert_vectors(
+            vec![(vector_id, vector, full_metadata)],
+            Some(PERCEPTUAL_FEATURES_NAMESPACE.to_string()),
+        ).await?;
+
+        Ok(())
+    }
+
+    /// Search for tracks with a similar perceptual feature vector (recommendation/
+    /// clustering), rather than `search_similar_fingerprints`'s exact-recording match
+    pub async fn search_similar_by_features(
+        &self,
+        query_features: &crate::audio::AudioFeatures,
+        top_k: u32,
+        language_filter: Option<String>,
+        genre_filter: Option<String>,
+    ) -> Result<Vec<VectorSearchResult>> {
+        let query_vector = self.perceptual_features_to_vector(query_features);
+
+        let mut filter = HashMap::new();
+        if let Some(lang) = language_filter {
+            filter.insert("language".to_string(), serde_json::Value::String(lang));
+        }
+        if let Some(genre) = genre_filter {
+            filter.insert("genre".to_string(), serde_json::Value::String(genre));
+        }
+
+        let filter_option = if filter.is_empty() { None } else { Some(filter) };
+
+        self.query_similar(
+            query_vector,
+            top_k,
+            Some(PERCEPTUAL_FEATURES_NAMESPACE.to_string()),
+            filter_option,
+        ).await
+    }
+
+    /// Search for tracks with a perceptual feature vector similar to an
+    /// already-indexed song, used to walk the library one sonically-close
+    /// track at a time (e.g. for playlist generation)
+    pub async fn search_similar_by_song_id(
+        &self,
+        song_id: Uuid,
+        top_k: u32,
+    ) -> Result<Vec<VectorSearchResult>> {
+        let vector_id = format!("features_{}", song_id);
+        let vector = match self.fetch_vector(&vector_id, Some(PERCEPTUAL_FEATURES_NAMESPACE.to_string())).await? {
+            Some(vector) => vector,
+            None => return Ok(Vec::new()),
+        };
+
+        self.query_similar(
+            vector,
+            top_k,
+            Some(PERCEPTUAL_FEATURES_NAMESPACE.to_string()),
+            None,
+        ).await
+    }
+
+    /// Convert a perceptual feature descriptor to the database's configured
+    /// vector width, zero-padding or truncating it (unlike
+    /// `fingerprint_to_vector`, which errors on a width mismatch)
+    fn perceptual_features_to_vector(&self, features: &crate::audio::AudioFeatures) -> Vec<f32> {
+        let mut vector = crate::analysis::to_comparable_vector(features).to_vec();
+
+        while vector.len() < self.dimensions as usize {
+            vector.push(0.0);
+        }
+        if vector.len() > self.dimensions as usize {
+            vector.truncate(self.dimensions as usize);
+        }
+
+        vector
+    }
+
     /// Get vector database health status
     pub async fn health_check(&self) -> Result<bool> {
         match self.get_index_stats().await {
@@ -438,6 +1480,7 @@ mod tests {
                 num_bins: 2048,
                 window_size: 4096,
                 overlap: 0.5,
+                key: None,
             },
         }
     }
@@ -458,6 +1501,102 @@ mod tests {
         assert!(vector.iter().all(|&x| x >= 0.0 && x <= 1.0));
     }
 
+    #[test]
+    fn test_perceptual_features_to_vector_matches_configured_dimensions() {
+        let db = VectorDatabase::new(
+            "test-key".to_string(),
+            "test-env".to_string(),
+            "test-index".to_string(),
+            64,
+        );
+
+        let features = crate::audio::AudioFeatures {
+            spectral_centroid: 2000.0,
+            spectral_rolloff: 8000.0,
+            spectral_flatness: 0.3,
+            mfcc: vec![0.1; 13],
+            zero_crossing_rate: 0.2,
+            spectrum: vec![0.0; 100],
+            tempo_bpm: 120.0,
+            chroma: vec![1.0 / 12.0; 12],
+            loudness: -20.0,
+            centroid_mean: 2000.0,
+            centroid_variance: 10.0,
+            rolloff_mean: 8000.0,
+            rolloff_variance: 10.0,
+            zcr_mean: 0.2,
+            zcr_variance: 0.01,
+            flatness_mean: 0.3,
+            flatness_variance: 0.01,
+            frames: Vec::new(),
+        };
+
+        let vector = db.perceptual_features_to_vector(&features);
+        assert_eq!(vector.len(), 64);
+    }
+
+    #[test]
+    fn test_qdrant_point_id_is_deterministic() {
+        assert_eq!(qdrant_point_id("fingerprint_abc"), qdrant_point_id("fingerprint_abc"));
+        assert_ne!(qdrant_point_id("fingerprint_abc"), qdrant_point_id("fingerprint_def"));
+    }
+
+    #[test]
+    fn test_qdrant_filter_builds_must_clause() {
+        let mut filter = HashMap::new();
+        filter.insert("genre".to_string(), serde_json::json!("rock"));
+
+        let built = qdrant_filter(&filter);
+        let must = built["must"].as_array().unwrap();
+        assert_eq!(must.len(), 1);
+        assert_eq!(must[0]["key"], "genre");
+        assert_eq!(must[0]["match"]["value"], "rock");
+    }
+
+    #[test]
+    fn test_new_qdrant_builds_qdrant_backend() {
+        let db = VectorDatabase::new_qdrant("http://localhost:6333".to_string(), "songs".to_string(), None, 32);
+        match &db.backend {
+            VectorBackend::Qdrant(_) => {}
+            _ => panic!("VectorDatabase::new_qdrant should build a Qdrant backend"),
+        }
+    }
+
+    #[test]
+    fn test_set_embedder_rejects_dimension_mismatch() {
+        let mut db = VectorDatabase::new_local(16, None).unwrap();
+        let err = db.set_embedder(Box::new(HistogramEmbedder::new(8))).unwrap_err();
+        assert!(err.to_string().contains("dimensions"));
+    }
+
+    #[tokio::test]
+    async fn test_precomputed_embedder_returns_registered_vector() {
+        let mut embedder = PrecomputedEmbedder::new(4);
+        let fingerprint = create_test_fingerprint();
+        embedder.register(&fingerprint, vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+
+        let mut db = VectorDatabase::new_local(4, None).unwrap();
+        db.set_embedder(Box::new(embedder)).unwrap();
+
+        db.add_fingerprint(Uuid::new_v4(), &fingerprint, HashMap::new()).await.unwrap();
+        let results = db.query_similar(vec![1.0, 2.0, 3.0, 4.0], 1, None, None).await.unwrap();
+        assert_eq!(results.len(), 1);
+    }
+
+    #[test]
+    fn test_precomputed_embedder_errors_on_unregistered_fingerprint() {
+        let embedder = PrecomputedEmbedder::new(4);
+        let fingerprint = create_test_fingerprint();
+        assert!(embedder.embed(&fingerprint).is_err());
+    }
+
+    #[test]
+    fn test_precomputed_embedder_register_rejects_dimension_mismatch() {
+        let mut embedder = PrecomputedEmbedder::new(4);
+        let fingerprint = create_test_fingerprint();
+        assert!(embedder.register(&fingerprint, vec![1.0, 2.0]).is_err());
+    }
+
     #[test]
     fn test_vector_database_creation() {
         let db = VectorDatabase::new(
@@ -468,6 +1607,232 @@ mod tests {
         );
         
         assert_eq!(db.dimensions, 1024);
-        assert_eq!(db.index_name, "test-index");
+        match &db.backend {
+            VectorBackend::Pinecone(backend) => assert_eq!(backend.index_name, "test-index"),
+            VectorBackend::Qdrant(_) | VectorBackend::Local(_) => panic!("VectorDatabase::new should build a Pinecone backend"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_local_backend_upsert_and_query_round_trips() {
+        let db = VectorDatabase::new_local(4, None).unwrap();
+        let mut metadata = HashMap::new();
+        metadata.insert("genre".to_string(), serde_json::json!("rock"));
+
+        db.upsert_vectors(vec![("song-1".to_string(), vec![1.0, 0.0, 0.0, 0.0], metadata)], None)
+            .await
+            .unwrap();
+
+        let results = db.query_similar(vec![1.0, 0.0, 0.0, 0.0], 1, None, None).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "song-1");
+        assert_eq!(results[0].metadata.get("genre").unwrap(), "rock");
+    }
+
+    #[tokio::test]
+    async fn test_local_backend_query_similar_applies_filter() {
+        let db = VectorDatabase::new_local(4, None).unwrap();
+        let mut rock_metadata = HashMap::new();
+        rock_metadata.insert("genre".to_string(), serde_json::json!("rock"));
+        let mut jazz_metadata = HashMap::new();
+        jazz_metadata.insert("genre".to_string(), serde_json::json!("jazz"));
+
+        db.upsert_vectors(
+            vec![
+                ("song-rock".to_string(), vec![1.0, 0.0, 0.0, 0.0], rock_metadata),
+                ("song-jazz".to_string(), vec![1.0, 0.0, 0.0, 0.0], jazz_metadata),
+            ],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let mut filter = HashMap::new();
+        filter.insert("genre".to_string(), serde_json::json!("jazz"));
+
+        let results = db.query_similar(vec![1.0, 0.0, 0.0, 0.0], 10, None, Some(filter)).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "song-jazz");
+    }
+
+    #[tokio::test]
+    async fn test_local_backend_delete_removes_vector_from_results() {
+        let db = VectorDatabase::new_local(4, None).unwrap();
+        db.upsert_vectors(vec![("song-1".to_string(), vec![1.0, 0.0, 0.0, 0.0], HashMap::new())], None)
+            .await
+            .unwrap();
+
+        db.delete_vectors(vec!["song-1".to_string()], None).await.unwrap();
+
+        let results = db.query_similar(vec![1.0, 0.0, 0.0, 0.0], 10, None, None).await.unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_local_backend_get_index_stats_counts_vectors() {
+        let db = VectorDatabase::new_local(4, None).unwrap();
+        db.upsert_vectors(
+            vec![
+                ("song-1".to_string(), vec![1.0, 0.0, 0.0, 0.0], HashMap::new()),
+                ("song-2".to_string(), vec![0.0, 1.0, 0.0, 0.0], HashMap::new()),
+            ],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let stats = db.get_index_stats().await.unwrap();
+        assert_eq!(stats.total_vector_count, 2);
+        assert_eq!(stats.dimension, 4);
+    }
+
+    #[tokio::test]
+    async fn test_local_backend_fetch_vector_returns_stored_values() {
+        let db = VectorDatabase::new_local(4, None).unwrap();
+        db.upsert_vectors(vec![("song-1".to_string(), vec![1.0, 2.0, 3.0, 4.0], HashMap::new())], None)
+            .await
+            .unwrap();
+
+        let fetched = db.fetch_vector("song-1", None).await.unwrap();
+        assert_eq!(fetched, Some(vec![1.0, 2.0, 3.0, 4.0]));
+        assert_eq!(db.fetch_vector("missing", None).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_new_local_persists_and_reloads_from_disk() {
+        let path = std::env::temp_dir().join(format!("local_vector_index_test_{}", Uuid::new_v4()));
+
+        let db = VectorDatabase::new_local(4, Some(path.clone())).unwrap();
+        db.upsert_vectors(vec![("song-1".to_string(), vec![1.0, 0.0, 0.0, 0.0], HashMap::new())], None)
+            .await
+            .unwrap();
+
+        let reloaded = VectorDatabase::new_local(4, Some(path.clone())).unwrap();
+        let results = reloaded.query_similar(vec![1.0, 0.0, 0.0, 0.0], 1, None, None).await.unwrap();
+
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "song-1");
+    }
+
+    #[test]
+    fn test_hash_index_rank_prefers_temporally_consistent_matches() {
+        let mut index = HashIndex::default();
+        // "real" matches a constant +1.0s offset; "decoy" shares one hash but at
+        // a time offset that doesn't line up with any of the others.
+        index.insert("real".to_string(), &Fingerprint {
+            hashes: vec![1, 2, 3, 4],
+            time_offsets: vec![1.0, 1.1, 1.2, 1.3],
+            peaks: Vec::new(),
+            metadata: FingerprintMetadata { sample_rate: 44100, duration: 10.0, num_bins: 2048, window_size: 4096, overlap: 0.5, key: None },
+        });
+        index.insert("decoy".to_string(), &Fingerprint {
+            hashes: vec![1],
+            time_offsets: vec![9.0],
+            peaks: Vec::new(),
+            metadata: FingerprintMetadata { sample_rate: 44100, duration: 10.0, num_bins: 2048, window_size: 4096, overlap: 0.5, key: None },
+        });
+
+        let query = Fingerprint {
+            hashes: vec![1, 2, 3, 4],
+            time_offsets: vec![0.0, 0.1, 0.2, 0.3],
+            peaks: Vec::new(),
+            metadata: FingerprintMetadata { sample_rate: 44100, duration: 10.0, num_bins: 2048, window_size: 4096, overlap: 0.5, key: None },
+        };
+
+        let ranked = index.rank(&query, 10);
+        assert_eq!(ranked[0].0, "real");
+        assert_eq!(ranked[0].1, 4);
+    }
+
+    #[test]
+    fn test_fuse_with_reciprocal_rank_fusion_semantic_ratio_one_is_vector_order() {
+        let vector_results = vec![
+            VectorSearchResult { id: "a".to_string(), score: 0.9, metadata: HashMap::new() },
+            VectorSearchResult { id: "b".to_string(), score: 0.5, metadata: HashMap::new() },
+        ];
+        let hash_results = vec![("b".to_string(), 10), ("a".to_string(), 1)];
+
+        let fused = fuse_with_reciprocal_rank_fusion(vector_results, hash_results, 1.0);
+        assert_eq!(fused[0].id, "a");
+        assert_eq!(fused[1].id, "b");
+    }
+
+    #[test]
+    fn test_fuse_with_reciprocal_rank_fusion_semantic_ratio_zero_is_hash_order() {
+        let vector_results = vec![
+            VectorSearchResult { id: "a".to_string(), score: 0.9, metadata: HashMap::new() },
+            VectorSearchResult { id: "b".to_string(), score: 0.5, metadata: HashMap::new() },
+        ];
+        let hash_results = vec![("b".to_string(), 10), ("a".to_string(), 1)];
+
+        let fused = fuse_with_reciprocal_rank_fusion(vector_results, hash_results, 0.0);
+        assert_eq!(fused[0].id, "b");
+        assert_eq!(fused[1].id, "a");
+    }
+
+    #[test]
+    fn test_fuse_with_reciprocal_rank_fusion_carries_scores_into_metadata() {
+        let vector_results = vec![VectorSearchResult { id: "a".to_string(), score: 0.75, metadata: HashMap::new() }];
+        let hash_results = vec![("a".to_string(), 7)];
+
+        let fused = fuse_with_reciprocal_rank_fusion(vector_results, hash_results, 0.5);
+        assert_eq!(fused[0].metadata.get("vector_score").unwrap(), 0.75);
+        assert_eq!(fused[0].metadata.get("hash_match_count").unwrap(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_search_similar_fingerprints_finds_exact_hash_match_by_hash_alone() {
+        let db = VectorDatabase::new_local(34, None).unwrap();
+        let fingerprint = create_test_fingerprint();
+
+        db.add_fingerprint(Uuid::new_v4(), &fingerprint, HashMap::new()).await.unwrap();
+
+        // semantic_ratio = 0.0 relies entirely on the hash index, since the
+        // local HNSW backend holds no vectors similar to an unrelated query.
+        let unrelated_query = Fingerprint {
+            hashes: fingerprint.hashes.clone(),
+            time_offsets: fingerprint.time_offsets.clone(),
+            peaks: Vec::new(),
+            metadata: fingerprint.metadata.clone(),
+        };
+
+        let results = db.hybrid_search_similar_fingerprints(&unrelated_query, 5, 0.0, None, None).await.unwrap();
+        assert!(!results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_query_similar_batch_returns_one_result_per_query_in_order() {
+        let db = VectorDatabase::new_local(4, None).unwrap();
+        db.upsert_vectors(
+            vec![
+                ("song-a".to_string(), vec![1.0, 0.0, 0.0, 0.0], HashMap::new()),
+                ("song-b".to_string(), vec![0.0, 1.0, 0.0, 0.0], HashMap::new()),
+            ],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let queries = vec![vec![1.0, 0.0, 0.0, 0.0], vec![0.0, 1.0, 0.0, 0.0]];
+        let results = db.query_similar_batch(queries, 1, None, None).await;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap()[0].id, "song-a");
+        assert_eq!(results[1].as_ref().unwrap()[0].id, "song-b");
+    }
+
+    #[tokio::test]
+    async fn test_search_similar_fingerprints_batch_matches_search_similar_fingerprints() {
+        let db = VectorDatabase::new_local(34, None).unwrap();
+        let fingerprint = create_test_fingerprint();
+        db.add_fingerprint(Uuid::new_v4(), &fingerprint, HashMap::new()).await.unwrap();
+
+        let single = db.search_similar_fingerprints(&fingerprint, 5, None, None).await.unwrap();
+        let batch = db.search_similar_fingerprints_batch(vec![&fingerprint], 5, None, None).await;
+
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].as_ref().unwrap().len(), single.len());
     }
 }
@@ -1,6 +1,7 @@
 //! WebAssembly bindings for Sonica audio engine
 
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 use web_sys::console;
 use crate::audio::AudioFeatures;
 use crate::fingerprint::Fingerprint;
@@ -18,6 +19,97 @@ macro_rules! console_log {
     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 }
 
+/// Interleaved PCM sample format for [`WasmAudioProcessor::from_raw_buffer`]
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleFormat {
+    S16LE,
+    S24LE,
+    S32LE,
+    F32LE,
+}
+
+/// Convert an interleaved raw PCM byte buffer into normalized mono `f32` samples,
+/// downmixing channels by averaging
+fn raw_buffer_to_mono_f32(data: &[u8], format: SampleFormat, channels: u32) -> Result<Vec<f32>, JsValue> {
+    let channels = channels.max(1) as usize;
+    let bytes_per_sample = match format {
+        SampleFormat::S16LE => 2,
+        SampleFormat::S24LE => 3,
+        SampleFormat::S32LE | SampleFormat::F32LE => 4,
+    };
+
+    let frame_size = bytes_per_sample * channels;
+    if frame_size == 0 || data.len() % frame_size != 0 {
+        return Err(JsValue::from_str(&format!(
+            "Invalid audio format: buffer length {} is not a multiple of the frame size {}",
+            data.len(),
+            frame_size
+        )));
+    }
+
+    let mut samples = Vec::with_capacity(data.len() / frame_size);
+
+    for frame in data.chunks_exact(frame_size) {
+        let mut sum = 0.0f32;
+
+        for channel in frame.chunks_exact(bytes_per_sample) {
+            let normalized = match format {
+                SampleFormat::S16LE => {
+                    let value = i16::from_le_bytes([channel[0], channel[1]]);
+                    value as f32 / i16::MAX as f32
+                }
+                SampleFormat::S24LE => {
+                    let raw = i32::from_le_bytes([channel[0], channel[1], channel[2], 0]);
+                    // Sign-extend the 24-bit value held in the low 3 bytes
+                    let signed = (raw << 8) >> 8;
+                    signed as f32 / 8_388_607.0 // 2^23 - 1
+                }
+                SampleFormat::S32LE => {
+                    let value = i32::from_le_bytes([channel[0], channel[1], channel[2], channel[3]]);
+                    value as f32 / i32::MAX as f32
+                }
+                SampleFormat::F32LE => f32::from_le_bytes([channel[0], channel[1], channel[2], channel[3]]),
+            };
+
+            sum += normalized;
+        }
+
+        samples.push(sum / channels as f32);
+    }
+
+    Ok(samples)
+}
+
+/// Convert an `AudioEngineError` into a structured `{ code, status, message, details }`
+/// JS object, so the browser client can branch on `error_code()` (e.g.
+/// `AUDIO_TOO_SHORT` vs `INVALID_AUDIO_FORMAT`) instead of parsing a flat string.
+fn error_to_js_value(err: &crate::error::AudioEngineError) -> JsValue {
+    use crate::error::AudioEngineError;
+
+    let details = match err {
+        AudioEngineError::AudioTooShort { duration, minimum } => {
+            serde_json::json!({ "duration": duration, "minimum": minimum })
+        }
+        AudioEngineError::AudioTooLong { duration, maximum } => {
+            serde_json::json!({ "duration": duration, "maximum": maximum })
+        }
+        AudioEngineError::InvalidAudioFormat(reason) => serde_json::json!({ "reason": reason }),
+        AudioEngineError::RecognitionFailed { reason } => serde_json::json!({ "reason": reason }),
+        AudioEngineError::SongNotFound { song_id } => serde_json::json!({ "song_id": song_id }),
+        _ => serde_json::Value::Null,
+    };
+
+    let payload = serde_json::json!({
+        "code": err.error_code(),
+        "status": err.status_code(),
+        "message": err.to_string(),
+        "details": details,
+    });
+
+    serde_wasm_bindgen::to_value(&payload).unwrap_or_else(|_| JsValue::from_str(&err.to_string()))
+}
+
 /// WebAssembly audio processor for client-side processing
 #[wasm_bindgen]
 pub struct WasmAudioProcessor {
@@ -45,73 +137,90 @@ impl WasmAudioProcessor {
     #[wasm_bindgen]
     pub fn process_audio(&self, audio_data: &[f32]) -> Result<JsValue, JsValue> {
         console_log!("Processing audio data with {} samples", audio_data.len());
-        
-        // Validate input
-        if audio_data.is_empty() {
-            return Err(JsValue::from_str("Audio data is empty"));
-        }
-        
+
+        // Validate input, reporting real AudioTooShort details instead of an ad-hoc string
         if audio_data.len() < self.window_size {
-            return Err(JsValue::from_str("Audio data too short for processing"));
+            let duration = audio_data.len() as f32 / self.sample_rate as f32;
+            let minimum = self.window_size as f32 / self.sample_rate as f32;
+            return Err(error_to_js_value(&crate::error::AudioEngineError::AudioTooShort {
+                duration,
+                minimum,
+            }));
         }
-        
+
         // Generate fingerprint
         match Fingerprint::generate(audio_data) {
             Ok(fingerprint) => {
                 console_log!("Generated fingerprint with {} hashes", fingerprint.hashes.len());
-                
+
                 // Convert to JavaScript object
-                let result = serde_wasm_bindgen::to_value(&fingerprint)
-                    .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))?;
-                
+                let result = serde_wasm_bindgen::to_value(&fingerprint).map_err(|e| {
+                    error_to_js_value(&crate::error::AudioEngineError::Internal(format!(
+                        "Serialization error: {}",
+                        e
+                    )))
+                })?;
+
                 Ok(result)
             }
             Err(e) => {
                 console_log!("Error generating fingerprint: {}", e);
-                Err(JsValue::from_str(&format!("Fingerprint generation failed: {}", e)))
+                Err(error_to_js_value(&crate::error::AudioEngineError::AudioProcessing(e)))
             }
         }
     }
-    
+
     /// Extract audio features
     #[wasm_bindgen]
     pub fn extract_features(&self, audio_data: &[f32]) -> Result<JsValue, JsValue> {
         console_log!("Extracting features from {} samples", audio_data.len());
-        
+
         match crate::audio::extract_features(audio_data, self.sample_rate) {
             Ok(features) => {
-                console_log!("Extracted features: spectral_centroid={}, spectral_rolloff={}", 
+                console_log!("Extracted features: spectral_centroid={}, spectral_rolloff={}",
                            features.spectral_centroid, features.spectral_rolloff);
-                
-                serde_wasm_bindgen::to_value(&features)
-                    .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+
+                serde_wasm_bindgen::to_value(&features).map_err(|e| {
+                    error_to_js_value(&crate::error::AudioEngineError::Internal(format!(
+                        "Serialization error: {}",
+                        e
+                    )))
+                })
             }
             Err(e) => {
                 console_log!("Error extracting features: {}", e);
-                Err(JsValue::from_str(&format!("Feature extraction failed: {}", e)))
+                Err(error_to_js_value(&crate::error::AudioEngineError::AudioProcessing(e)))
             }
         }
     }
-    
+
     /// Calculate similarity between two fingerprints
     #[wasm_bindgen]
     pub fn calculate_similarity(&self, fingerprint1: &JsValue, fingerprint2: &JsValue) -> Result<f32, JsValue> {
         console_log!("Calculating similarity between fingerprints");
-        
+
         // Deserialize fingerprints
-        let fp1: Fingerprint = serde_wasm_bindgen::from_value(fingerprint1.clone())
-            .map_err(|e| JsValue::from_str(&format!("Deserialization error: {}", e)))?;
-        
-        let fp2: Fingerprint = serde_wasm_bindgen::from_value(fingerprint2.clone())
-            .map_err(|e| JsValue::from_str(&format!("Deserialization error: {}", e)))?;
-        
+        let fp1: Fingerprint = serde_wasm_bindgen::from_value(fingerprint1.clone()).map_err(|e| {
+            error_to_js_value(&crate::error::AudioEngineError::InvalidAudioFormat(format!(
+                "Failed to deserialize fingerprint: {}",
+                e
+            )))
+        })?;
+
+        let fp2: Fingerprint = serde_wasm_bindgen::from_value(fingerprint2.clone()).map_err(|e| {
+            error_to_js_value(&crate::error::AudioEngineError::InvalidAudioFormat(format!(
+                "Failed to deserialize fingerprint: {}",
+                e
+            )))
+        })?;
+
         // Calculate similarity
         let similarity = calculate_similarity(&fp1, &fp2);
         console_log!("Similarity calculated: {}", similarity);
-        
+
         Ok(similarity)
     }
-    
+
     /// Normalize audio data
     #[wasm_bindgen]
     pub fn normalize_audio(&self, audio_data: &[f32]) -> Vec<f32> {
@@ -134,9 +243,40 @@ impl WasmAudioProcessor {
             "window_size": self.window_size,
             "overlap": self.overlap
         });
-        
+
         serde_wasm_bindgen::to_value(&config).unwrap_or(JsValue::NULL)
     }
+
+    /// Decode raw encoded file bytes (MP3/FLAC/WAV/OGG) into mono PCM resampled to this
+    /// processor's `sample_rate`, so the browser doesn't have to decode audio itself
+    /// before calling `process_audio`/`extract_features`.
+    ///
+    /// `extension_hint` (e.g. `"mp3"`, `"wav"`) speeds up container probing but isn't
+    /// required; pass `None` (or an empty string) to let symphonia sniff the format.
+    #[wasm_bindgen]
+    pub fn from_encoded_bytes(&self, data: &[u8], extension_hint: Option<String>) -> Result<Vec<f32>, JsValue> {
+        console_log!("Decoding {} bytes of encoded audio", data.len());
+
+        let hint = extension_hint.filter(|h| !h.is_empty());
+        let decoded = crate::decode::decode_bytes(data, hint.as_deref())
+            .map_err(|e| JsValue::from_str(&format!("Invalid audio format: {}", e)))?;
+
+        let resampled = decoded
+            .resampled_to(self.sample_rate)
+            .map_err(|e| JsValue::from_str(&format!("Failed to resample decoded audio: {}", e)))?;
+
+        console_log!("Decoded to {} PCM samples at {} Hz", resampled.len(), self.sample_rate);
+        Ok(resampled)
+    }
+
+    /// Interpret a raw interleaved PCM byte buffer (as handed back by Web Audio /
+    /// device capture APIs) as `sample_format`/`channels`, downmix to mono, and
+    /// normalize to `[-1.0, 1.0]` `f32` ready for `process_audio`/`extract_features`
+    #[wasm_bindgen]
+    pub fn from_raw_buffer(&self, data: &[u8], sample_format: SampleFormat, channels: u32) -> Result<Vec<f32>, JsValue> {
+        console_log!("Interpreting {} raw bytes as {:?} with {} channel(s)", data.len(), sample_format, channels);
+        raw_buffer_to_mono_f32(data, sample_format, channels)
+    }
 }
 
 /// WebAssembly audio visualizer for real-time visualization
@@ -144,77 +284,245 @@ impl WasmAudioProcessor {
 pub struct WasmAudioVisualizer {
     fft_size: usize,
     sample_rate: u32,
+    // Reused across calls so twiddle factors aren't recomputed every frame
+    planner: std::cell::RefCell<rustfft::FftPlanner<f32>>,
 }
 
 #[wasm_bindgen]
 impl WasmAudioVisualizer {
     /// Create a new audio visualizer
+    ///
+    /// `fft_size` must be a power of two, since the underlying FFT backend only takes
+    /// the fast radix-2 path for power-of-two sizes.
     #[wasm_bindgen(constructor)]
-    pub fn new(fft_size: usize, sample_rate: u32) -> WasmAudioVisualizer {
-        console_log!("Initializing WasmAudioVisualizer with fft_size: {}, sample_rate: {}", 
+    pub fn new(fft_size: usize, sample_rate: u32) -> Result<WasmAudioVisualizer, JsValue> {
+        console_log!("Initializing WasmAudioVisualizer with fft_size: {}, sample_rate: {}",
                     fft_size, sample_rate);
-        
-        WasmAudioVisualizer {
+
+        if !fft_size.is_power_of_two() {
+            return Err(JsValue::from_str(&format!(
+                "Invalid audio format: fft_size {} must be a power of two",
+                fft_size
+            )));
+        }
+
+        Ok(WasmAudioVisualizer {
             fft_size,
             sample_rate,
-        }
+            planner: std::cell::RefCell::new(rustfft::FftPlanner::new()),
+        })
     }
-    
+
     /// Compute frequency spectrum for visualization
     #[wasm_bindgen]
     pub fn compute_spectrum(&self, audio_data: &[f32]) -> Result<Vec<f32>, JsValue> {
         console_log!("Computing spectrum for {} samples", audio_data.len());
-        
+
         if audio_data.len() < self.fft_size {
             return Err(JsValue::from_str("Audio data too short for FFT"));
         }
-        
+
         // Take the first fft_size samples
         let window_data: Vec<f32> = audio_data[..self.fft_size].to_vec();
-        
+
         // Apply window function
         let windowed = crate::audio::apply_window(&window_data, crate::audio::WindowType::Hamming);
-        
-        // Compute FFT (simplified version for WASM)
-        let spectrum = self.compute_fft_simple(&windowed);
-        
+
+        let spectrum = self.compute_fft_magnitudes(&windowed);
+
         console_log!("Computed spectrum with {} bins", spectrum.len());
         Ok(spectrum)
     }
-    
+
+    /// Compute frequency spectrum in decibels (`20*log10(magnitude)`), which is usually
+    /// what a browser-side visualizer actually wants to draw
+    #[wasm_bindgen]
+    pub fn compute_spectrum_db(&self, audio_data: &[f32]) -> Result<Vec<f32>, JsValue> {
+        let magnitudes = self.compute_spectrum(audio_data)?;
+
+        Ok(magnitudes
+            .iter()
+            .map(|&mag| 20.0 * mag.max(1e-10).log10())
+            .collect())
+    }
+
     /// Get frequency bins for visualization
     #[wasm_bindgen]
     pub fn get_frequency_bins(&self) -> Vec<f32> {
         let mut bins = Vec::new();
         let bin_width = self.sample_rate as f32 / (2.0 * self.fft_size as f32);
-        
+
         for i in 0..self.fft_size / 2 {
             bins.push(i as f32 * bin_width);
         }
-        
+
         bins
     }
-    
-    /// Simple FFT implementation for WebAssembly
-    fn compute_fft_simple(&self, data: &[f32]) -> Vec<f32> {
-        // Simplified FFT for demonstration
-        // In production, you would use a proper FFT library
-        let mut spectrum = vec![0.0; self.fft_size / 2];
-        
-        for i in 0..self.fft_size / 2 {
-            let mut real = 0.0;
-            let mut imag = 0.0;
-            
-            for j in 0..data.len() {
-                let angle = -2.0 * std::f32::consts::PI * i as f32 * j as f32 / data.len() as f32;
-                real += data[j] * angle.cos();
-                imag += data[j] * angle.sin();
+
+    /// Radix-2 Cooley-Tukey FFT via the shared `rustfft` planner, returning the
+    /// magnitude spectrum for the first `fft_size / 2` bins
+    fn compute_fft_magnitudes(&self, data: &[f32]) -> Vec<f32> {
+        use rustfft::num_complex::Complex;
+
+        let mut buffer: Vec<Complex<f32>> = data.iter().map(|&x| Complex::new(x, 0.0)).collect();
+
+        let fft = self.planner.borrow_mut().plan_fft_forward(buffer.len());
+        fft.process(&mut buffer);
+
+        buffer
+            .iter()
+            .take(self.fft_size / 2)
+            .map(|c| (c.re * c.re + c.im * c.im).sqrt())
+            .collect()
+    }
+}
+
+/// Live microphone capture and streaming recognition over a Web Audio backend
+///
+/// Opens an `AudioContext` + `ScriptProcessorNode` against the user's microphone (the
+/// same approach the `cpal` wasm-bindgen Web Audio backend uses), buffers incoming
+/// samples into a ring buffer for cross-callback continuity, and emits a fingerprint
+/// for every completed `window_size` frame without the JS side managing raw buffers.
+#[wasm_bindgen]
+pub struct WasmAudioStream {
+    sample_rate: u32,
+    window_size: usize,
+    overlap: f32,
+    ring_buffer: std::rc::Rc<std::cell::RefCell<std::collections::VecDeque<f32>>>,
+    audio_context: Option<web_sys::AudioContext>,
+    script_node: Option<web_sys::ScriptProcessorNode>,
+    // Kept alive for as long as the stream is running; dropping it would detach the
+    // `onaudioprocess` callback from the JS side.
+    onaudioprocess_closure: Option<Closure<dyn FnMut(web_sys::AudioProcessingEvent)>>,
+}
+
+#[wasm_bindgen]
+impl WasmAudioStream {
+    /// Create a new (not-yet-started) audio stream
+    #[wasm_bindgen(constructor)]
+    pub fn new(sample_rate: u32, window_size: usize, overlap: f32) -> WasmAudioStream {
+        console_log!(
+            "Initializing WasmAudioStream with sample_rate: {}, window_size: {}, overlap: {}",
+            sample_rate, window_size, overlap
+        );
+
+        WasmAudioStream {
+            sample_rate,
+            window_size,
+            overlap,
+            ring_buffer: std::rc::Rc::new(std::cell::RefCell::new(std::collections::VecDeque::new())),
+            audio_context: None,
+            script_node: None,
+            onaudioprocess_closure: None,
+        }
+    }
+
+    /// Start capturing from the microphone, calling `on_fingerprint(fingerprint_json,
+    /// latency_ms)` for every completed window
+    #[wasm_bindgen]
+    pub async fn start(&mut self, on_fingerprint: js_sys::Function) -> Result<(), JsValue> {
+        let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window available"))?;
+        let navigator = window.navigator();
+        let media_devices = navigator.media_devices()?;
+
+        let mut constraints = web_sys::MediaStreamConstraints::new();
+        constraints.audio(&JsValue::from_bool(true));
+
+        let stream_promise = media_devices.get_user_media_with_constraints(&constraints)?;
+        let stream: web_sys::MediaStream =
+            wasm_bindgen_futures::JsFuture::from(stream_promise).await?.into();
+
+        let audio_context = web_sys::AudioContext::new()?;
+        let source = audio_context.create_media_stream_source(&stream)?;
+
+        // 4096 is the largest buffer size ScriptProcessorNode supports; window_size
+        // frames are assembled from the ring buffer regardless of this chunk size.
+        let script_node = audio_context.create_script_processor_with_buffer_size_and_number_of_input_channels_and_number_of_output_channels(
+            4096, 1, 1,
+        )?;
+
+        let ring_buffer = self.ring_buffer.clone();
+        let window_size = self.window_size;
+        let hop_size = ((window_size as f32) * (1.0 - self.overlap)).max(1.0) as usize;
+        let sample_rate = self.sample_rate;
+        let callback = on_fingerprint;
+        let mut monitor = WasmPerformanceMonitor::new();
+
+        let closure = Closure::wrap(Box::new(move |event: web_sys::AudioProcessingEvent| {
+            let input_buffer = match event.input_buffer() {
+                Ok(buf) => buf,
+                Err(_) => return,
+            };
+
+            let mut channel_data = vec![0.0f32; input_buffer.length() as usize];
+            if input_buffer.copy_from_channel(&mut channel_data, 0).is_err() {
+                return;
             }
-            
-            spectrum[i] = (real * real + imag * imag).sqrt();
+
+            let mut buffer = ring_buffer.borrow_mut();
+            buffer.extend(channel_data.iter().copied());
+
+            while buffer.len() >= window_size {
+                monitor.start();
+
+                let frame: Vec<f32> = buffer.iter().take(window_size).copied().collect();
+                for _ in 0..hop_size.min(buffer.len()) {
+                    buffer.pop_front();
+                }
+
+                if let Ok(fingerprint) = Fingerprint::generate_with_config(
+                    &frame,
+                    &crate::config::AudioConfig {
+                        sample_rate,
+                        window_size,
+                        hop_size,
+                        overlap: 0.5,
+                        min_duration: 0.0,
+                        max_duration: f32::MAX,
+                        noise_threshold: 0.01,
+                    },
+                ) {
+                    let latency_ms = monitor.end();
+                    if let Ok(serialized) = serde_wasm_bindgen::to_value(&fingerprint) {
+                        let _ = callback.call2(
+                            &JsValue::NULL,
+                            &serialized,
+                            &JsValue::from_f64(latency_ms),
+                        );
+                    }
+                }
+            }
+        }) as Box<dyn FnMut(web_sys::AudioProcessingEvent)>);
+
+        script_node.set_onaudioprocess(Some(closure.as_ref().unchecked_ref()));
+        source.connect_with_audio_node(&script_node)?;
+        script_node.connect_with_audio_node(&audio_context.destination())?;
+
+        self.audio_context = Some(audio_context);
+        self.script_node = Some(script_node);
+        self.onaudioprocess_closure = Some(closure);
+
+        console_log!("WasmAudioStream started");
+        Ok(())
+    }
+
+    /// Stop capturing and tear down the audio graph
+    #[wasm_bindgen]
+    pub fn stop(&mut self) -> Result<(), JsValue> {
+        if let Some(script_node) = self.script_node.take() {
+            script_node.set_onaudioprocess(None);
+            script_node.disconnect()?;
         }
-        
-        spectrum
+
+        if let Some(audio_context) = self.audio_context.take() {
+            let _ = audio_context.close();
+        }
+
+        self.onaudioprocess_closure = None;
+        self.ring_buffer.borrow_mut().clear();
+
+        console_log!("WasmAudioStream stopped");
+        Ok(())
     }
 }
 
@@ -287,22 +595,86 @@ mod tests {
         // Test with dummy audio data
         let audio_data = vec![0.1; 44100]; // 1 second of audio
         let result = processor.process_audio(&audio_data);
-        
+
         assert!(result.is_ok());
     }
 
+    #[wasm_bindgen_test]
+    fn test_process_audio_too_short_reports_structured_error() {
+        let processor = WasmAudioProcessor::new(44100, 4096, 0.5);
+
+        let audio_data = vec![0.1; 10]; // far shorter than window_size
+        let err = processor.process_audio(&audio_data).unwrap_err();
+
+        let code = js_sys::Reflect::get(&err, &JsValue::from_str("code")).unwrap();
+        assert_eq!(code.as_string().unwrap(), "AUDIO_TOO_SHORT");
+
+        let status = js_sys::Reflect::get(&err, &JsValue::from_str("status")).unwrap();
+        assert_eq!(status.as_f64().unwrap(), 400.0);
+    }
+
     #[wasm_bindgen_test]
     fn test_wasm_audio_visualizer() {
-        let visualizer = WasmAudioVisualizer::new(1024, 44100);
-        
+        let visualizer = WasmAudioVisualizer::new(1024, 44100).unwrap();
+
         // Test with dummy audio data
         let audio_data = vec![0.1; 1024];
         let spectrum = visualizer.compute_spectrum(&audio_data);
-        
+
         assert!(spectrum.is_ok());
         assert_eq!(spectrum.unwrap().len(), 512);
     }
 
+    #[wasm_bindgen_test]
+    fn test_from_raw_buffer_s16le_mono() {
+        let processor = WasmAudioProcessor::new(44100, 4096, 0.5);
+
+        // Two S16LE mono samples: i16::MAX and i16::MIN
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&i16::MAX.to_le_bytes());
+        bytes.extend_from_slice(&i16::MIN.to_le_bytes());
+
+        let samples = processor.from_raw_buffer(&bytes, SampleFormat::S16LE, 1).unwrap();
+        assert_eq!(samples.len(), 2);
+        assert!((samples[0] - 1.0).abs() < 1e-4);
+        assert!(samples[1] < -0.99);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_from_raw_buffer_f32le_stereo_downmix() {
+        let processor = WasmAudioProcessor::new(44100, 4096, 0.5);
+
+        // One stereo frame: left=1.0, right=-1.0 -> averages to 0.0
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&1.0f32.to_le_bytes());
+        bytes.extend_from_slice(&(-1.0f32).to_le_bytes());
+
+        let samples = processor.from_raw_buffer(&bytes, SampleFormat::F32LE, 2).unwrap();
+        assert_eq!(samples.len(), 1);
+        assert!(samples[0].abs() < 1e-6);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_from_raw_buffer_rejects_misaligned_length() {
+        let processor = WasmAudioProcessor::new(44100, 4096, 0.5);
+        let bytes = vec![0u8; 3]; // not a multiple of the S16LE mono frame size (2)
+        assert!(processor.from_raw_buffer(&bytes, SampleFormat::S16LE, 1).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_from_encoded_bytes_rejects_garbage() {
+        let processor = WasmAudioProcessor::new(44100, 4096, 0.5);
+        let garbage = vec![0u8; 64];
+        let result = processor.from_encoded_bytes(&garbage, None);
+        assert!(result.is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_wasm_audio_visualizer_rejects_non_power_of_two() {
+        let visualizer = WasmAudioVisualizer::new(1000, 44100);
+        assert!(visualizer.is_err());
+    }
+
     #[wasm_bindgen_test]
     fn test_performance_monitor() {
         let mut monitor = WasmPerformanceMonitor::new();